@@ -0,0 +1,104 @@
+use super::{excerpt, ms, Notification};
+use crate::monitor::Health;
+use crate::report;
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use std::str::FromStr;
+
+/// A Discord webhook, optionally only for the alerts of one endpoint
+#[derive(Clone, Debug)]
+pub struct DiscordRoute {
+    /// Host of the endpoint whose alerts go here, all endpoints' if unset
+    pub host: Option<String>,
+    pub url: String,
+}
+
+impl FromStr for DiscordRoute {
+    type Err = String;
+
+    /// `URL` for every endpoint, or `HOST=URL` for one
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (host, url) = match s.split_once('=') {
+            // An `=` after the first slash belongs to the webhook URL's query
+            Some((host, url)) if !host.contains('/') => (Some(host.trim().to_string()), url.trim()),
+            _ => (None, s.trim()),
+        };
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!("expected a webhook URL or HOST=URL, got '{}'", s));
+        }
+        Ok(DiscordRoute { host, url: url.to_string() })
+    }
+}
+
+impl DiscordRoute {
+    pub fn accepts(&self, endpoint: &str) -> bool {
+        self.host.as_ref().is_none_or(|host| *host == report::host(endpoint))
+    }
+}
+
+/// Webhook message with one embed colored by the new health
+pub fn render(notification: &Notification) -> Value {
+    let alert = &notification.alert;
+    let color = match alert.to {
+        Health::Up => 0x2ecc71,
+        Health::Degraded => 0xf1c40f,
+        Health::Down => 0xe74c3c,
+    };
+    let host = report::host(&alert.endpoint);
+
+    let mut fields = vec![
+        json!({ "name": "Endpoint", "value": host, "inline": true }),
+        json!({ "name": "Method", "value": format!("`{}`", alert.method), "inline": true }),
+        json!({ "name": "Was", "value": alert.from.label(), "inline": true }),
+        json!({
+            "name": "Latency",
+            "value": format!(
+                "last {} · p50 {} · p95 {}",
+                ms(alert.stats.last_ms),
+                ms(alert.stats.p50_ms),
+                ms(alert.stats.p95_ms)
+            ),
+            "inline": true,
+        }),
+        json!({
+            "name": "Success",
+            "value": format!("{:.1}% of {}", alert.stats.success_rate, alert.stats.total),
+            "inline": true,
+        }),
+    ];
+    if let Some(error) = &alert.error {
+        fields.push(json!({ "name": "Error", "value": format!("```{}```", excerpt(error)) }));
+    }
+
+    let mut embed = json!({
+        "title": format!("{} {} is {}", host, alert.method, alert.to.label()),
+        "description": alert.reason,
+        "color": color,
+        "fields": fields,
+        "timestamp": alert.timestamp.to_rfc3339(),
+    });
+    if notification.suppressed > 0 {
+        embed["footer"] =
+            json!({ "text": format!("{} earlier changes held back by the cooldown", notification.suppressed) });
+    }
+
+    json!({ "username": "Solana RPC Monitor", "embeds": [embed] })
+}
+
+/// Post the embed to a Discord webhook
+pub async fn send(client: &reqwest::Client, route: &DiscordRoute, notification: &Notification) -> Result<()> {
+    let response = client
+        .post(&route.url)
+        .json(&render(notification))
+        .send()
+        .await
+        .context("Failed to reach Discord")?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        bail!("Discord webhook answered {}: {}", status, body.trim());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,237 @@
+use super::{excerpt, ms, Notification};
+use crate::monitor::{Alert, Health, Monitor};
+use crate::report;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How the connection to the SMTP server is secured
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SmtpTls {
+    /// Upgrade a plain connection with STARTTLS (port 587)
+    Starttls,
+    /// TLS from the first byte (port 465)
+    Tls,
+    /// No encryption, e.g. a local relay (port 25)
+    None,
+}
+
+impl SmtpTls {
+    fn default_port(self) -> u16 {
+        match self {
+            SmtpTls::Starttls => 587,
+            SmtpTls::Tls => 465,
+            SmtpTls::None => 25,
+        }
+    }
+}
+
+/// SMTP server and who the mails go to
+#[derive(Clone, Debug)]
+pub struct EmailConfig {
+    pub server: String,
+    pub port: Option<u16>,
+    pub tls: SmtpTls,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+}
+
+/// Escape text for HTML element content and attributes
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Send one mail: an HTML body, and optionally an HTML file attached
+async fn send_mail(config: &EmailConfig, subject: &str, body: String, attachment: Option<(String, String)>) -> Result<()> {
+    let mut builder = Message::builder()
+        .from(config.from.parse::<Mailbox>().with_context(|| format!("Invalid sender {}", config.from))?)
+        .subject(subject);
+    for to in &config.to {
+        builder = builder.to(to.parse::<Mailbox>().with_context(|| format!("Invalid recipient {}", to))?);
+    }
+    let body = SinglePart::html(body);
+    let message = match attachment {
+        Some((filename, html)) => builder.multipart(
+            MultiPart::mixed().singlepart(body).singlepart(Attachment::new(filename).body(html, ContentType::TEXT_HTML)),
+        )?,
+        None => builder.singlepart(body)?,
+    };
+
+    let transport = match config.tls {
+        SmtpTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.server)?,
+        SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.server)?,
+        SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.server),
+    };
+    let mut transport = transport.port(config.port.unwrap_or(config.tls.default_port()));
+    if let (Some(user), Some(password)) = (&config.user, &config.password) {
+        transport = transport.credentials(Credentials::new(user.clone(), password.clone()));
+    }
+    transport
+        .build()
+        .send(message)
+        .await
+        .with_context(|| format!("Failed to send mail through {}", config.server))?;
+    Ok(())
+}
+
+/// Immediate mail about a method that went down
+pub async fn send(config: &EmailConfig, notification: &Notification) -> Result<()> {
+    let alert = &notification.alert;
+    let host = report::host(&alert.endpoint);
+    let subject = format!("[{}] {} {} on {}", alert.to.label(), alert.method, alert.to.label().to_lowercase(), host);
+
+    let mut body = format!(
+        "<p><b>{}</b> {} is <b>{}</b> since {} UTC: {}</p>\n<ul>\n<li>Endpoint: {}</li>\n\
+         <li>Latency: last {}, p50 {}, p95 {}</li>\n<li>Success: {:.1}% of {} probes</li>\n</ul>\n",
+        escape(&host),
+        escape(&alert.method),
+        alert.to.label(),
+        alert.timestamp.format("%Y-%m-%d %H:%M:%S"),
+        escape(&alert.reason),
+        escape(&alert.endpoint),
+        ms(alert.stats.last_ms),
+        ms(alert.stats.p50_ms),
+        ms(alert.stats.p95_ms),
+        alert.stats.success_rate,
+        alert.stats.total
+    );
+    if let Some(error) = &alert.error {
+        body.push_str(&format!("<pre>{}</pre>\n", escape(&excerpt(error))));
+    }
+    if notification.suppressed > 0 {
+        body.push_str(&format!("<p>{} earlier changes were held back by the cooldown.</p>\n", notification.suppressed));
+    }
+    send_mail(config, &subject, body, None).await
+}
+
+/// A digest ready to be mailed
+pub struct DigestMail {
+    pub subject: String,
+    pub body: String,
+    pub filename: String,
+    pub report: String,
+}
+
+/// Collects the alerts of a period and mails a summary with an HTML report when it ends
+pub struct Digest {
+    pub config: Arc<EmailConfig>,
+    every: Duration,
+    next: Instant,
+    started: DateTime<Utc>,
+    alerts: Vec<Alert>,
+}
+
+impl Digest {
+    pub fn new(config: Arc<EmailConfig>, every: Duration) -> Self {
+        Digest { config, every, next: Instant::now() + every, started: Utc::now(), alerts: Vec::new() }
+    }
+
+    pub fn record(&mut self, alerts: &[Alert]) {
+        self.alerts.extend_from_slice(alerts);
+    }
+
+    /// The digest if the period is over, starting the next one
+    pub fn due(&mut self, monitor: &Monitor) -> Option<DigestMail> {
+        if Instant::now() < self.next {
+            return None;
+        }
+        let now = Utc::now();
+        let report = render_report(monitor, &self.alerts, self.started, now);
+        let unhealthy = monitor.series.iter().filter(|s| s.health != Health::Up).count();
+        let subject = format!(
+            "RPC monitor digest {}: {} changes, {} of {} methods unhealthy",
+            now.format("%Y-%m-%d"),
+            self.alerts.len(),
+            unhealthy,
+            monitor.series.len()
+        );
+        let body = format!(
+            "<p>{} health changes between {} and {} UTC. {} of {} methods are unhealthy now.</p>\n\
+             <p>The full report is attached.</p>\n",
+            self.alerts.len(),
+            self.started.format("%Y-%m-%d %H:%M"),
+            now.format("%Y-%m-%d %H:%M"),
+            unhealthy,
+            monitor.series.len()
+        );
+        let filename = format!("rpc-monitor-{}.html", now.format("%Y-%m-%d"));
+
+        self.next += self.every;
+        self.started = now;
+        self.alerts.clear();
+        Some(DigestMail { subject, body, filename, report })
+    }
+}
+
+/// Mail a digest with its report attached
+pub async fn send_digest(config: &EmailConfig, digest: DigestMail) -> Result<()> {
+    send_mail(config, &digest.subject, digest.body, Some((digest.filename, digest.report))).await
+}
+
+/// Standalone HTML page with every method's health and the period's changes
+fn render_report(monitor: &Monitor, alerts: &[Alert], from: DateTime<Utc>, to: DateTime<Utc>) -> String {
+    let mut out = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>RPC monitor digest</title>\n<style>\n\
+         body { font-family: sans-serif; } table { border-collapse: collapse; }\n\
+         th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: right; } th:first-child, td:first-child { text-align: left; }\n\
+         .up { color: #2e7d32; } .degraded { color: #b26a00; } .down { color: #c62828; }\n</style>\n</head>\n<body>\n",
+    );
+    out.push_str(&format!(
+        "<h1>RPC monitor digest</h1>\n<p>{} to {} UTC</p>\n",
+        from.format("%Y-%m-%d %H:%M"),
+        to.format("%Y-%m-%d %H:%M")
+    ));
+
+    out.push_str(
+        "<h2>Methods</h2>\n<table>\n<tr><th>Endpoint</th><th>Method</th><th>Health</th><th>Probes</th>\
+         <th>Availability</th><th>Window success</th><th>p50</th><th>p95</th></tr>\n",
+    );
+    for series in &monitor.series {
+        let stats = series.stats();
+        let probes = series.totals.succeeded + series.totals.failed;
+        out.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td class=\"{}\">{}</td><td>{}</td><td>{:.2}%</td><td>{:.1}%</td><td>{}</td><td>{}</td></tr>\n",
+            escape(&report::host(&series.endpoint)),
+            escape(&series.method),
+            series.health.label().to_lowercase(),
+            series.health.label(),
+            probes,
+            series.totals.succeeded as f64 / probes.max(1) as f64 * 100.0,
+            stats.success_rate,
+            ms(stats.p50_ms),
+            ms(stats.p95_ms)
+        ));
+    }
+    out.push_str("</table>\n");
+
+    out.push_str("<h2>Changes</h2>\n");
+    if alerts.is_empty() {
+        out.push_str("<p>No health changes.</p>\n");
+    } else {
+        out.push_str("<table>\n<tr><th>Time (UTC)</th><th>Endpoint</th><th>Method</th><th>Health</th><th>Reason</th></tr>\n");
+        for alert in alerts {
+            out.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"{}\">{}</td><td>{}</td></tr>\n",
+                alert.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                escape(&report::host(&alert.endpoint)),
+                escape(&alert.method),
+                alert.to.label().to_lowercase(),
+                alert.to.label(),
+                escape(&alert.reason)
+            ));
+        }
+        out.push_str("</table>\n");
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
@@ -0,0 +1,200 @@
+//! Destinations monitor mode sends health changes to, throttled so flapping methods don't spam
+
+pub mod discord;
+#[cfg(feature = "email")]
+pub mod email;
+pub mod pagerduty;
+pub mod slack;
+pub mod webhook;
+
+#[cfg(feature = "email")]
+use crate::monitor::Monitor;
+use crate::monitor::{Alert, Health};
+use anyhow::Result;
+use futures::FutureExt;
+use serde::Serialize;
+use std::collections::HashMap;
+#[cfg(feature = "email")]
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+
+/// Longest a delivery may take, so a dead receiver never piles up requests
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest error text put in a chat message, the full one is in the webhook payload
+const ERROR_EXCERPT: usize = 300;
+
+/// The error cut at a character boundary after `ERROR_EXCERPT` characters
+fn excerpt(error: &str) -> String {
+    match error.char_indices().nth(ERROR_EXCERPT) {
+        Some((cut, _)) => format!("{}…", &error[..cut]),
+        None => error.to_string(),
+    }
+}
+
+fn ms(value: Option<u128>) -> String {
+    value.map(|ms| format!("{}ms", ms)).unwrap_or_else(|| "-".to_string())
+}
+
+/// A receiver of alerts
+#[derive(Clone, Debug)]
+pub enum Destination {
+    Webhook(String),
+    /// Slack incoming webhook URL
+    Slack(String),
+    Discord(discord::DiscordRoute),
+    /// Routing key of a PagerDuty Events API v2 integration
+    PagerDuty(String),
+    /// Mail, only about methods that went down
+    #[cfg(feature = "email")]
+    Email(Arc<email::EmailConfig>),
+}
+
+impl Destination {
+    /// Whether this alert goes here
+    fn accepts(&self, alert: &Alert) -> bool {
+        match self {
+            Destination::Discord(route) => route.accepts(&alert.endpoint),
+            #[cfg(feature = "email")]
+            Destination::Email(_) => alert.to == Health::Down,
+            _ => true,
+        }
+    }
+
+    async fn send(&self, client: &reqwest::Client, notification: &Notification) -> Result<()> {
+        match self {
+            Destination::Webhook(url) => webhook::send(client, url, notification).await,
+            Destination::Slack(url) => slack::send(client, url, notification).await,
+            Destination::Discord(route) => discord::send(client, route, notification).await,
+            Destination::PagerDuty(routing_key) => pagerduty::send(client, routing_key, notification).await,
+            #[cfg(feature = "email")]
+            Destination::Email(config) => email::send(config, notification).await,
+        }
+    }
+}
+
+/// An alert as delivered
+#[derive(Serialize, Clone, Debug)]
+pub struct Notification {
+    #[serde(flatten)]
+    pub alert: Alert,
+    /// Changes of this method held back by the cooldown since its last notification
+    pub suppressed: usize,
+}
+
+/// Delivery state of one method on one endpoint
+struct Throttle {
+    /// Health the destinations were last told about
+    notified: Health,
+    sent_at: Option<Instant>,
+    /// Latest change not delivered yet
+    pending: Option<Alert>,
+    suppressed: usize,
+}
+
+impl Default for Throttle {
+    fn default() -> Self {
+        // Methods start out up, so a first change to up is nothing to report
+        Throttle { notified: Health::Up, sent_at: None, pending: None, suppressed: 0 }
+    }
+}
+
+/// Sends alerts to every destination, at most one per method and cooldown. A change back to
+/// the health last notified is dropped, and the latest held-back change goes out once the
+/// cooldown ends, so receivers always learn the state a flapping method settled in
+pub struct Dispatcher {
+    destinations: Vec<Destination>,
+    cooldown: Duration,
+    client: reqwest::Client,
+    throttles: HashMap<(String, String), Throttle>,
+    deliveries: JoinSet<()>,
+    #[cfg(feature = "email")]
+    digest: Option<email::Digest>,
+}
+
+impl Dispatcher {
+    pub fn new(destinations: Vec<Destination>, cooldown: Duration) -> Self {
+        Dispatcher {
+            destinations,
+            cooldown,
+            client: reqwest::Client::builder().timeout(SEND_TIMEOUT).build().unwrap_or_default(),
+            throttles: HashMap::new(),
+            deliveries: JoinSet::new(),
+            #[cfg(feature = "email")]
+            digest: None,
+        }
+    }
+
+    /// Also mail the methods that go down, and every `digest_interval` a digest of every change
+    #[cfg(feature = "email")]
+    pub fn with_email(mut self, config: Arc<email::EmailConfig>, digest_interval: Duration) -> Self {
+        self.destinations.push(Destination::Email(config.clone()));
+        self.digest = Some(email::Digest::new(config, digest_interval));
+        self
+    }
+
+    /// Queue a round's alerts and deliver those that are due, in the background
+    pub fn dispatch(&mut self, alerts: Vec<Alert>) {
+        // The digest lists every change, the cooldown only spares the chat channels
+        #[cfg(feature = "email")]
+        if let Some(digest) = &mut self.digest {
+            digest.record(&alerts);
+        }
+        for alert in alerts {
+            let throttle = self.throttles.entry((alert.endpoint.clone(), alert.method.clone())).or_default();
+            if throttle.pending.replace(alert).is_some() {
+                throttle.suppressed += 1;
+            }
+        }
+
+        let now = Instant::now();
+        let mut due = Vec::new();
+        for throttle in self.throttles.values_mut() {
+            if throttle.sent_at.is_some_and(|sent| now.duration_since(sent) < self.cooldown) {
+                continue;
+            }
+            let Some(alert) = throttle.pending.take() else { continue };
+            if alert.to == throttle.notified {
+                throttle.suppressed += 1;
+                continue;
+            }
+            throttle.notified = alert.to;
+            throttle.sent_at = Some(now);
+            due.push(Notification { alert, suppressed: std::mem::take(&mut throttle.suppressed) });
+        }
+
+        for notification in due {
+            for destination in self.destinations.iter().filter(|d| d.accepts(&notification.alert)) {
+                let (client, destination, notification) = (self.client.clone(), destination.clone(), notification.clone());
+                self.deliveries.spawn(async move {
+                    // The watchdog keeps probing whether or not anyone could be told
+                    if let Err(e) = destination.send(&client, &notification).await {
+                        eprintln!("Alert not delivered: {:#}", e);
+                    }
+                });
+            }
+        }
+        // Forget deliveries that are done
+        while let Some(Some(_)) = self.deliveries.join_next().now_or_never() {}
+    }
+
+    /// Mail the digest if its period is over
+    #[cfg(feature = "email")]
+    pub fn digest(&mut self, monitor: &Monitor) {
+        let Some(digest) = &mut self.digest else { return };
+        let Some(mail) = digest.due(monitor) else { return };
+        let config = digest.config.clone();
+        self.deliveries.spawn(async move {
+            if let Err(e) = email::send_digest(&config, mail).await {
+                eprintln!("Digest not delivered: {:#}", e);
+            }
+        });
+    }
+
+    /// Wait for deliveries still in flight, before the process exits
+    pub async fn flush(&mut self) {
+        while self.deliveries.join_next().await.is_some() {}
+    }
+}
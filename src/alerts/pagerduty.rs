@@ -0,0 +1,66 @@
+use super::{excerpt, Notification};
+use crate::monitor::Health;
+use crate::report;
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+
+/// Events API v2 endpoint
+const EVENTS_URL: &str = "https://events.pagerduty.com/v2/enqueue";
+
+/// One incident per endpoint and method, so a recovery resolves what its failure opened
+pub fn dedup_key(endpoint: &str, method: &str) -> String {
+    format!("solana-rpc-checker/{}/{}", endpoint, method)
+}
+
+/// Trigger event for down and degraded methods, resolve event once they are up again
+pub fn render(routing_key: &str, notification: &Notification) -> Value {
+    let alert = &notification.alert;
+    let dedup_key = dedup_key(&alert.endpoint, &alert.method);
+    if alert.to == Health::Up {
+        return json!({ "routing_key": routing_key, "event_action": "resolve", "dedup_key": dedup_key });
+    }
+
+    let severity = if alert.to == Health::Down { "critical" } else { "warning" };
+    let mut details = json!({
+        "reason": alert.reason,
+        "trigger": alert.trigger,
+        "stats": alert.stats,
+        "suppressed_changes": notification.suppressed,
+    });
+    if let Some(error) = &alert.error {
+        details["error"] = json!(excerpt(error));
+    }
+    json!({
+        "routing_key": routing_key,
+        "event_action": "trigger",
+        "dedup_key": dedup_key,
+        "payload": {
+            "summary": format!("{} {} is {}: {}", report::host(&alert.endpoint), alert.method, alert.to.label(), alert.reason),
+            "source": alert.endpoint,
+            "severity": severity,
+            "timestamp": alert.timestamp.to_rfc3339(),
+            "component": alert.method,
+            "group": report::host(&alert.endpoint),
+            "class": alert.trigger,
+            "custom_details": details,
+        },
+    })
+}
+
+/// Enqueue the event with the integration's routing key
+pub async fn send(client: &reqwest::Client, routing_key: &str, notification: &Notification) -> Result<()> {
+    let response = client
+        .post(EVENTS_URL)
+        .json(&render(routing_key, notification))
+        .send()
+        .await
+        .context("Failed to reach PagerDuty")?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        bail!("PagerDuty rejected the event with {}: {}", status, body.trim());
+    }
+
+    Ok(())
+}
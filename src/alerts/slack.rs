@@ -0,0 +1,73 @@
+use super::{excerpt, ms, Notification};
+use crate::monitor::Health;
+use crate::report;
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+
+/// Block Kit message for an incoming webhook, with a plain `text` fallback for notifications
+pub fn render(notification: &Notification) -> Value {
+    let alert = &notification.alert;
+    let emoji = match alert.to {
+        Health::Up => ":large_green_circle:",
+        Health::Degraded => ":large_yellow_circle:",
+        Health::Down => ":red_circle:",
+    };
+    let host = report::host(&alert.endpoint);
+    let title = format!("{} {} {} is {}", emoji, host, alert.method, alert.to.label());
+
+    let mut blocks = vec![
+        json!({ "type": "section", "text": { "type": "mrkdwn", "text": format!("*{}*\n{}", title, alert.reason) } }),
+        json!({
+            "type": "section",
+            "fields": [
+                { "type": "mrkdwn", "text": format!("*Endpoint*\n<{}|{}>", alert.endpoint, host) },
+                { "type": "mrkdwn", "text": format!("*Method*\n`{}`", alert.method) },
+                {
+                    "type": "mrkdwn",
+                    "text": format!(
+                        "*Latency*\nlast {} · p50 {} · p95 {}",
+                        ms(alert.stats.last_ms),
+                        ms(alert.stats.p50_ms),
+                        ms(alert.stats.p95_ms)
+                    ),
+                },
+                {
+                    "type": "mrkdwn",
+                    "text": format!("*Success*\n{:.1}% of {}", alert.stats.success_rate, alert.stats.total),
+                },
+            ],
+        }),
+    ];
+    if let Some(error) = &alert.error {
+        blocks.push(json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": format!("*Error*\n```{}```", excerpt(error)) },
+        }));
+    }
+
+    let mut context = format!("{} UTC", alert.timestamp.format("%Y-%m-%d %H:%M:%S"));
+    if notification.suppressed > 0 {
+        context.push_str(&format!(" · {} earlier changes held back by the cooldown", notification.suppressed));
+    }
+    blocks.push(json!({ "type": "context", "elements": [{ "type": "mrkdwn", "text": context }] }));
+
+    json!({ "text": title, "blocks": blocks })
+}
+
+/// Post the message to a Slack incoming webhook
+pub async fn send(client: &reqwest::Client, url: &str, notification: &Notification) -> Result<()> {
+    let response = client
+        .post(url)
+        .json(&render(notification))
+        .send()
+        .await
+        .context("Failed to reach Slack")?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        bail!("Slack webhook answered {}: {}", status, body.trim());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use super::Notification;
+use anyhow::{bail, Context, Result};
+
+/// POST the notification as JSON, for any receiver that takes arbitrary payloads
+pub async fn send(client: &reqwest::Client, url: &str, notification: &Notification) -> Result<()> {
+    let response = client
+        .post(url)
+        .json(notification)
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach webhook {}", url))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        bail!("Webhook {} answered {}: {}", url, status, body.trim());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use crate::endpoint::Endpoint;
+use crate::rate_limit::RateLimitTracker;
+use crate::transport::Transport;
+use futures::future::join_all;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::cmp::Reverse;
+use std::time::Instant;
+
+/// One node behind the endpoint, told apart by its identity
+#[derive(Serialize, Clone, Debug)]
+pub struct Backend {
+    pub identity: String,
+    pub responses: usize,
+    pub min_slot: u64,
+    pub max_slot: u64,
+}
+
+/// Which backends answered a burst of requests and how far apart they were
+#[derive(Serialize, Clone, Debug)]
+pub struct BackendSpread {
+    pub requests: usize,
+    pub answered: usize,
+    /// Most responses first
+    pub backends: Vec<Backend>,
+    /// Highest slot of the most advanced backend minus that of the least advanced one
+    pub slot_skew: u64,
+    /// Responses, in arrival order, with a lower slot than the one before
+    pub slot_regressions: usize,
+}
+
+/// getIdentity and getSlot in one batch, so the same backend answers both
+fn fingerprint_request(id: u64) -> Value {
+    json!([
+        { "jsonrpc": "2.0", "id": id * 2, "method": "getIdentity" },
+        { "jsonrpc": "2.0", "id": id * 2 + 1, "method": "getSlot", "params": [{ "commitment": "processed" }] },
+    ])
+}
+
+/// Identity and slot out of a batch response, whatever order the entries come in
+fn parse_fingerprint(body: &str) -> Option<(String, u64)> {
+    let responses: Vec<Value> = serde_json::from_str(body).ok()?;
+    let identity = responses.iter().find_map(|r| r["result"]["identity"].as_str())?.to_string();
+    let slot = responses.iter().find_map(|r| r["result"].as_u64())?;
+    Some((identity, slot))
+}
+
+/// Fire `requests` fingerprint batches at once and group the answers by backend identity
+pub async fn detect(endpoint: &Endpoint, requests: usize) -> BackendSpread {
+    let transport = endpoint.transport(None, RateLimitTracker::default());
+
+    let start = Instant::now();
+    let mut answers: Vec<_> = join_all((0..requests as u64).map(|id| {
+        let transport = &transport;
+        async move {
+            let response = transport.post_json(&fingerprint_request(id)).await.ok()?;
+            let fingerprint = parse_fingerprint(&response.body)?;
+            Some((start.elapsed(), fingerprint))
+        }
+    }))
+    .await
+    .into_iter()
+    .flatten()
+    .collect();
+    answers.sort_by_key(|(arrived, _)| *arrived);
+
+    let slot_regressions = answers.windows(2).filter(|pair| pair[1].1 .1 < pair[0].1 .1).count();
+
+    let mut backends: Vec<Backend> = Vec::new();
+    for (_, (identity, slot)) in &answers {
+        match backends.iter_mut().find(|b| b.identity == *identity) {
+            Some(backend) => {
+                backend.responses += 1;
+                backend.min_slot = backend.min_slot.min(*slot);
+                backend.max_slot = backend.max_slot.max(*slot);
+            }
+            None => backends.push(Backend { identity: identity.clone(), responses: 1, min_slot: *slot, max_slot: *slot }),
+        }
+    }
+    backends.sort_by_key(|b| Reverse(b.responses));
+
+    let tips = backends.iter().map(|b| b.max_slot);
+    let slot_skew = tips.clone().max().unwrap_or_default() - tips.min().unwrap_or_default();
+
+    BackendSpread { requests, answered: answers.len(), backends, slot_skew, slot_regressions }
+}
+
+/// Whether one connection keeps hitting the same backend while fresh connections don't
+#[derive(Serialize, Clone, Debug)]
+pub struct Stickiness {
+    pub requests: usize,
+    /// Distinct identities seen over one reused connection
+    pub pooled_backends: usize,
+    /// Distinct identities seen with a new connection per request
+    pub fresh_backends: usize,
+    /// Responses over the reused connection with a lower slot than the one before
+    pub pooled_regressions: usize,
+}
+
+impl Stickiness {
+    /// What a client keeping its connection open can expect
+    pub fn verdict(&self) -> &'static str {
+        match (self.pooled_backends, self.fresh_backends) {
+            (0, _) | (_, 0) => "unknown, fingerprint requests failed",
+            (1, 1) => "single backend (or identities hidden)",
+            (1, _) => "sticky per connection, fresh connections land on other backends",
+            _ => "not sticky, one connection is spread over several backends",
+        }
+    }
+
+    /// A reused connection sees one backend, so state read over it is consistent
+    pub fn consistent(&self) -> bool {
+        self.pooled_backends == 1 && self.pooled_regressions == 0
+    }
+}
+
+/// Identities and slots of `requests` sequential fingerprints over the given transport(s)
+async fn fingerprints<'a>(transports: impl Iterator<Item = &'a Transport>) -> Vec<(String, u64)> {
+    let mut seen = Vec::new();
+    for (id, transport) in transports.enumerate() {
+        if let Ok(response) = transport.post_json(&fingerprint_request(id as u64)).await {
+            seen.extend(parse_fingerprint(&response.body));
+        }
+    }
+    seen
+}
+
+fn distinct(seen: &[(String, u64)]) -> usize {
+    let mut identities: Vec<&str> = seen.iter().map(|(identity, _)| identity.as_str()).collect();
+    identities.sort_unstable();
+    identities.dedup();
+    identities.len()
+}
+
+/// Fingerprint `requests` times over one kept-alive connection, then as often with a new connection each
+pub async fn stickiness(endpoint: &Endpoint, requests: usize) -> Stickiness {
+    let pooled = endpoint.transport(None, RateLimitTracker::default());
+    let pooled_seen = fingerprints(std::iter::repeat_n(&pooled, requests)).await;
+
+    // Every transport has its own client, and so its own connection
+    let fresh: Vec<Transport> = (0..requests).map(|_| endpoint.transport(None, RateLimitTracker::default())).collect();
+    let fresh_seen = fingerprints(fresh.iter()).await;
+
+    Stickiness {
+        requests,
+        pooled_backends: distinct(&pooled_seen),
+        fresh_backends: distinct(&fresh_seen),
+        pooled_regressions: pooled_seen.windows(2).filter(|pair| pair[1].1 < pair[0].1).count(),
+    }
+}
@@ -0,0 +1,153 @@
+use crate::endpoint::Endpoint;
+use crate::rate_limit::RateLimitTracker;
+use crate::report::histogram::LatencyHistogram;
+use crate::transport::Transport;
+use anyhow::{anyhow, bail, Result};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::time::Instant;
+
+/// getMultipleAccounts batch sizes every endpoint is measured at
+const BATCH_SIZES: [usize; 4] = [1, 10, 50, 100];
+
+/// Largest batch tried when looking for the endpoint's cap
+const CAP_CEILING: usize = 1000;
+
+/// Latency of getMultipleAccounts at one batch size
+#[derive(Serialize, Clone, Debug)]
+pub struct BatchLevel {
+    pub size: usize,
+    pub requests: usize,
+    pub succeeded: usize,
+    pub p50_ms: Option<u128>,
+    pub p95_ms: Option<u128>,
+    /// Median time per account, to tell fixed overhead from per-account cost
+    pub per_account_ms: Option<f64>,
+}
+
+/// How getMultipleAccounts latency grows with the batch size, and where the endpoint caps it
+#[derive(Serialize, Clone, Debug)]
+pub struct BatchScaling {
+    pub levels: Vec<BatchLevel>,
+    /// Largest batch answered
+    pub max_accepted: Option<usize>,
+    /// Smallest batch refused, if any was within the searched range
+    pub rejected_at: Option<usize>,
+    /// What the endpoint said when refusing it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rejection: Option<String>,
+}
+
+/// Real accounts to look up, the vote accounts of the cluster repeated up to `count`
+async fn accounts(transport: &Transport, count: usize) -> Result<Vec<String>> {
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "getVoteAccounts" });
+    let response = transport.post_json(&request).await?;
+    let json: Value = serde_json::from_str(&response.body)?;
+    let result = &json["result"];
+    let keys: Vec<String> = ["current", "delinquent"]
+        .iter()
+        .flat_map(|group| result[group].as_array().into_iter().flatten())
+        .filter_map(|account| account["votePubkey"].as_str().map(String::from))
+        .collect();
+    if keys.is_empty() {
+        bail!("getVoteAccounts returned no accounts to batch");
+    }
+    Ok(keys.iter().cycle().take(count).cloned().collect())
+}
+
+/// One getMultipleAccounts call, the elapsed time if every account came back
+async fn fetch(transport: &Transport, keys: &[String]) -> Result<u128> {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "getMultipleAccounts",
+        "params": [keys, { "encoding": "base64", "dataSlice": { "offset": 0, "length": 0 } }],
+    });
+    let start = Instant::now();
+    let response = transport.post_json(&request).await?;
+    let elapsed = start.elapsed().as_millis();
+    if !response.status.is_success() {
+        bail!("HTTP {}", response.status);
+    }
+    let json: Value = serde_json::from_str(&response.body)?;
+    if let Some(error) = json.get("error") {
+        return Err(anyhow!(error["message"].as_str().unwrap_or("RPC error").to_string()));
+    }
+    match json["result"]["value"].as_array() {
+        Some(values) if values.len() == keys.len() => Ok(elapsed),
+        Some(values) => bail!("{} of {} accounts returned", values.len(), keys.len()),
+        None => bail!("response without result"),
+    }
+}
+
+/// Time getMultipleAccounts `iterations` times at each batch size, then search for the
+/// largest batch the endpoint accepts: doubling past the last size up to a ceiling, then
+/// bisecting between the largest accepted and smallest refused batch
+pub async fn scaling(endpoint: &Endpoint, iterations: usize) -> Result<BatchScaling> {
+    let transport = endpoint.transport(None, RateLimitTracker::default());
+    let keys = accounts(&transport, CAP_CEILING).await?;
+
+    let mut accepted = 0;
+    let mut refused: Option<(usize, String)> = None;
+    let mut levels = Vec::new();
+    for size in BATCH_SIZES {
+        let mut histogram = LatencyHistogram::new();
+        let mut requests = 0;
+        for _ in 0..iterations.max(1) {
+            requests += 1;
+            match fetch(&transport, &keys[..size]).await {
+                Ok(ms) => histogram.record(ms),
+                Err(e) => {
+                    refused.get_or_insert((size, e.to_string()));
+                }
+            }
+        }
+        if !histogram.is_empty() {
+            accepted = accepted.max(size);
+        }
+        let p50_ms = histogram.percentile(50.0);
+        levels.push(BatchLevel {
+            size,
+            requests,
+            succeeded: histogram.len() as usize,
+            p50_ms,
+            p95_ms: histogram.percentile(95.0),
+            per_account_ms: p50_ms.map(|ms| ms as f64 / size as f64),
+        });
+    }
+
+    // A size that failed every time is treated as refused, occasional failures are noise
+    refused = refused.filter(|(size, _)| *size > accepted);
+
+    if refused.is_none() {
+        let mut size = accepted;
+        while size < CAP_CEILING {
+            size = (size * 2).min(CAP_CEILING);
+            match fetch(&transport, &keys[..size]).await {
+                Ok(_) => accepted = size,
+                Err(e) => {
+                    refused = Some((size, e.to_string()));
+                    break;
+                }
+            }
+        }
+    }
+
+    while let Some((refused_size, _)) = &refused {
+        if refused_size - accepted <= 1 {
+            break;
+        }
+        let size = (accepted + refused_size) / 2;
+        match fetch(&transport, &keys[..size]).await {
+            Ok(_) => accepted = size,
+            Err(e) => refused = Some((size, e.to_string())),
+        }
+    }
+
+    Ok(BatchScaling {
+        levels,
+        max_accepted: (accepted > 0).then_some(accepted),
+        rejected_at: refused.as_ref().map(|(size, _)| *size),
+        rejection: refused.map(|(_, reason)| reason),
+    })
+}
@@ -0,0 +1,110 @@
+use crate::har::HarRecorder;
+use crate::rate_limit::RateLimitTracker;
+use crate::transport::{Transport, TransportSender};
+use clap::ValueEnum;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::RpcClientConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// An endpoint to test, with the settings that differ between providers
+#[derive(Clone, Debug)]
+pub struct Endpoint {
+    pub url: String,
+    /// Sent with every HTTP request, e.g. an API key header
+    pub headers: HeaderMap,
+    /// Commitment of the suite and probes (default: finalized)
+    pub commitment: Option<CommitmentConfig>,
+    /// PubSub URL, overriding --ws-url and the one derived from `url`
+    pub ws_url: Option<String>,
+}
+
+impl Endpoint {
+    pub fn commitment(&self) -> CommitmentConfig {
+        self.commitment.unwrap_or_default()
+    }
+
+    /// A transport that sends the endpoint's headers
+    pub fn transport(&self, har: Option<HarRecorder>, rate_limits: RateLimitTracker) -> Transport {
+        Transport::new(&self.url, har, rate_limits).with_headers(self.headers.clone())
+    }
+
+    /// An RpcClient for probes outside the measured tests, at the endpoint's commitment
+    pub fn rpc_client(&self) -> RpcClient {
+        self.rpc_client_with(self.commitment())
+    }
+
+    pub fn rpc_client_with(&self, commitment: CommitmentConfig) -> RpcClient {
+        let transport = Arc::new(self.transport(None, RateLimitTracker::default()));
+        RpcClient::new_sender(TransportSender(transport), RpcClientConfig::with_commitment(commitment))
+    }
+}
+
+/// `URL [key=value ...]`, where the keys are
+/// `header=NAME:VALUE` (repeatable), `api-key=KEY` (added as the `api-key` query parameter),
+/// `commitment=processed|confirmed|finalized` and `ws=PUBSUB_URL`
+impl FromStr for Endpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let raw = parts.next().ok_or("expected an endpoint URL")?;
+        let mut url = reqwest::Url::parse(raw).map_err(|e| format!("invalid URL '{}': {}", raw, e))?;
+        let mut endpoint = Endpoint { url: raw.to_string(), headers: HeaderMap::new(), commitment: None, ws_url: None };
+
+        for option in parts {
+            let (key, value) = option.split_once('=').ok_or_else(|| format!("expected key=value, got '{}'", option))?;
+            match key {
+                "header" => {
+                    let (name, value) =
+                        value.split_once(':').ok_or_else(|| format!("expected header=NAME:VALUE, got '{}'", option))?;
+                    let name = HeaderName::from_str(name.trim()).map_err(|e| format!("invalid header name '{}': {}", name, e))?;
+                    let value = HeaderValue::from_str(value.trim()).map_err(|e| format!("invalid value for header {}: {}", name, e))?;
+                    endpoint.headers.append(name, value);
+                }
+                "api-key" => {
+                    url.query_pairs_mut().append_pair("api-key", value);
+                    endpoint.url = url.to_string();
+                }
+                "commitment" => {
+                    endpoint.commitment =
+                        Some(CommitmentConfig::from_str(value).map_err(|_| format!("unknown commitment '{}'", value))?);
+                }
+                "ws" => endpoint.ws_url = Some(value.to_string()),
+                other => return Err(format!("unknown endpoint option '{}' (use header, api-key, commitment, ws)", other)),
+            }
+        }
+
+        Ok(endpoint)
+    }
+}
+
+/// Well-known public endpoints, for benchmarking without hunting for URLs
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Preset {
+    /// Keyless public mainnet-beta endpoints
+    MainnetPublic,
+    /// The Solana Labs devnet endpoint
+    Devnet,
+    /// The Solana Labs testnet endpoint
+    Testnet,
+    /// A solana-test-validator on this machine
+    Localnet,
+}
+
+impl Preset {
+    pub fn urls(self) -> &'static [&'static str] {
+        match self {
+            Preset::MainnetPublic => &[
+                "https://api.mainnet-beta.solana.com",
+                "https://solana-rpc.publicnode.com",
+                "https://solana.drpc.org",
+            ],
+            Preset::Devnet => &["https://api.devnet.solana.com"],
+            Preset::Testnet => &["https://api.testnet.solana.com"],
+            Preset::Localnet => &["http://127.0.0.1:8899"],
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::fmt;
+
+/// Broad kinds of failures, so reports can show counts instead of raw messages only
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ErrorCategory {
+    RateLimited,
+    ServerError,
+    ClientError,
+    Timeout,
+    Connection,
+    JsonParse,
+    /// A JSON-RPC error object, with its error code
+    Rpc(i64),
+    Other,
+}
+
+impl ErrorCategory {
+    /// Classify an error message as produced by the transport, reqwest, serde or solana-client
+    pub fn classify(message: &str) -> Self {
+        let lower = message.to_lowercase();
+
+        if let Some(status) = http_status(message) {
+            return match status {
+                429 => ErrorCategory::RateLimited,
+                500..=599 => ErrorCategory::ServerError,
+                _ => ErrorCategory::ClientError,
+            };
+        }
+        if let Some(code) = rpc_code(message) {
+            return ErrorCategory::Rpc(code);
+        }
+        if lower.contains("timed out") || lower.contains("timeout") || lower.contains("deadline has elapsed") {
+            return ErrorCategory::Timeout;
+        }
+        if lower.contains("connection refused")
+            || lower.contains("connection reset")
+            || lower.contains("error trying to connect")
+            || lower.contains("dns error")
+            || lower.contains("error sending request")
+            || lower.contains("broken pipe")
+        {
+            return ErrorCategory::Connection;
+        }
+        if lower.contains("expected value")
+            || lower.contains("eof while parsing")
+            || lower.contains("invalid type")
+            || lower.contains("missing field")
+            || lower.contains("error decoding response body")
+        {
+            return ErrorCategory::JsonParse;
+        }
+        ErrorCategory::Other
+    }
+}
+
+/// Status code of messages like `HTTP 429 Too Many Requests: ...` or `HTTP status client error (429 ...)`
+fn http_status(message: &str) -> Option<u16> {
+    if let Some(rest) = message.split("HTTP ").nth(1) {
+        if let Ok(status) = rest.chars().take_while(char::is_ascii_digit).collect::<String>().parse() {
+            return Some(status);
+        }
+    }
+    let rest = message.split("HTTP status ").nth(1)?;
+    let rest = rest.split('(').nth(1)?;
+    rest.chars().take_while(char::is_ascii_digit).collect::<String>().parse().ok()
+}
+
+/// Code of solana-client messages like `RPC response error -32005: ...`
+fn rpc_code(message: &str) -> Option<i64> {
+    let rest = message.split("RPC response error ").nth(1)?;
+    rest.chars()
+        .take_while(|c| *c == '-' || c.is_ascii_digit())
+        .collect::<String>()
+        .parse()
+        .ok()
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorCategory::RateLimited => write!(f, "http_429"),
+            ErrorCategory::ServerError => write!(f, "http_5xx"),
+            ErrorCategory::ClientError => write!(f, "http_4xx"),
+            ErrorCategory::Timeout => write!(f, "timeout"),
+            ErrorCategory::Connection => write!(f, "connection"),
+            ErrorCategory::JsonParse => write!(f, "json_parse"),
+            ErrorCategory::Rpc(code) => write!(f, "rpc_{}", code),
+            ErrorCategory::Other => write!(f, "other"),
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::endpoint::Endpoint;
+use crate::rate_limit::RateLimitTracker;
+use crate::transport::Transport;
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+/// How the primary fails in the rounds a failure is injected
+#[derive(ValueEnum, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InjectedFailure {
+    /// The primary never answers, the client waits out its whole timeout
+    Timeout,
+    /// The primary answers with an error after a normal round trip
+    Error,
+}
+
+impl InjectedFailure {
+    pub fn label(self) -> &'static str {
+        match self {
+            InjectedFailure::Timeout => "timeouts",
+            InjectedFailure::Error => "errors",
+        }
+    }
+}
+
+/// What the simulation is asked to do
+pub struct FailoverConfig {
+    pub rounds: usize,
+    pub inject: InjectedFailure,
+    /// Share of rounds in which the primary fails, in percent
+    pub failure_pct: f64,
+    /// Client timeout per attempt, charged in full for an injected timeout
+    pub timeout: Duration,
+}
+
+/// Outcome of a chain of endpoints tried in order until one answers
+#[derive(Serialize, Clone, Debug)]
+pub struct FailoverResult {
+    pub chain: Vec<String>,
+    pub rounds: usize,
+    pub inject: InjectedFailure,
+    pub injected: usize,
+    pub timeout_ms: u128,
+    /// Rounds answered by each endpoint, in chain order
+    pub served_by: Vec<usize>,
+    /// Rounds in which no endpoint of the chain answered
+    pub failed: usize,
+    /// Share of rounds the chain answered, in percent
+    pub availability: f64,
+    /// Share of rounds the primary alone would have answered, in percent
+    pub primary_availability: f64,
+    /// Time until a successful response, including failed attempts before it
+    pub p50_ms: Option<u128>,
+    pub p95_ms: Option<u128>,
+    pub max_ms: Option<u128>,
+}
+
+/// Spread injected failures evenly: fail round `i` when the running share crosses a whole round
+fn injected(round: usize, failure_pct: f64) -> bool {
+    let rate = failure_pct.clamp(0.0, 100.0) / 100.0;
+    ((round + 1) as f64 * rate).floor() > (round as f64 * rate).floor()
+}
+
+/// Nearest-rank percentile of an ascending slice
+fn nearest_rank(sorted: &[u128], p: f64) -> Option<u128> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// One real getSlot against an endpoint, bounded by the client timeout
+async fn attempt(transport: &Transport, timeout: Duration) -> (Duration, bool) {
+    let start = Instant::now();
+    let body = json!({ "jsonrpc": "2.0", "id": 1, "method": "getSlot" });
+    let ok = match tokio::time::timeout(timeout, transport.post_json(&body)).await {
+        Ok(Ok(response)) if response.status.is_success() => {
+            serde_json::from_str::<Value>(&response.body).is_ok_and(|json| json.get("result").is_some())
+        }
+        _ => false,
+    };
+    (start.elapsed(), ok)
+}
+
+/// Send each round to the endpoints in order until one answers, with the primary failing
+/// on purpose in `failure_pct` of the rounds. Injected timeouts are charged, not waited out.
+pub async fn simulate(endpoints: &[Endpoint], config: &FailoverConfig) -> FailoverResult {
+    let transports: Vec<Transport> =
+        endpoints.iter().map(|endpoint| endpoint.transport(None, RateLimitTracker::default())).collect();
+
+    let mut served_by = vec![0; endpoints.len()];
+    let mut primary_ok = 0;
+    let mut injected_rounds = 0;
+    let mut times = Vec::new();
+
+    for round in 0..config.rounds {
+        let inject = injected(round, config.failure_pct);
+        injected_rounds += inject as usize;
+        let mut elapsed = Duration::ZERO;
+
+        for (i, transport) in transports.iter().enumerate() {
+            let ok = if i == 0 && inject {
+                match config.inject {
+                    InjectedFailure::Timeout => elapsed += config.timeout,
+                    // A real round trip whose answer is thrown away
+                    InjectedFailure::Error => elapsed += attempt(transport, config.timeout).await.0,
+                }
+                false
+            } else {
+                let (duration, ok) = attempt(transport, config.timeout).await;
+                elapsed += duration;
+                ok
+            };
+
+            if ok {
+                served_by[i] += 1;
+                primary_ok += (i == 0) as usize;
+                times.push(elapsed.as_millis());
+                break;
+            }
+        }
+    }
+
+    times.sort_unstable();
+    let pct = |count: usize| if config.rounds > 0 { count as f64 * 100.0 / config.rounds as f64 } else { 0.0 };
+    FailoverResult {
+        chain: endpoints.iter().map(|endpoint| endpoint.url.clone()).collect(),
+        rounds: config.rounds,
+        inject: config.inject,
+        injected: injected_rounds,
+        timeout_ms: config.timeout.as_millis(),
+        failed: config.rounds - times.len(),
+        availability: pct(times.len()),
+        primary_availability: pct(primary_ok),
+        served_by,
+        p50_ms: nearest_rank(&times, 50.0),
+        p95_ms: nearest_rank(&times, 95.0),
+        max_ms: times.last().copied(),
+    }
+}
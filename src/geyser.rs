@@ -0,0 +1,138 @@
+use crate::rpc_tests::TestResult;
+use crate::ws::Throughput;
+use anyhow::{anyhow, Context, Result};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::time::timeout;
+use tonic::transport::ClientTlsConfig;
+use yellowstone_grpc_client::GeyserGrpcClient;
+use yellowstone_grpc_proto::geyser::subscribe_update::UpdateOneof;
+use yellowstone_grpc_proto::geyser::{
+    SubscribeRequest, SubscribeRequestFilterAccounts, SubscribeRequestFilterSlots, SubscribeUpdate,
+};
+
+/// How long to wait for any single update before giving up
+const UPDATE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where the Yellowstone (Geyser gRPC) endpoint lives
+pub struct GeyserConfig {
+    pub url: String,
+    /// Sent as `x-token`, which most hosted offerings require
+    pub token: Option<String>,
+}
+
+type Updates = std::pin::Pin<Box<dyn futures::Stream<Item = Result<SubscribeUpdate, tonic::Status>> + Send>>;
+
+async fn subscribe(config: &GeyserConfig, request: SubscribeRequest) -> Result<Updates> {
+    let tls = config.url.starts_with("https://").then(ClientTlsConfig::new);
+    let mut client = GeyserGrpcClient::connect(config.url.clone(), config.token.clone(), tls)
+        .with_context(|| format!("Failed to connect to {}", config.url))?;
+    let (_sink, stream) = client.subscribe_with_request(Some(request)).await?;
+    Ok(Box::pin(stream))
+}
+
+async fn next_update(updates: &mut Updates) -> Result<UpdateOneof> {
+    loop {
+        let update = timeout(UPDATE_TIMEOUT, updates.next())
+            .await
+            .with_context(|| format!("No update within {}s", UPDATE_TIMEOUT.as_secs()))?
+            .ok_or_else(|| anyhow!("Stream ended"))??;
+        match update.update_oneof {
+            // Keep-alive pings carry no data
+            Some(UpdateOneof::Ping(_)) | None => continue,
+            Some(update) => return Ok(update),
+        }
+    }
+}
+
+/// Time to the first slot update, then the gap before each of the next `slots` new slots
+async fn slots(config: &GeyserConfig, slots: usize) -> Vec<TestResult> {
+    const FIRST: &str = "gRPC slots (first update)";
+    const INTERVAL: &str = "gRPC slots (interval)";
+
+    let request = SubscribeRequest {
+        slots: HashMap::from([("slots".to_string(), SubscribeRequestFilterSlots::default())]),
+        ..SubscribeRequest::default()
+    };
+
+    let start = Instant::now();
+    let mut updates = match subscribe(config, request).await {
+        Ok(updates) => updates,
+        Err(e) => return vec![TestResult::failure(FIRST, start.elapsed(), format!("{:#}", e))],
+    };
+
+    let mut results = Vec::new();
+    let mut last_slot = None;
+    let mut last = start;
+    while results.len() <= slots {
+        match next_update(&mut updates).await {
+            // Every slot is reported once per commitment level, only its first sighting counts
+            Ok(UpdateOneof::Slot(update)) if !last_slot.is_some_and(|s| update.slot <= s) => {
+                let name = if last_slot.is_some() { INTERVAL } else { FIRST };
+                results.push(TestResult::success(name, last.elapsed()));
+                last_slot = Some(update.slot);
+                last = Instant::now();
+            }
+            Ok(_) => continue,
+            Err(e) => {
+                let name = if last_slot.is_some() { INTERVAL } else { FIRST };
+                results.push(TestResult::failure(name, last.elapsed(), format!("{:#}", e)));
+                break;
+            }
+        }
+    }
+
+    results
+}
+
+/// Time to the first account update of `owner`'s accounts, then count them for `window`
+async fn accounts(config: &GeyserConfig, owner: &str, window: Duration) -> (Vec<TestResult>, Option<Throughput>) {
+    const FIRST: &str = "gRPC accounts (first update)";
+
+    let filter = SubscribeRequestFilterAccounts { owner: vec![owner.to_string()], ..Default::default() };
+    let request = SubscribeRequest {
+        accounts: HashMap::from([("accounts".to_string(), filter)]),
+        ..SubscribeRequest::default()
+    };
+
+    let start = Instant::now();
+    let mut updates = match subscribe(config, request).await {
+        Ok(updates) => updates,
+        Err(e) => return (vec![TestResult::failure(FIRST, start.elapsed(), format!("{:#}", e))], None),
+    };
+
+    let first = next_update(&mut updates).await;
+    let results = vec![TestResult::from_result(FIRST, start.elapsed(), first.as_ref().map_err(|e| format!("{:#}", e)))];
+    if first.is_err() {
+        return (results, None);
+    }
+
+    let counting = Instant::now();
+    let (mut count, mut bytes) = (0, 0);
+    while counting.elapsed() < window {
+        match timeout(window.saturating_sub(counting.elapsed()), next_update(&mut updates)).await {
+            Ok(Ok(UpdateOneof::Account(update))) => {
+                count += 1;
+                bytes += update.account.map_or(0, |account| account.data.len() as u64);
+            }
+            Ok(Ok(_)) => continue,
+            Ok(Err(_)) | Err(_) => break,
+        }
+    }
+
+    (results, Some(Throughput::new("gRPC accounts", count, bytes, counting.elapsed())))
+}
+
+/// Slot stream latency and account stream throughput of a Yellowstone endpoint
+pub async fn benchmark(
+    config: &GeyserConfig,
+    notifications: usize,
+    owner: &str,
+    window: Duration,
+) -> (Vec<TestResult>, Option<Throughput>) {
+    let mut results = slots(config, notifications).await;
+    let (account_results, throughput) = accounts(config, owner, window).await;
+    results.extend(account_results);
+    (results, throughput)
+}
@@ -0,0 +1,163 @@
+use clap::ValueEnum;
+use serde_json::{json, Value};
+
+/// Which exported metrics the dashboard queries
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Datasource {
+    /// Metrics from --prom-textfile / --format prometheus
+    Prometheus,
+    /// Points pushed with --influx-url (Flux queries)
+    Influx,
+}
+
+fn timeseries(id: u32, title: &str, unit: &str, targets: Vec<Value>, x: u32, y: u32) -> Value {
+    json!({
+        "id": id,
+        "type": "timeseries",
+        "title": title,
+        "datasource": { "uid": "${datasource}" },
+        "gridPos": { "h": 9, "w": 12, "x": x, "y": y },
+        "fieldConfig": { "defaults": { "unit": unit }, "overrides": [] },
+        "options": { "legend": { "displayMode": "table", "placement": "bottom", "calcs": ["mean", "max"] } },
+        "targets": targets,
+    })
+}
+
+fn prometheus_panels() -> Vec<Value> {
+    let target = |expr: &str, legend: &str| json!({ "refId": "A", "expr": expr, "legendFormat": legend });
+    vec![
+        timeseries(
+            1,
+            "Average latency by method",
+            "s",
+            vec![target(
+                "solana_rpc_request_duration_seconds_sum{endpoint=~\"$endpoint\"} / solana_rpc_request_duration_seconds_count{endpoint=~\"$endpoint\"}",
+                "{{method}}",
+            )],
+            0,
+            0,
+        ),
+        timeseries(
+            2,
+            "Success ratio by method",
+            "percentunit",
+            vec![target("solana_rpc_success_ratio{endpoint=~\"$endpoint\"}", "{{method}}")],
+            12,
+            0,
+        ),
+        timeseries(
+            3,
+            "Requests by outcome",
+            "short",
+            vec![target(
+                "sum by (status) (solana_rpc_requests_total{endpoint=~\"$endpoint\"})",
+                "{{status}}",
+            )],
+            0,
+            9,
+        ),
+        timeseries(
+            4,
+            "Time since last run",
+            "s",
+            vec![target("time() - solana_rpc_last_run_timestamp_seconds{endpoint=~\"$endpoint\"}", "{{endpoint}}")],
+            12,
+            9,
+        ),
+    ]
+}
+
+fn influx_panels(bucket: &str) -> Vec<Value> {
+    let flux = |body: &str| {
+        json!({
+            "refId": "A",
+            "query": format!(
+                "from(bucket: \"{}\")\n  |> range(start: v.timeRangeStart, stop: v.timeRangeStop)\n  |> filter(fn: (r) => r._measurement == \"rpc_latency\" and r.endpoint =~ /^${{endpoint:regex}}$/)\n{}",
+                bucket, body
+            ),
+        })
+    };
+    vec![
+        timeseries(
+            1,
+            "Average latency by method",
+            "ms",
+            vec![flux(
+                "  |> filter(fn: (r) => r._field == \"duration_ms\")\n  |> group(columns: [\"method\"])\n  |> aggregateWindow(every: v.windowPeriod, fn: mean, createEmpty: false)",
+            )],
+            0,
+            0,
+        ),
+        timeseries(
+            2,
+            "p95 latency by method",
+            "ms",
+            vec![flux(
+                "  |> filter(fn: (r) => r._field == \"duration_ms\")\n  |> group(columns: [\"method\"])\n  |> aggregateWindow(every: v.windowPeriod, fn: (column, tables=<-) => tables |> quantile(q: 0.95, column: column), createEmpty: false)",
+            )],
+            12,
+            0,
+        ),
+        timeseries(
+            3,
+            "Success ratio by method",
+            "percentunit",
+            vec![flux(
+                "  |> filter(fn: (r) => r._field == \"success\")\n  |> map(fn: (r) => ({ r with _value: if r._value then 1.0 else 0.0 }))\n  |> group(columns: [\"method\"])\n  |> aggregateWindow(every: v.windowPeriod, fn: mean, createEmpty: false)",
+            )],
+            0,
+            9,
+        ),
+    ]
+}
+
+/// A dashboard wired to the metric names this tool exports, ready for Grafana's import dialog
+pub fn dashboard(datasource: Datasource, bucket: &str) -> Value {
+    let (plugin, panels, endpoint_query) = match datasource {
+        Datasource::Prometheus => (
+            "prometheus",
+            prometheus_panels(),
+            json!({ "query": "label_values(solana_rpc_requests_total, endpoint)", "refId": "endpoints" }),
+        ),
+        Datasource::Influx => (
+            "influxdb",
+            influx_panels(bucket),
+            json!(format!(
+                "import \"influxdata/influxdb/schema\"\nschema.tagValues(bucket: \"{}\", tag: \"endpoint\")",
+                bucket
+            )),
+        ),
+    };
+
+    json!({
+        "title": "Solana RPC Checker",
+        "uid": format!("solana-rpc-checker-{}", plugin),
+        "tags": ["solana", "rpc"],
+        "timezone": "browser",
+        "schemaVersion": 39,
+        "refresh": "1m",
+        "time": { "from": "now-24h", "to": "now" },
+        "templating": {
+            "list": [
+                {
+                    "name": "datasource",
+                    "type": "datasource",
+                    "query": plugin,
+                    "label": "Data source",
+                },
+                {
+                    "name": "endpoint",
+                    "type": "query",
+                    "label": "Endpoint",
+                    "datasource": { "uid": "${datasource}" },
+                    "query": endpoint_query,
+                    "refresh": 2,
+                    "multi": true,
+                    "includeAll": true,
+                    "current": { "text": "All", "value": "$__all" },
+                },
+            ],
+        },
+        "panels": panels,
+    })
+}
@@ -0,0 +1,125 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Collects every HTTP exchange of a run as HAR 1.2 entries
+#[derive(Clone)]
+pub struct HarRecorder {
+    entries: Arc<Mutex<Vec<Value>>>,
+    /// Bodies longer than this many bytes are cut off
+    max_body: Option<usize>,
+}
+
+/// Everything known about one finished request/response pair
+pub struct Exchange<'a> {
+    pub started: DateTime<Utc>,
+    pub url: &'a str,
+    pub version: String,
+    pub request_headers: &'a HeaderMap,
+    pub request_body: &'a str,
+    pub status: StatusCode,
+    pub response_headers: &'a HeaderMap,
+    pub response_body: &'a str,
+    /// Time until the response headers arrived
+    pub wait: Duration,
+    pub total: Duration,
+}
+
+fn headers(map: &HeaderMap) -> Vec<Value> {
+    map.iter()
+        .map(|(name, value)| json!({ "name": name.as_str(), "value": value.to_str().unwrap_or_default() }))
+        .collect()
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+impl HarRecorder {
+    pub fn new(max_body: Option<usize>) -> Self {
+        HarRecorder { entries: Arc::new(Mutex::new(Vec::new())), max_body }
+    }
+
+    fn truncate<'a>(&self, body: &'a str) -> &'a str {
+        match self.max_body {
+            Some(max) if body.len() > max => {
+                let mut end = max;
+                while !body.is_char_boundary(end) {
+                    end -= 1;
+                }
+                &body[..end]
+            }
+            _ => body,
+        }
+    }
+
+    pub fn record(&self, exchange: &Exchange) {
+        let mime_type = exchange
+            .response_headers
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("application/json");
+
+        let entry = json!({
+            "startedDateTime": exchange.started.to_rfc3339(),
+            "time": ms(exchange.total),
+            "request": {
+                "method": "POST",
+                "url": exchange.url,
+                "httpVersion": exchange.version,
+                "headers": headers(exchange.request_headers),
+                "queryString": [],
+                "cookies": [],
+                "headersSize": -1,
+                "bodySize": exchange.request_body.len(),
+                "postData": { "mimeType": "application/json", "text": self.truncate(exchange.request_body) },
+            },
+            "response": {
+                "status": exchange.status.as_u16(),
+                "statusText": exchange.status.canonical_reason().unwrap_or_default(),
+                "httpVersion": exchange.version,
+                "headers": headers(exchange.response_headers),
+                "cookies": [],
+                "content": {
+                    "size": exchange.response_body.len(),
+                    "mimeType": mime_type,
+                    "text": self.truncate(exchange.response_body),
+                },
+                "redirectURL": "",
+                "headersSize": -1,
+                "bodySize": exchange.response_body.len(),
+            },
+            "cache": {},
+            // reqwest does not expose connection phases, so only wait and receive are known
+            "timings": {
+                "blocked": -1,
+                "dns": -1,
+                "connect": -1,
+                "ssl": -1,
+                "send": 0,
+                "wait": ms(exchange.wait),
+                "receive": ms(exchange.total - exchange.wait),
+            },
+        });
+        self.entries.lock().unwrap().push(entry);
+    }
+
+    /// Write all recorded entries as a HAR file
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let entries = self.entries.lock().unwrap();
+        let har = json!({
+            "log": {
+                "version": "1.2",
+                "creator": { "name": "solana-rpc-checker", "version": env!("CARGO_PKG_VERSION") },
+                "entries": *entries,
+            }
+        });
+        std::fs::write(path, serde_json::to_string_pretty(&har)?)
+            .with_context(|| format!("Failed to write HAR to {}", path.display()))
+    }
+}
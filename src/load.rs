@@ -0,0 +1,762 @@
+use crate::report::histogram::LatencyHistogram;
+use crate::rpc_tests::{TestContext, TestFn, TestResult};
+use crate::runner::{self, RunOptions};
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use futures::future::join_all;
+use indicatif::ProgressBar;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::{sleep_until, Instant};
+
+/// The suite tests named by `methods`, in that order
+pub fn select(tests: &[(Cow<'static, str>, TestFn)], methods: &[String]) -> Result<Vec<(Cow<'static, str>, TestFn)>> {
+    methods
+        .iter()
+        .map(|method| {
+            tests.iter().find(|(name, _)| name == method).cloned().ok_or_else(|| {
+                let known: Vec<&str> = tests.iter().map(|(name, _)| name.as_ref()).collect();
+                anyhow!("Unknown method {}, expected one of: {}", method, known.join(", "))
+            })
+        })
+        .collect()
+}
+
+/// Methods of a mixed workload with their relative share of the requests
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mix {
+    pub weights: Vec<(String, u32)>,
+}
+
+impl FromStr for Mix {
+    type Err = String;
+
+    /// Comma-separated `METHOD=WEIGHT` pairs, e.g. `getAccountInfo=50,getLatestBlockhash=30`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let weights = s
+            .split(',')
+            .map(|pair| {
+                let (method, weight) = pair
+                    .rsplit_once('=')
+                    .ok_or_else(|| format!("invalid mix entry '{}', expected METHOD=WEIGHT", pair))?;
+                let weight: u32 = weight.trim().parse().map_err(|_| format!("invalid weight in '{}'", pair))?;
+                if weight == 0 {
+                    return Err(format!("weight of {} must be positive", method.trim()));
+                }
+                Ok((method.trim().to_string(), weight))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Mix { weights })
+    }
+}
+
+/// Named mixes approximating the traffic of common kinds of applications
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Workload {
+    /// Balances, token accounts and blockhashes for transfers
+    Wallet,
+    /// Hot account reads, fresh blockhashes, fees and simulations
+    DexBot,
+    /// Program scans and account reads over recent blocks
+    NftIndexer,
+    /// Blocks, accounts and network statistics for browsing the chain
+    Explorer,
+}
+
+impl Workload {
+    pub fn mix(self) -> Mix {
+        let weights: &[(&str, u32)] = match self {
+            Workload::Wallet => &[
+                ("getBalance", 30),
+                ("getTokenAccountsByOwner", 25),
+                ("getLatestBlockhash", 20),
+                ("getAccountInfo", 10),
+                ("getRecentPrioritizationFees", 10),
+                ("simulateTransaction", 5),
+            ],
+            Workload::DexBot => &[
+                ("getAccountInfo", 40),
+                ("getLatestBlockhash", 20),
+                ("getSlot", 15),
+                ("getBalance (batch of 10)", 10),
+                ("getRecentPrioritizationFees", 10),
+                ("simulateTransaction", 5),
+            ],
+            Workload::NftIndexer => &[
+                ("getProgramAccounts", 30),
+                ("getAccountInfo", 30),
+                ("getTokenAccountsByOwner", 20),
+                ("getBlock", 15),
+                ("getSlot", 5),
+            ],
+            Workload::Explorer => &[
+                ("getBlock", 25),
+                ("getAccountInfo", 20),
+                ("getBalance", 15),
+                ("getSlot", 15),
+                ("getTokenAccountsByOwner", 10),
+                ("getRecentPerformanceSamples", 10),
+                ("getSupply (excl. accounts)", 5),
+            ],
+        };
+        Mix { weights: weights.iter().map(|(method, weight)| (method.to_string(), *weight)).collect() }
+    }
+}
+
+/// One rotation of the mix's tests, each appearing in proportion to its weight and spread
+/// evenly through the rotation (smooth weighted round-robin), so any stretch of a run sees
+/// roughly the mix rather than a burst of one method
+pub fn select_mix(tests: &[(Cow<'static, str>, TestFn)], mix: &Mix) -> Result<Vec<(Cow<'static, str>, TestFn)>> {
+    let methods: Vec<String> = mix.weights.iter().map(|(method, _)| method.clone()).collect();
+    let selected = select(tests, &methods)?;
+
+    let divisor = mix.weights.iter().fold(0, |acc, (_, weight)| gcd(acc, *weight));
+    let weights: Vec<i64> = mix.weights.iter().map(|(_, weight)| (weight / divisor) as i64).collect();
+    let total: i64 = weights.iter().sum();
+
+    let mut current = vec![0i64; weights.len()];
+    let mut rotation = Vec::with_capacity(total as usize);
+    for _ in 0..total {
+        for (current, weight) in current.iter_mut().zip(&weights) {
+            *current += weight;
+        }
+        let (pick, _) = current.iter().enumerate().max_by_key(|(i, c)| (**c, std::cmp::Reverse(*i))).expect("mix is not empty");
+        current[pick] -= total;
+        rotation.push(selected[pick].clone());
+    }
+    Ok(rotation)
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// One stretch of a load profile: a held rate, or a linear ramp between two rates
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stage {
+    pub from_rps: f64,
+    pub to_rps: f64,
+    pub duration: Duration,
+}
+
+/// Ramps are reported in this many equal windows, so degradation along the ramp shows
+const RAMP_WINDOWS: usize = 5;
+
+impl Stage {
+    pub fn is_ramp(&self) -> bool {
+        self.from_rps != self.to_rps
+    }
+
+    /// Offsets from the start of the stage at which its requests are sent, so that the
+    /// number sent by any point in time follows the integral of the rate
+    fn schedule(&self) -> Vec<Duration> {
+        let length = self.duration.as_secs_f64();
+        let total = ((self.from_rps + self.to_rps) / 2.0 * length).round() as usize;
+        let slope = (self.to_rps - self.from_rps) / length;
+        (0..total)
+            .map(|k| {
+                let k = k as f64;
+                // Solve from·t + slope·t²/2 = k for t
+                let t = if slope.abs() < f64::EPSILON {
+                    k / self.from_rps
+                } else {
+                    (-self.from_rps + (self.from_rps.powi(2) + 2.0 * slope * k).sqrt()) / slope
+                };
+                Duration::from_secs_f64(t.clamp(0.0, length))
+            })
+            .collect()
+    }
+
+    fn label(&self) -> String {
+        let duration = humantime::format_duration(self.duration);
+        if self.is_ramp() {
+            format!("{}-{}rps/{}", self.from_rps, self.to_rps, duration)
+        } else {
+            format!("{}rps/{}", self.from_rps, duration)
+        }
+    }
+}
+
+impl FromStr for Stage {
+    type Err = String;
+
+    /// `RATErps/DURATION` holds a rate, `FROM-TOrps/DURATION` ramps linearly, e.g. `0-500rps/5m`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid stage '{}', expected RATErps/DURATION or FROM-TOrps/DURATION", s);
+        let (rates, duration) = s.trim().split_once('/').ok_or_else(invalid)?;
+        let rates = rates.trim().strip_suffix("rps").ok_or_else(invalid)?;
+        let (from, to) = rates.split_once('-').unwrap_or((rates, rates));
+        let from_rps: f64 = from.trim().parse().map_err(|_| invalid())?;
+        let to_rps: f64 = to.trim().parse().map_err(|_| invalid())?;
+        let duration = humantime::parse_duration(duration.trim()).map_err(|e| format!("{}: {}", invalid(), e))?;
+        if !from_rps.is_finite() || !to_rps.is_finite() {
+            return Err(invalid());
+        }
+        if from_rps < 0.0 || to_rps < 0.0 || from_rps + to_rps == 0.0 || duration.is_zero() {
+            return Err(format!("stage '{}' sends nothing", s));
+        }
+        Ok(Stage { from_rps, to_rps, duration })
+    }
+}
+
+/// Start and end of a reporting window, as offsets from the start of the run
+type Window = (Duration, Duration);
+
+/// One request of a profile's schedule
+#[derive(Clone, Copy, Debug)]
+struct ScheduledSend {
+    /// Offset from the start of the run
+    at: Duration,
+    /// Index of the reporting window the send falls in
+    window: usize,
+}
+
+/// Request rate over time of an open-loop run, one stage after the other
+#[derive(Clone, Debug)]
+pub struct LoadProfile {
+    pub stages: Vec<Stage>,
+}
+
+impl LoadProfile {
+    pub fn constant(rps: f64, duration: Duration) -> Self {
+        LoadProfile { stages: vec![Stage { from_rps: rps, to_rps: rps, duration }] }
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.stages.iter().map(|stage| stage.duration).sum()
+    }
+
+    pub fn peak_rps(&self) -> f64 {
+        self.stages.iter().map(|stage| stage.from_rps.max(stage.to_rps)).fold(0.0, f64::max)
+    }
+
+    /// Requests the schedule sends over the whole run
+    pub fn planned(&self) -> usize {
+        self.stages.iter().map(|stage| stage.schedule().len()).sum()
+    }
+
+    pub fn label(&self) -> String {
+        self.stages.iter().map(Stage::label).collect::<Vec<_>>().join(",")
+    }
+
+    /// Send offsets from the start of the run, each with the reporting window it falls in
+    fn schedule(&self) -> (Vec<ScheduledSend>, Vec<Window>) {
+        let mut sends = Vec::new();
+        let mut windows = Vec::new();
+        let mut offset = Duration::ZERO;
+        for stage in &self.stages {
+            let parts = if stage.is_ramp() { RAMP_WINDOWS } else { 1 };
+            let part = stage.duration / parts as u32;
+            let first = windows.len();
+            for i in 0..parts {
+                windows.push((offset + part * i as u32, offset + part * (i as u32 + 1)));
+            }
+            for at in stage.schedule() {
+                let window = first + ((at.as_secs_f64() / part.as_secs_f64()) as usize).min(parts - 1);
+                sends.push(ScheduledSend { at: offset + at, window });
+            }
+            offset += stage.duration;
+        }
+        (sends, windows)
+    }
+}
+
+impl FromStr for LoadProfile {
+    type Err = String;
+
+    /// Comma-separated stages run in order, e.g. `0-500rps/5m,500rps/1m`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let stages = s.split(',').map(str::parse).collect::<Result<Vec<Stage>, String>>()?;
+        Ok(LoadProfile { stages })
+    }
+}
+
+/// Requests sent during one window of a load profile and how they fared
+#[derive(Serialize, Clone, Debug)]
+pub struct LoadWindow {
+    pub from_ms: u128,
+    pub to_ms: u128,
+    /// Rate the profile offered over the window
+    pub target_rps: f64,
+    pub sent: usize,
+    pub succeeded: usize,
+    pub p50_ms: Option<u128>,
+    pub p99_ms: Option<u128>,
+}
+
+/// How closely an endpoint kept up with the offered rate
+#[derive(Serialize, Clone, Debug)]
+pub struct LoadSummary {
+    /// Stages of the profile, e.g. `100rps/30s` for a constant rate
+    pub profile: String,
+    /// Highest rate the profile offers
+    pub target_rps: f64,
+    pub duration_ms: u128,
+    pub sent: usize,
+    pub succeeded: usize,
+    /// Successful responses per second over the time from the first send to the last answer
+    pub achieved_rps: f64,
+    /// Most requests waiting for an answer at once, grows without bound past capacity
+    pub max_in_flight: usize,
+    /// Latency per stage, ramps split into windows; empty for a single held rate
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub windows: Vec<LoadWindow>,
+}
+
+/// A context shared by every request of a load run, with its connection already open
+async fn warm_context(tests: &[(Cow<'static, str>, TestFn)], options: &RunOptions) -> Arc<TestContext> {
+    let ctx = Arc::new(TestContext::new(
+        &options.endpoint,
+        options.har.clone(),
+        options.rate_limits.clone(),
+        options.settings.clone(),
+    ));
+
+    // The first scheduled request shouldn't pay for the handshake
+    if let Some((_, test_fn)) = tests.first() {
+        let _ = test_fn(&ctx).await;
+    }
+    ctx
+}
+
+/// Send requests on the schedule of the profile, cycling through `tests`, and never wait
+/// for a response before sending the next. A slow endpoint then shows up as growing
+/// latency and in-flight requests rather than a lower request rate.
+/// Latency counts from when a request was due, so one sent late still shows its wait.
+pub async fn run(
+    tests: &[(Cow<'static, str>, TestFn)],
+    profile: &LoadProfile,
+    options: &RunOptions,
+    progress_bar: &ProgressBar,
+) -> (Vec<TestResult>, LoadSummary) {
+    let ctx = warm_context(tests, options).await;
+    open_loop(tests, &ctx, profile, options, progress_bar).await
+}
+
+async fn open_loop(
+    tests: &[(Cow<'static, str>, TestFn)],
+    ctx: &Arc<TestContext>,
+    profile: &LoadProfile,
+    options: &RunOptions,
+    progress_bar: &ProgressBar,
+) -> (Vec<TestResult>, LoadSummary) {
+    let (schedule, windows) = profile.schedule();
+    let planned = schedule.len();
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let mut max_in_flight = 0;
+    let mut sent = 0;
+    let mut results = Vec::new();
+    let mut by_window: Vec<Vec<(bool, u128)>> = vec![Vec::new(); windows.len()];
+    let (tx, mut rx) = mpsc::unbounded_channel();
+
+    let start = Instant::now();
+    for (i, &ScheduledSend { at, window }) in schedule.iter().enumerate() {
+        // A late send goes out at once instead of stretching the schedule, the rate is the point
+        let due = start + at;
+        sleep_until(due).await;
+        if options.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            // Out of time: the rest of the schedule shows up as skipped in the report
+            for j in i..planned {
+                runner::record(&mut results, TestResult::skipped(&tests[j % tests.len()].0), options);
+            }
+            progress_bar.inc((planned - i) as u64);
+            break;
+        }
+
+        sent += 1;
+        let (test_name, test_fn) = tests[i % tests.len()].clone();
+        let ctx = ctx.clone();
+        let tx = tx.clone();
+        let deadline = options.deadline;
+        let in_flight_now = in_flight.fetch_add(1, Ordering::Relaxed) + 1;
+        max_in_flight = max_in_flight.max(in_flight_now);
+        let in_flight = in_flight.clone();
+        tokio::spawn(async move {
+            // Measuring from the actual send would hide the time a late send waited, which
+            // is exactly what a struggling client would have seen (coordinated omission)
+            let late = due.elapsed();
+            let outcome = match deadline {
+                Some(deadline) => tokio::time::timeout_at(deadline, test_fn(&ctx)).await.ok(),
+                None => Some(test_fn(&ctx).await),
+            };
+            in_flight.fetch_sub(1, Ordering::Relaxed);
+            let mut result = match outcome {
+                Some(Ok(result)) => result,
+                Some(Err(e)) => TestResult::failure(&test_name, Duration::ZERO, e),
+                None => TestResult::skipped(&test_name),
+            };
+            if !result.skipped {
+                result.duration_ms += late.as_millis();
+            }
+            let _ = tx.send((window, result));
+        });
+
+        progress_bar.set_message(format!("Sent {}/{}, {} in flight", i + 1, planned, in_flight_now));
+        if options.echo {
+            print!("Sent {}/{}, {} in flight...\r", i + 1, planned, in_flight_now);
+        }
+
+        // Collect whatever has answered so far, so streaming and progress keep up
+        while let Ok((window, result)) = rx.try_recv() {
+            progress_bar.inc(1);
+            by_window[window].push((result.success, result.duration_ms));
+            runner::record(&mut results, result, options);
+        }
+    }
+    // Every task holds a sender, so the channel closes once the last answer is in
+    drop(tx);
+
+    while let Some((window, result)) = rx.recv().await {
+        progress_bar.inc(1);
+        by_window[window].push((result.success, result.duration_ms));
+        runner::record(&mut results, result, options);
+    }
+    let elapsed = start.elapsed();
+
+    let windows = if windows.len() > 1 {
+        windows
+            .iter()
+            .zip(&by_window)
+            .map(|(&(from, to), outcomes)| {
+                let histogram: LatencyHistogram = outcomes.iter().filter(|(ok, _)| *ok).map(|(_, ms)| *ms).collect();
+                let offered = schedule.iter().filter(|send| send.at >= from && send.at < to).count();
+                LoadWindow {
+                    from_ms: from.as_millis(),
+                    to_ms: to.as_millis(),
+                    target_rps: offered as f64 / (to - from).as_secs_f64().max(f64::EPSILON),
+                    sent: outcomes.len(),
+                    succeeded: histogram.len() as usize,
+                    p50_ms: histogram.percentile(50.0),
+                    p99_ms: histogram.percentile(99.0),
+                }
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let succeeded = results.iter().filter(|r| r.success).count();
+    // A deadline can cut the run short, the rate is over the time actually spent
+    let summary = LoadSummary {
+        profile: profile.label(),
+        target_rps: profile.peak_rps(),
+        duration_ms: profile.duration().as_millis(),
+        sent,
+        succeeded,
+        achieved_rps: succeeded as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        max_in_flight,
+        windows,
+    };
+    (results, summary)
+}
+
+/// Concurrency levels and time per level of a sweep
+#[derive(Clone, Copy, Debug)]
+pub struct SweepConfig {
+    pub max_concurrency: usize,
+    pub step: Duration,
+}
+
+impl SweepConfig {
+    /// Powers of two up to the maximum, which is always the last level
+    pub fn levels(&self) -> Vec<usize> {
+        let mut levels: Vec<usize> =
+            std::iter::successors(Some(1usize), |n| n.checked_mul(2)).take_while(|n| *n < self.max_concurrency).collect();
+        levels.push(self.max_concurrency.max(1));
+        levels
+    }
+}
+
+/// Latency and throughput at one concurrency level of a sweep
+#[derive(Serialize, Clone, Debug)]
+pub struct SweepLevel {
+    pub concurrency: usize,
+    pub requests: usize,
+    pub succeeded: usize,
+    /// Successful responses per second
+    pub rps: f64,
+    pub p50_ms: Option<u128>,
+    pub p95_ms: Option<u128>,
+    pub p99_ms: Option<u128>,
+}
+
+/// A saturation curve: throughput and latency of one method as concurrency doubles
+#[derive(Serialize, Clone, Debug)]
+pub struct Sweep {
+    pub method: String,
+    pub step_ms: u128,
+    pub levels: Vec<SweepLevel>,
+    /// Concurrency with the highest throughput, beyond it requests only queue up
+    pub peak_concurrency: Option<usize>,
+}
+
+/// Run `workers` loops that each send the next request as soon as the previous one answered,
+/// until `until`, cycling through `tests` from their own starting point. Every worker shares
+/// the context, and with it the connection pool.
+async fn closed_loop(
+    tests: &[(Cow<'static, str>, TestFn)],
+    ctx: &Arc<TestContext>,
+    workers: usize,
+    until: Instant,
+) -> Vec<TestResult> {
+    let rotation: Arc<[(Cow<'static, str>, TestFn)]> = tests.into();
+    let handles = (0..workers).map(|worker| {
+        let ctx = ctx.clone();
+        let rotation = rotation.clone();
+        tokio::spawn(async move {
+            let mut results = Vec::new();
+            let mut next = worker;
+            while Instant::now() < until {
+                let (test_name, test_fn) = &rotation[next % rotation.len()];
+                next += 1;
+                // A request still in flight at the end is cut off rather than stretching the run
+                let result = match tokio::time::timeout_at(until, test_fn(&ctx)).await {
+                    Ok(Ok(result)) => result,
+                    Ok(Err(e)) => TestResult::failure(test_name, Duration::ZERO, e),
+                    Err(_) => break,
+                };
+                results.push(result);
+            }
+            results
+        })
+    });
+    join_all(handles).await.into_iter().filter_map(|worker| worker.ok()).flatten().collect()
+}
+
+/// Throughput of a fixed number of back-to-back workers
+#[derive(Serialize, Clone, Debug)]
+pub struct ClosedLoopSummary {
+    pub workers: usize,
+    pub duration_ms: u128,
+    pub sent: usize,
+    pub succeeded: usize,
+    /// Successful responses per second, the most these workers could get out of the endpoint
+    pub achieved_rps: f64,
+}
+
+/// Keep `workers` requests in flight at all times for `duration`, each worker sending its
+/// next request the moment the previous one answered: maximum pressure from K connections,
+/// where the rate is whatever the endpoint sustains
+pub async fn run_closed(
+    tests: &[(Cow<'static, str>, TestFn)],
+    workers: usize,
+    duration: Duration,
+    options: &RunOptions,
+    progress_bar: &ProgressBar,
+) -> (Vec<TestResult>, ClosedLoopSummary) {
+    let ctx = warm_context(tests, options).await;
+    let start = Instant::now();
+    let until = match options.deadline {
+        Some(deadline) => (start + duration).min(deadline),
+        None => start + duration,
+    };
+
+    progress_bar.set_message(format!("{} workers running back to back", workers));
+    if options.echo {
+        print!("{} workers running back to back...\r", workers);
+    }
+    let run = closed_loop(tests, &ctx, workers, until);
+    tokio::pin!(run);
+    let mut ticks = tokio::time::interval(Duration::from_secs(1));
+    let finished = loop {
+        tokio::select! {
+            results = &mut run => break results,
+            _ = ticks.tick() => progress_bar.set_position(start.elapsed().as_secs()),
+        }
+    };
+    let elapsed = start.elapsed().as_secs_f64().max(f64::EPSILON);
+
+    let mut results = Vec::new();
+    for result in finished {
+        runner::record(&mut results, result, options);
+    }
+    let succeeded = results.iter().filter(|r| r.success).count();
+    let summary = ClosedLoopSummary {
+        workers,
+        duration_ms: duration.as_millis(),
+        sent: results.len(),
+        succeeded,
+        achieved_rps: succeeded as f64 / elapsed,
+    };
+    (results, summary)
+}
+
+/// Run one method closed-loop at each concurrency level of the sweep in turn
+pub async fn sweep(
+    test: (Cow<'static, str>, TestFn),
+    config: SweepConfig,
+    options: &RunOptions,
+    progress_bar: &ProgressBar,
+) -> (Vec<TestResult>, Sweep) {
+    let ctx = warm_context(std::slice::from_ref(&test), options).await;
+    let mut results = Vec::new();
+    let mut levels = Vec::new();
+    for concurrency in config.levels() {
+        let mut until = Instant::now() + config.step;
+        if let Some(deadline) = options.deadline {
+            if Instant::now() >= deadline {
+                break;
+            }
+            until = until.min(deadline);
+        }
+
+        progress_bar.set_message(format!("Sweeping {} at concurrency {}", test.0, concurrency));
+        if options.echo {
+            print!("Sweeping {} at concurrency {}...\r", test.0, concurrency);
+        }
+
+        let start = Instant::now();
+        let level = closed_loop(std::slice::from_ref(&test), &ctx, concurrency, until).await;
+        let elapsed = start.elapsed().as_secs_f64().max(f64::EPSILON);
+
+        let histogram: LatencyHistogram = level.iter().filter(|r| r.success).map(|r| r.duration_ms).collect();
+        let succeeded = histogram.len() as usize;
+        levels.push(SweepLevel {
+            concurrency,
+            requests: level.len(),
+            succeeded,
+            rps: succeeded as f64 / elapsed,
+            p50_ms: histogram.percentile(50.0),
+            p95_ms: histogram.percentile(95.0),
+            p99_ms: histogram.percentile(99.0),
+        });
+        for result in level {
+            runner::record(&mut results, result, options);
+        }
+        progress_bar.inc(1);
+    }
+
+    let peak_concurrency =
+        levels.iter().filter(|l| l.succeeded > 0).max_by(|a, b| a.rps.total_cmp(&b.rps)).map(|l| l.concurrency);
+    let sweep = Sweep { method: test.0.to_string(), step_ms: config.step.as_millis(), levels, peak_concurrency };
+    (results, sweep)
+}
+
+/// Search range and pass criteria of a capacity search
+#[derive(Clone, Copy, Debug)]
+pub struct CapacityConfig {
+    pub min_rps: f64,
+    pub max_rps: f64,
+    /// How long each probed rate is held
+    pub step: Duration,
+    /// Most failed requests, in percent, a sustainable rate may have
+    pub max_error_pct: f64,
+    /// Highest p99 a sustainable rate may have
+    pub max_p99_ms: u128,
+    /// Most rates probed, including both ends of the range
+    pub max_probes: usize,
+}
+
+/// One rate held during a capacity search
+#[derive(Serialize, Clone, Debug)]
+pub struct CapacityProbe {
+    pub target_rps: f64,
+    pub achieved_rps: f64,
+    pub error_pct: f64,
+    pub p99_ms: Option<u128>,
+    pub passed: bool,
+}
+
+/// The highest request rate an endpoint sustains within the error and p99 limits
+#[derive(Serialize, Clone, Debug)]
+pub struct Capacity {
+    pub max_error_pct: f64,
+    pub max_p99_ms: u128,
+    pub step_ms: u128,
+    pub probes: Vec<CapacityProbe>,
+    /// Highest passing rate, `None` when even the minimum failed
+    pub sustainable_rps: Option<f64>,
+    /// Whether the maximum of the range passed, so the real capacity may be higher
+    pub at_range_limit: bool,
+}
+
+impl CapacityConfig {
+    /// Whether an open-loop run at `target_rps` stayed within the limits
+    fn probe(&self, target_rps: f64, results: &[TestResult], summary: &LoadSummary) -> CapacityProbe {
+        let ran: Vec<&TestResult> = results.iter().filter(|r| !r.skipped).collect();
+        let histogram: LatencyHistogram = ran.iter().filter(|r| r.success).map(|r| r.duration_ms).collect();
+        let error_pct = if ran.is_empty() {
+            100.0
+        } else {
+            (ran.len() - histogram.len() as usize) as f64 / ran.len() as f64 * 100.0
+        };
+        let p99_ms = histogram.percentile(99.0);
+        // Falling behind the schedule counts as failing, the requests just queue up elsewhere
+        let passed = error_pct <= self.max_error_pct
+            && p99_ms.is_some_and(|p99| p99 <= self.max_p99_ms)
+            && summary.achieved_rps >= target_rps * 0.95;
+        CapacityProbe { target_rps, achieved_rps: summary.achieved_rps, error_pct, p99_ms, passed }
+    }
+}
+
+/// Binary-search the request rate between the configured bounds for the highest one that
+/// stays within the error and p99 limits, holding each probed rate open-loop for one step
+pub async fn capacity(
+    tests: &[(Cow<'static, str>, TestFn)],
+    config: CapacityConfig,
+    options: &RunOptions,
+    progress_bar: &ProgressBar,
+) -> (Vec<TestResult>, Capacity) {
+    let ctx = warm_context(tests, options).await;
+    let mut results = Vec::new();
+    let mut probes: Vec<CapacityProbe> = Vec::new();
+
+    // Try both ends first, a range that is all pass or all fail needs no search
+    let mut low = config.min_rps;
+    let mut high = config.max_rps;
+    let mut next = Some(low);
+    while let Some(rps) = next.take() {
+        if probes.len() >= config.max_probes || options.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break;
+        }
+
+        progress_bar.set_message(format!("Holding {:.0} rps (probe {}/{})", rps, probes.len() + 1, config.max_probes));
+        let profile = LoadProfile::constant(rps, config.step);
+        let (probe_results, summary) = open_loop(tests, &ctx, &profile, options, &ProgressBar::hidden()).await;
+        let probe = config.probe(rps, &probe_results, &summary);
+        progress_bar.inc(1);
+        results.extend(probe_results);
+
+        let passed = probe.passed;
+        let first = probes.is_empty();
+        probes.push(probe);
+        next = match (first, passed) {
+            (true, false) => None,
+            (true, true) => Some(high),
+            (false, true) if rps == high => None,
+            (false, true) => {
+                low = rps;
+                Some((low + high) / 2.0)
+            }
+            (false, false) => {
+                high = rps;
+                Some((low + high) / 2.0)
+            }
+        };
+        // Stop once the bracket is within 5% of its lower end, finer steps are just noise
+        if next.is_some() && probes.len() > 2 && (high - low) <= low * 0.05 {
+            next = None;
+        }
+    }
+
+    let sustainable_rps = probes.iter().filter(|p| p.passed).map(|p| p.target_rps).max_by(f64::total_cmp);
+    let at_range_limit = sustainable_rps == Some(config.max_rps);
+    let capacity = Capacity {
+        max_error_pct: config.max_error_pct,
+        max_p99_ms: config.max_p99_ms,
+        step_ms: config.step.as_millis(),
+        probes,
+        sustainable_rps,
+        at_range_limit,
+    };
+    (results, capacity)
+}
@@ -1,20 +1,47 @@
-use anyhow::Result;
-use chrono::Utc;
-use clap::Parser;
+mod alerts;
+mod backends;
+mod batch;
+mod endpoint;
+mod errors;
+mod failover;
+#[cfg(feature = "geyser")]
+mod geyser;
+mod grafana;
+mod har;
+mod load;
+mod monitor;
+mod node;
+mod phases;
+mod race;
+mod rate_limit;
+mod report;
+mod rpc_tests;
+mod runner;
+mod scan;
+mod sinks;
+mod style;
+mod transport;
+mod ws;
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Parser, Subcommand};
+use endpoint::{Endpoint, Preset};
 use colored::*;
-use futures::future::join_all;
 use indicatif::{ProgressBar, ProgressStyle};
-use reqwest::Client;
-use serde_json::{json, Value};
-use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_client::rpc_config::RpcBlockConfig;
-use solana_client::rpc_request::TokenAccountsFilter;
-use solana_sdk::commitment_config::CommitmentConfig;
+use report::{EndpointInfo, OutputFormat, RatingMetric, Report, RunConfig, ScoreWeights, SpeedThresholds};
+use rpc_tests::{CustomMethod, DataSlice, MemcmpFilter, ProgramAccountsQuery, TestContext, TestSettings};
+use runner::{RunOptions, StreamFormat};
 use solana_sdk::pubkey::Pubkey;
-use solana_transaction_status::UiTransactionEncoding;
-use std::str::FromStr;
-use std::time::{Duration, Instant};
-use tokio::time::sleep;
+use solana_sdk::signature::read_keypair_file;
+use sinks::influx::InfluxConfig;
+use sinks::statsd::StatsdConfig;
+use sinks::zabbix::ZabbixConfig;
+use style::icon;
+use std::io::{IsTerminal, Write};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -24,14 +51,33 @@ use tokio::time::sleep;
     long_about = "Tests various RPC methods and provides detailed performance metrics for Solana RPC endpoints."
 )]
 struct Args {
-    /// RPC endpoint URL
-    #[arg(short, long, default_value = "https://mainnet.helius-rpc.com/?api-key=af2cecd4-ff66-48c9-8ef1-fddeb04f3a08")]
-    url: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// RPC endpoint URL, repeat to test several endpoints in one run [default: api.mainnet-beta.solana.com]
+    #[arg(short, long = "url")]
+    urls: Vec<Endpoint>,
+
+    /// Also test the well-known public endpoints of this preset (repeatable)
+    #[arg(long, value_enum)]
+    preset: Vec<Preset>,
+
+    /// File with one endpoint per line as URL [header=NAME:VALUE] [api-key=KEY] [commitment=LEVEL] [ws=URL]
+    #[arg(long = "url-file")]
+    url_file: Option<PathBuf>,
 
     /// Number of iterations for each test
     #[arg(short, long, default_value_t = 3)]
     iterations: usize,
 
+    /// Run each test repeatedly for this long instead of a fixed number of iterations (e.g. 60s)
+    #[arg(long, value_parser = humantime::parse_duration, conflicts_with = "iterations")]
+    duration: Option<Duration>,
+
+    /// Unrecorded warm-up iterations per test, run before the measured ones
+    #[arg(long, default_value_t = 0)]
+    warmup: usize,
+
     /// Run tests in parallel
     #[arg(short, long, default_value_t = false)]
     parallel: bool,
@@ -39,403 +85,746 @@ struct Args {
     /// Show detailed progress bar
     #[arg(long = "no-progress", action = clap::ArgAction::SetFalse)]
     progress: bool,
-}
 
-struct TestResult {
-    name: String,
-    success: bool,
-    duration_ms: u128,
-    error: Option<String>,
-}
+    /// Time DNS, TCP connect, TLS handshake and the first request on a fresh connection
+    #[arg(long = "connection-timing")]
+    connection_timing: bool,
 
-async fn test_get_latest_blockhash(client: &RpcClient) -> Result<TestResult> {
-    let start = Instant::now();
-    let result = client.get_latest_blockhash().await;
-    let duration = start.elapsed();
-
-    match result {
-        Ok(_blockhash) => Ok(TestResult {
-            name: "getLatestBlockhash".to_string(),
-            success: true,
-            duration_ms: duration.as_millis(),
-            error: None,
-        }),
-        Err(e) => Ok(TestResult {
-            name: "getLatestBlockhash".to_string(),
-            success: false,
-            duration_ms: duration.as_millis(),
-            error: Some(e.to_string()),
-        }),
-    }
-}
+    /// Only print the report: no banner, configuration, progress or status lines
+    #[arg(short, long)]
+    quiet: bool,
 
-async fn test_get_slot(client: &RpcClient) -> Result<TestResult> {
-    let start = Instant::now();
-    let result = client.get_slot().await;
-    let duration = start.elapsed();
-
-    match result {
-        Ok(_slot) => Ok(TestResult {
-            name: "getSlot".to_string(),
-            success: true,
-            duration_ms: duration.as_millis(),
-            error: None,
-        }),
-        Err(e) => Ok(TestResult {
-            name: "getSlot".to_string(),
-            success: false,
-            duration_ms: duration.as_millis(),
-            error: Some(e.to_string()),
-        }),
-    }
-}
+    /// No colors, emoji or box drawing (the default when stdout is not a terminal)
+    #[arg(long)]
+    plain: bool,
 
-async fn test_get_balance(client: &RpcClient) -> Result<TestResult> {
-    // Using a known Solana address for testing
-    let address = Pubkey::from_str("SoLANAGZJPWXuWQiACz5JJzx1jZKp55FpbjLPwmxA").unwrap_or_default();
+    /// Abort remaining iterations once an endpoint's run exceeds this duration (e.g. 120s, 5m)
+    #[arg(long = "max-duration", value_parser = humantime::parse_duration)]
+    max_duration: Option<Duration>,
 
-    let start = Instant::now();
-    let result = client.get_balance(&address).await;
-    let duration = start.elapsed();
+    /// Print each iteration to stdout as soon as it completes
+    #[arg(long, value_enum)]
+    stream: Option<StreamFormat>,
 
-    match result {
-        Ok(_) => Ok(TestResult {
-            name: "getBalance".to_string(),
-            success: true,
-            duration_ms: duration.as_millis(),
-            error: None,
-        }),
-        Err(e) => Ok(TestResult {
-            name: "getBalance".to_string(),
-            success: false,
-            duration_ms: duration.as_millis(),
-            error: Some(e.to_string()),
-        }),
-    }
-}
+    /// Upper bounds in ms for the Excellent, Good, Average and Slow ratings
+    #[arg(long = "rating-thresholds", default_value = "100,300,600,1000")]
+    rating_thresholds: SpeedThresholds,
 
-async fn test_get_account_info(client: &RpcClient) -> Result<TestResult> {
-    // Using a known Solana address for testing
-    let address = Pubkey::from_str("SoLANAGZJPWXuWQiACz5JJzx1jZKp55FpbjLPwmxA").unwrap_or_default();
+    /// Latency statistic the speed rating is based on
+    #[arg(long = "rating-metric", value_enum, default_value_t = RatingMetric::P95)]
+    rating_metric: RatingMetric,
 
-    let start = Instant::now();
-    let result = client.get_account_with_commitment(&address, CommitmentConfig::confirmed()).await;
-    let duration = start.elapsed();
+    /// Weights of the composite score components, e.g. success=0.5,median=0.2,tail=0.2,coverage=0.1
+    #[arg(long = "score-weights", default_value = "success=0.4,median=0.25,tail=0.25,coverage=0.1")]
+    score_weights: ScoreWeights,
 
-    match result {
-        Ok(_) => Ok(TestResult {
-            name: "getAccountInfo".to_string(),
-            success: true,
-            duration_ms: duration.as_millis(),
-            error: None,
-        }),
-        Err(e) => Ok(TestResult {
-            name: "getAccountInfo".to_string(),
-            success: false,
-            duration_ms: duration.as_millis(),
-            error: Some(e.to_string()),
-        }),
-    }
-}
+    /// Flag successful iterations further than this many median absolute deviations from the median
+    #[arg(long = "outlier-mads", default_value_t = 3.5)]
+    outlier_mads: f64,
 
-async fn test_get_block(client: &RpcClient) -> Result<TestResult> {
-    // First get the current slot
-    let slot_result = client.get_slot().await;
+    /// Also show the mean with this percent of the slowest and fastest iterations trimmed
+    #[arg(long = "trim")]
+    trim_pct: Option<f64>,
 
-    if let Err(e) = slot_result {
-        return Ok(TestResult {
-            name: "getBlock".to_string(),
-            success: false,
-            duration_ms: 0,
-            error: Some(format!("Failed to get slot: {}", e)),
-        });
-    }
+    /// Program whose accounts the getProgramAccounts test queries
+    #[arg(long = "gpa-program", default_value = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA")]
+    gpa_program: Pubkey,
 
-    // Use a slot that's a bit older to ensure it's available
-    let slot = slot_result.unwrap().saturating_sub(10);
+    /// dataSize filter for the getProgramAccounts test
+    #[arg(long = "gpa-data-size")]
+    gpa_data_size: Option<u64>,
 
-    let start = Instant::now();
+    /// memcmp filter for the getProgramAccounts test as OFFSET:BASE58_BYTES (repeatable)
+    #[arg(long = "gpa-memcmp")]
+    gpa_memcmp: Vec<MemcmpFilter>,
 
-    // Use a custom config to handle transaction version
-    let config = RpcBlockConfig {
-        encoding: Some(UiTransactionEncoding::Base64),
-        transaction_details: None,
-        rewards: None,
-        commitment: None,
-        max_supported_transaction_version: Some(0),
-    };
+    /// dataSlice for the getProgramAccounts test as OFFSET:LENGTH
+    #[arg(long = "gpa-data-slice")]
+    gpa_data_slice: Option<DataSlice>,
 
-    let result = client.get_block_with_config(slot, config).await;
-    let duration = start.elapsed();
+    /// Query every account of --gpa-program, unsliced, instead of the sample wallet's token accounts
+    #[arg(long = "gpa-no-filters", conflicts_with_all = ["gpa_data_size", "gpa_memcmp", "gpa_data_slice"])]
+    gpa_no_filters: bool,
 
-    match result {
-        Ok(_) => Ok(TestResult {
-            name: "getBlock".to_string(),
-            success: true,
-            duration_ms: duration.as_millis(),
-            error: None,
-        }),
-        Err(e) => Ok(TestResult {
-            name: "getBlock".to_string(),
-            success: false,
-            duration_ms: duration.as_millis(),
-            error: Some(e.to_string()),
-        }),
-    }
-}
+    /// Vote account for getInflationReward (default: the highest-staked current one)
+    #[arg(long = "vote-account")]
+    vote_account: Option<Pubkey>,
 
-async fn test_get_token_accounts_by_owner(client: &RpcClient) -> Result<TestResult> {
-    // Using a known Solana address for testing
-    let address = Pubkey::from_str("SoLANAGZJPWXuWQiACz5JJzx1jZKp55FpbjLPwmxA").unwrap_or_default();
-    let token_program_id = Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
-
-    let start = Instant::now();
-    let result = client.get_token_accounts_by_owner(
-        &address,
-        TokenAccountsFilter::ProgramId(token_program_id)
-    ).await;
-    let duration = start.elapsed();
-
-    match result {
-        Ok(_) => Ok(TestResult {
-            name: "getTokenAccountsByOwner".to_string(),
-            success: true,
-            duration_ms: duration.as_millis(),
-            error: None,
-        }),
-        Err(e) => Ok(TestResult {
-            name: "getTokenAccountsByOwner".to_string(),
-            success: false,
-            duration_ms: duration.as_millis(),
-            error: Some(e.to_string()),
-        }),
-    }
-}
+    /// Stake account for getStakeActivation, which only runs when this is set
+    #[arg(long = "stake-account")]
+    stake_account: Option<Pubkey>,
 
-async fn test_get_health(url: &str) -> Result<TestResult> {
-    let client = Client::new();
-    let start = Instant::now();
-
-    let response = client
-        .post(url)
-        .json(&json!({
-            "jsonrpc": "2.0",
-            "id": 1,
-            "method": "getHealth",
-        }))
-        .send()
-        .await;
-
-    let duration = start.elapsed();
-
-    match response {
-        Ok(resp) => {
-            let json: Value = resp.json().await?;
-            if json["result"] == "ok" {
-                Ok(TestResult {
-                    name: "getHealth".to_string(),
-                    success: true,
-                    duration_ms: duration.as_millis(),
-                    error: None,
-                })
-            } else {
-                Ok(TestResult {
-                    name: "getHealth".to_string(),
-                    success: false,
-                    duration_ms: duration.as_millis(),
-                    error: Some(format!("Unexpected response: {:?}", json)),
-                })
-            }
-        },
-        Err(e) => Ok(TestResult {
-            name: "getHealth".to_string(),
-            success: false,
-            duration_ms: duration.as_millis(),
-            error: Some(e.to_string()),
-        }),
-    }
+    /// Scope getRecentPrioritizationFees to this writable account, e.g. a DEX program (repeatable)
+    #[arg(long = "fee-account")]
+    fee_accounts: Vec<Pubkey>,
+
+    /// Funded keypair file, pays for simulated and (with --enable-send) submitted transactions
+    #[arg(long)]
+    keypair: Option<PathBuf>,
+
+    /// Submit real self-transfers from --keypair to benchmark sendTransaction
+    #[arg(long = "enable-send", requires = "keypair")]
+    enable_send: bool,
+
+    /// Allow --enable-send against mainnet-beta, spending real SOL on fees
+    #[arg(long = "allow-mainnet-send", requires = "enable_send")]
+    allow_mainnet_send: bool,
+
+    /// Also fetch full jsonParsed blocks and every vote account to measure sustained MB/s
+    #[arg(long)]
+    bandwidth: bool,
+
+    /// Also benchmark the DAS API (getAsset, getAssetsByOwner, searchAssets) of enhanced endpoints
+    #[arg(long)]
+    das: bool,
+
+    /// Also benchmark this JSON-RPC method, e.g. getSignaturesForAddress
+    #[arg(long)]
+    method: Option<String>,
+
+    /// JSON params for --method, e.g. '["Vote111111111111111111111111111111111111111", {"limit": 10}]'
+    #[arg(long, requires = "method", value_parser = parse_json)]
+    params: Option<serde_json::Value>,
+
+    /// Also run the WebSocket (PubSub) tests
+    #[arg(long)]
+    websocket: bool,
+
+    /// PubSub URL for the WebSocket tests (default: derived from each --url)
+    #[arg(long = "ws-url")]
+    ws_url: Option<String>,
+
+    /// Notifications to time per WebSocket subscription test
+    #[arg(long = "ws-notifications", alias = "ws-slots", default_value_t = 20)]
+    ws_notifications: usize,
+
+    /// Account watched by the accountSubscribe test
+    #[arg(long = "ws-account", default_value = "SysvarC1ock11111111111111111111111111111111")]
+    ws_account: Pubkey,
+
+    /// Program watched by the programSubscribe test (default: Orca Whirlpool)
+    #[arg(long = "ws-program", default_value = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc")]
+    ws_program: Pubkey,
+
+    /// How long the programSubscribe test counts notifications
+    #[arg(long = "ws-window", value_parser = humantime::parse_duration, default_value = "10s")]
+    ws_window: Duration,
+
+    /// Stress the event stream: take logsSubscribe on all transactions for this long (e.g. 60s)
+    #[arg(long = "ws-stress", value_parser = humantime::parse_duration, requires = "websocket")]
+    ws_stress: Option<Duration>,
+
+    /// Probe how many subscriptions one WebSocket connection takes, doubling up to this many
+    #[arg(long = "ws-max-subscriptions", requires = "websocket")]
+    ws_max_subscriptions: Option<usize>,
+
+    /// Also benchmark this Yellowstone (Geyser gRPC) endpoint's slot and account streams
+    #[cfg(feature = "geyser")]
+    #[arg(long = "grpc-url")]
+    grpc_url: Option<String>,
+
+    /// x-token for the gRPC endpoint
+    #[cfg(feature = "geyser")]
+    #[arg(long = "grpc-token", env = "GEYSER_X_TOKEN", hide_env_values = true)]
+    grpc_token: Option<String>,
+
+    /// Race all endpoints with identical requests for this many rounds and report who answers first
+    #[arg(long)]
+    race: Option<usize>,
+
+    /// Simulate client failover down the endpoint list (in order) for this many rounds
+    #[arg(long)]
+    failover: Option<usize>,
+
+    /// How the primary fails in the simulated failover
+    #[arg(long = "failover-inject", value_enum, default_value_t = failover::InjectedFailure::Timeout, requires = "failover")]
+    failover_inject: failover::InjectedFailure,
+
+    /// Percent of failover rounds in which the primary fails
+    #[arg(long = "failover-rate", default_value_t = 50.0, requires = "failover")]
+    failover_rate: f64,
+
+    /// Client timeout per attempt in the failover simulation
+    #[arg(long = "failover-timeout", value_parser = humantime::parse_duration, default_value = "2s", requires = "failover")]
+    failover_timeout: Duration,
+
+    /// Fire this many batched getIdentity + getSlot requests at once to detect load-balanced backends
+    #[arg(long = "backend-burst")]
+    backend_burst: Option<usize>,
+
+    /// Fingerprint the backend this many times over one connection and over fresh ones to detect sticky sessions
+    #[arg(long = "sticky-check")]
+    sticky_check: Option<usize>,
+
+    /// Time getMultipleAccounts at batch sizes 1, 10, 50 and 100 and find the largest batch accepted
+    #[arg(long = "batch-scaling")]
+    batch_scaling: bool,
+
+    /// Sample each endpoint's slot against this endpoint during the run to measure how far behind it is
+    #[arg(long = "reference-url")]
+    reference_url: Option<String>,
+
+    /// Warn loudly if the endpoint turns out to serve a different cluster
+    #[arg(long = "expect-cluster", value_enum)]
+    expect_cluster: Option<node::Cluster>,
+
+    /// Report format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Leave per-iteration samples out of the JSON report, keeping only the aggregates
+    #[arg(long = "no-samples", action = clap::ArgAction::SetFalse)]
+    samples: bool,
+
+    /// Write the report to this file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Append the run and its samples to this SQLite history database
+    #[cfg(feature = "sqlite")]
+    #[arg(long)]
+    db: Option<PathBuf>,
+
+    /// Insert the run and its samples into this PostgreSQL database
+    #[cfg(feature = "postgres")]
+    #[arg(long = "pg-dsn", env = "RPC_CHECKER_PG_DSN", hide_env_values = true)]
+    pg_dsn: Option<String>,
+
+    /// Exit with status 1 unless this holds for every endpoint, e.g. 'getSlot.p95<200ms' or
+    /// 'overall.success_rate>99' (repeatable)
+    #[arg(long = "assert")]
+    assertions: Vec<report::assertions::Assertion>,
+
+    /// Average latency (ms) above which a method is WARNING with --format nagios
+    #[arg(long = "warn-latency", default_value_t = 600)]
+    warn_latency: u128,
+
+    /// Average latency (ms) above which a method is CRITICAL with --format nagios
+    #[arg(long = "crit-latency", default_value_t = 1000)]
+    crit_latency: u128,
+
+    /// Success rate (%) below which a method is WARNING with --format nagios
+    #[arg(long = "warn-success", default_value_t = 95.0)]
+    warn_success: f64,
+
+    /// Success rate (%) below which a method is CRITICAL with --format nagios
+    #[arg(long = "crit-success", default_value_t = 80.0)]
+    crit_success: f64,
+
+    /// Also write per-iteration results to this Parquet file
+    #[cfg(feature = "parquet")]
+    #[arg(long)]
+    parquet: Option<PathBuf>,
+
+    /// Record every HTTP request and response to this HAR file
+    #[arg(long)]
+    har: Option<PathBuf>,
+
+    /// Truncate request and response bodies in the HAR file to this many bytes
+    #[arg(long = "har-max-body", requires = "har")]
+    har_max_body: Option<usize>,
+
+    /// Also write Prometheus metrics to this file (for the node_exporter textfile collector)
+    #[arg(long = "prom-textfile")]
+    prom_textfile: Option<PathBuf>,
+
+    /// Push per-iteration measurements to this InfluxDB 2.x server
+    #[arg(long = "influx-url", requires = "influx_bucket")]
+    influx_url: Option<String>,
+
+    /// InfluxDB bucket to write to
+    #[arg(long = "influx-bucket", requires = "influx_url")]
+    influx_bucket: Option<String>,
+
+    /// InfluxDB organization
+    #[arg(long = "influx-org", env = "INFLUX_ORG")]
+    influx_org: Option<String>,
+
+    /// InfluxDB API token
+    #[arg(long = "influx-token", env = "INFLUX_TOKEN", hide_env_values = true)]
+    influx_token: Option<String>,
+
+    /// Send per-iteration timings to this StatsD/DogStatsD agent (host:port)
+    #[arg(long)]
+    statsd: Option<String>,
+
+    /// Prefix for StatsD metric names
+    #[arg(long = "statsd-prefix", default_value = "solana_rpc")]
+    statsd_prefix: String,
+
+    /// Export each iteration as a span to this OTLP/HTTP collector (e.g. http://localhost:4318)
+    #[arg(long = "otlp-endpoint", env = "OTEL_EXPORTER_OTLP_ENDPOINT")]
+    otlp_endpoint: Option<String>,
+
+    /// Push latency and availability items to this Zabbix server/trapper (host:port)
+    #[arg(long = "zabbix-server", requires = "zabbix_host")]
+    zabbix_server: Option<String>,
+
+    /// Host name the items belong to in Zabbix
+    #[arg(long = "zabbix-host")]
+    zabbix_host: Option<String>,
 }
 
-async fn run_test(
-    test_fn: fn(&RpcClient) -> futures::future::BoxFuture<'_, Result<TestResult>>,
-    client: &RpcClient,
-    iterations: usize,
-    test_name: &str,
-    progress_bar: &ProgressBar,
-) -> Vec<TestResult> {
-    let mut results = Vec::new();
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print a Grafana dashboard JSON for the exported metrics and exit
+    GrafanaDashboard {
+        /// Which metrics the dashboard queries
+        #[arg(long, value_enum, default_value_t = grafana::Datasource::Prometheus)]
+        datasource: grafana::Datasource,
+
+        /// InfluxDB bucket the measurements are written to
+        #[arg(long = "influx-bucket", default_value = "rpc")]
+        influx_bucket: String,
+    },
+    /// Find the cluster's RPC nodes in gossip and probe each one for a survey of public endpoints
+    Scan {
+        /// Endpoint whose getClusterNodes lists the nodes (default: the first --url)
+        #[arg(long)]
+        seed: Option<String>,
+
+        /// Nodes probed at the same time
+        #[arg(long, default_value_t = 32)]
+        concurrency: usize,
+
+        /// How long each node gets to answer
+        #[arg(long, value_parser = humantime::parse_duration, default_value = "3s")]
+        timeout: Duration,
+
+        /// Probe at most this many nodes
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Send requests at a fixed rate regardless of response times and report latency under load
+    Load {
+        /// Requests started per second
+        #[arg(
+            long,
+            required_unless_present_any = ["ramp", "profile_file", "concurrency"],
+            conflicts_with_all = ["ramp", "profile_file", "concurrency"],
+            value_parser = parse_rate
+        )]
+        rps: Option<f64>,
+
+        /// Closed loop instead: this many workers, each sending its next request as soon as the last one answered
+        #[arg(long, conflicts_with_all = ["ramp", "profile_file"])]
+        concurrency: Option<usize>,
+
+        /// How long to keep sending at --rps or --concurrency
+        #[arg(long, value_parser = humantime::parse_duration, default_value = "30s")]
+        duration: Duration,
+
+        /// Load profile of comma-separated stages, RATErps/DURATION or FROM-TOrps/DURATION (e.g. 0-500rps/5m)
+        #[arg(long, conflicts_with = "profile_file")]
+        ramp: Option<load::LoadProfile>,
+
+        /// File with one load profile stage per line
+        #[arg(long = "profile-file")]
+        profile_file: Option<PathBuf>,
+
+        /// Methods sent in rotation (repeatable)
+        #[arg(long = "method", default_value = "getSlot")]
+        methods: Vec<String>,
+
+        /// Weighted mix of methods as comma-separated METHOD=WEIGHT (e.g. getAccountInfo=50,getSlot=50)
+        #[arg(long, conflicts_with_all = ["methods", "mix_file"])]
+        mix: Option<load::Mix>,
+
+        /// File with one METHOD=WEIGHT pair of the mix per line
+        #[arg(long = "mix-file", conflicts_with = "methods")]
+        mix_file: Option<PathBuf>,
+
+        /// Named mix resembling an application's traffic
+        #[arg(long, value_enum, conflicts_with_all = ["methods", "mix", "mix_file"])]
+        workload: Option<load::Workload>,
+
+        /// Hold this many slot subscriptions during the run and report how their delivery degrades
+        #[arg(long = "ws-subscriptions")]
+        ws_subscriptions: Option<usize>,
+
+        /// How long the subscriptions are watched before the load starts, for comparison
+        #[arg(long = "ws-baseline", value_parser = humantime::parse_duration, default_value = "10s", requires = "ws_subscriptions")]
+        ws_baseline: Duration,
+    },
+    /// Run one method closed-loop at concurrency 1, 2, 4, … up to a maximum for a saturation curve
+    Sweep {
+        /// Highest concurrency level, always included
+        #[arg(long = "max-concurrency", default_value_t = 64)]
+        max_concurrency: usize,
+
+        /// How long each level runs
+        #[arg(long, value_parser = humantime::parse_duration, default_value = "10s")]
+        step: Duration,
+
+        /// Method swept
+        #[arg(long, default_value = "getSlot")]
+        method: String,
+    },
+    /// Binary-search the highest request rate that stays within error rate and p99 limits
+    Capacity {
+        /// Lowest rate tried
+        #[arg(long = "min-rps", default_value_t = 10.0)]
+        min_rps: f64,
+
+        /// Highest rate tried
+        #[arg(long = "max-rps", default_value_t = 1000.0)]
+        max_rps: f64,
+
+        /// How long each probed rate is held
+        #[arg(long, value_parser = humantime::parse_duration, default_value = "10s")]
+        step: Duration,
+
+        /// Most failed requests, in percent, a sustainable rate may have
+        #[arg(long = "max-error-pct", default_value_t = 1.0)]
+        max_error_pct: f64,
+
+        /// Highest p99 latency a sustainable rate may have
+        #[arg(long = "max-p99", value_parser = humantime::parse_duration, default_value = "1s")]
+        max_p99: Duration,
+
+        /// Most rates probed before settling
+        #[arg(long, default_value_t = 10)]
+        probes: usize,
+
+        /// Methods sent in rotation (repeatable)
+        #[arg(long = "method", default_value = "getSlot")]
+        methods: Vec<String>,
+    },
+    /// Raise the request rate until the endpoint throttles, then check how it recovers
+    RateLimit {
+        /// First rate tried
+        #[arg(long = "start-rps", default_value_t = 5.0)]
+        start_rps: f64,
+
+        /// Stop raising the rate here if no 429 came
+        #[arg(long = "max-rps", default_value_t = 1000.0)]
+        max_rps: f64,
+
+        /// Each step's rate over the previous one
+        #[arg(long, default_value_t = 1.5)]
+        growth: f64,
+
+        /// How long each rate is held
+        #[arg(long, value_parser = humantime::parse_duration, default_value = "5s")]
+        step: Duration,
+
+        /// How long to keep retrying for a success after backing off
+        #[arg(long = "recovery-window", value_parser = humantime::parse_duration, default_value = "30s")]
+        recovery_window: Duration,
+    },
+    /// Probe every endpoint each interval until interrupted, printing only health changes
+    Monitor(Box<MonitorArgs>),
+    /// Compare two JSON reports: p50, p95 and success rate per method, before and after
+    Diff {
+        /// Report to compare against, written with --format json
+        old: PathBuf,
+
+        /// Report compared
+        new: PathBuf,
+
+        /// Latency changes within this many percent count as unchanged
+        #[arg(long, default_value_t = 5.0)]
+        noise: f64,
+    },
+    /// Show per-method latency trends over the last runs stored in the --db history database
+    #[cfg(feature = "sqlite")]
+    History {
+        /// Runs per endpoint to show, newest last
+        #[arg(long, default_value_t = 20)]
+        runs: usize,
+    },
+    /// Run the suite against each endpoint and print a side-by-side table of methods × endpoints
+    Compare {
+        /// Endpoints to compare
+        #[arg(required = true, num_args = 2..)]
+        urls: Vec<Endpoint>,
+    },
+}
 
-    for i in 0..iterations {
-        progress_bar.set_message(format!("Running {} test {}/{}", test_name, i + 1, iterations));
+/// Options of the monitor subcommand, boxed since they outnumber every other subcommand's
+#[derive(clap::Args, Debug)]
+struct MonitorArgs {
+    /// Time between probe rounds
+    #[arg(long, value_parser = humantime::parse_duration, default_value = "30s")]
+    interval: Duration,
+
+    /// Span the rolling success rate and p95 cover
+    #[arg(long, value_parser = humantime::parse_duration, default_value = "5m")]
+    window: Duration,
+
+    /// Methods probed each round (repeatable)
+    #[arg(long = "method", default_values_t = monitor::DEFAULT_PROBES.map(String::from))]
+    methods: Vec<String>,
+
+    /// Success rate in percent below which a method is degraded
+    #[arg(long = "min-success", default_value_t = 95.0)]
+    min_success: f64,
+
+    /// p95 above which a method is degraded
+    #[arg(long = "max-p95", value_parser = humantime::parse_duration, default_value = "1s")]
+    max_p95: Duration,
+
+    /// Failed probes in a row before a method is down
+    #[arg(long = "fail-after", default_value_t = 3)]
+    fail_after: usize,
+
+    /// Standard deviations above its latency baseline at which a probe is a spike, 0 to disable
+    #[arg(long = "anomaly-z", default_value_t = 4.0)]
+    anomaly_z: f64,
+
+    /// Weight of each probe in the exponentially weighted latency baseline, between 0 and 1
+    #[arg(long = "ewma-alpha", value_parser = parse_alpha, default_value_t = 0.1)]
+    ewma_alpha: f64,
+
+    /// Stop after this many rounds
+    #[arg(long)]
+    rounds: Option<usize>,
+
+    /// Serve live metrics for Prometheus on this address, at /metrics (e.g. 0.0.0.0:9464)
+    #[arg(long = "metrics-addr")]
+    metrics_addr: Option<SocketAddr>,
+
+    /// POST every health change as JSON to this URL (repeatable)
+    #[arg(long = "webhook")]
+    webhooks: Vec<String>,
+
+    /// Post every health change to this Slack incoming webhook (repeatable)
+    #[arg(long = "slack-webhook")]
+    slack_webhooks: Vec<String>,
+
+    /// Post every health change to this Discord webhook as an embed, or with HOST=URL only
+    /// the changes of that endpoint (repeatable)
+    #[arg(long = "discord-webhook")]
+    discord_webhooks: Vec<alerts::discord::DiscordRoute>,
+
+    /// Open a PagerDuty incident per endpoint and method when it goes down or degraded,
+    /// resolved when it is up again, via the Events API v2 integration with this routing key
+    #[arg(long = "pagerduty-key", env = "PAGERDUTY_ROUTING_KEY", hide_env_values = true)]
+    pagerduty_key: Option<String>,
+
+    /// Mail alerts and digests
+    #[cfg(feature = "email")]
+    #[command(flatten)]
+    email: EmailArgs,
+
+    /// Least time between two alerts about the same method; changes in between are held back
+    #[arg(long = "alert-cooldown", value_parser = humantime::parse_duration, default_value = "5m")]
+    alert_cooldown: Duration,
+}
 
-        match test_fn(client).await {
-            Ok(result) => results.push(result),
-            Err(e) => results.push(TestResult {
-                name: test_name.to_string(),
-                success: false,
-                duration_ms: 0,
-                error: Some(e.to_string()),
-            }),
-        }
+/// SMTP options of the monitor subcommand
+#[cfg(feature = "email")]
+#[derive(clap::Args, Debug)]
+struct EmailArgs {
+    /// SMTP server to mail a digest through, and an alert whenever a method goes down
+    #[arg(long = "smtp-server", requires_all = ["email_from", "email_to"])]
+    smtp_server: Option<String>,
+
+    /// SMTP port (default: 587 for starttls, 465 for tls, 25 for none)
+    #[arg(long = "smtp-port")]
+    smtp_port: Option<u16>,
+
+    /// How the SMTP connection is secured
+    #[arg(long = "smtp-tls", value_enum, default_value_t = alerts::email::SmtpTls::Starttls)]
+    smtp_tls: alerts::email::SmtpTls,
+
+    /// SMTP login
+    #[arg(long = "smtp-user", env = "SMTP_USER")]
+    smtp_user: Option<String>,
+
+    /// SMTP password
+    #[arg(long = "smtp-password", env = "SMTP_PASSWORD", hide_env_values = true)]
+    smtp_password: Option<String>,
+
+    /// Sender of the mails
+    #[arg(long = "email-from")]
+    email_from: Option<String>,
+
+    /// Recipient of the mails (repeatable)
+    #[arg(long = "email-to")]
+    email_to: Vec<String>,
+
+    /// Time between two digests
+    #[arg(long = "digest-interval", value_parser = humantime::parse_duration, default_value = "1day")]
+    digest_interval: Duration,
+}
 
-        // Add a small delay between tests
-        sleep(Duration::from_millis(100)).await;
-        progress_bar.inc(1);
+#[cfg(feature = "email")]
+impl EmailArgs {
+    fn config(&self) -> Option<Arc<alerts::email::EmailConfig>> {
+        self.smtp_server.as_ref().map(|server| {
+            Arc::new(alerts::email::EmailConfig {
+                server: server.clone(),
+                port: self.smtp_port,
+                tls: self.smtp_tls,
+                user: self.smtp_user.clone(),
+                password: self.smtp_password.clone(),
+                from: self.email_from.clone().unwrap_or_default(),
+                to: self.email_to.clone(),
+            })
+        })
     }
+}
 
-    results
+/// Used when neither --url, --preset nor --url-file names an endpoint
+const DEFAULT_URL: &str = "https://api.mainnet-beta.solana.com";
+
+/// What run_endpoint measures: the suite, or one of the load subcommands in its place
+enum Mode {
+    Suite,
+    Load(load::LoadProfile),
+    /// Workers and how long they run
+    ClosedLoop(usize, Duration),
+    Sweep(load::SweepConfig),
+    Capacity(load::CapacityConfig),
+    RateLimit(rate_limit::ProbeConfig),
 }
 
-fn get_speed_rating(duration_ms: u128) -> (&'static str, &'static str) {
-    match duration_ms {
-        0..=100 => ("Excellent", "bright_green"),
-        101..=300 => ("Good", "green"),
-        301..=600 => ("Average", "yellow"),
-        601..=1000 => ("Slow", "yellow"),
-        _ => ("Very Slow", "red"),
+/// Load profile stages from a file, one per line, skipping blank lines and `#` comments
+fn read_profile(path: &Path) -> Result<load::LoadProfile> {
+    let contents = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut stages = Vec::new();
+    for (n, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        stages.push(line.parse().map_err(|e: String| anyhow!("{} line {}: {}", path.display(), n + 1, e))?);
+    }
+    if stages.is_empty() {
+        bail!("{} lists no load profile stages", path.display());
     }
+    Ok(load::LoadProfile { stages })
 }
 
-fn print_test_summary(results: &[TestResult]) {
-    if results.is_empty() {
-        println!("No test results to display.");
-        return;
+/// A workload mix from a file, one `METHOD=WEIGHT` pair per line, skipping blank lines and `#` comments
+fn read_mix(path: &Path) -> Result<load::Mix> {
+    let contents = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut weights = Vec::new();
+    for (n, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mix: load::Mix = line.parse().map_err(|e: String| anyhow!("{} line {}: {}", path.display(), n + 1, e))?;
+        weights.extend(mix.weights);
+    }
+    if weights.is_empty() {
+        bail!("{} lists no methods", path.display());
     }
+    Ok(load::Mix { weights })
+}
 
-    // Group results by test name
-    let mut grouped_results: std::collections::HashMap<String, Vec<&TestResult>> = std::collections::HashMap::new();
-    for result in results {
-        grouped_results
-            .entry(result.name.clone())
-            .or_insert_with(Vec::new)
-            .push(result);
+/// Endpoints from the compare subcommand, or from --url, --preset and --url-file, in the order given
+fn endpoints(args: &Args) -> Result<Vec<Endpoint>> {
+    if let Some(Command::Compare { urls }) = &args.command {
+        return Ok(urls.clone());
     }
 
-    // Calculate overall stats
-    let total_tests = results.len();
-    let successful_tests = results.iter().filter(|r| r.success).count();
-    let overall_success_rate = (successful_tests as f64 / total_tests as f64) * 100.0;
+    let mut urls = args.urls.clone();
+    for preset in &args.preset {
+        for url in preset.urls() {
+            urls.push(url.parse().map_err(|e: String| anyhow!(e))?);
+        }
+    }
 
-    let overall_avg_duration = if successful_tests > 0 {
-        results.iter().filter(|r| r.success).map(|r| r.duration_ms).sum::<u128>() / successful_tests as u128
-    } else {
-        0
-    };
+    if let Some(path) = &args.url_file {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read endpoints from {}", path.display()))?;
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let endpoint = line.parse().map_err(|e| anyhow!("{} line {}: {}", path.display(), number + 1, e))?;
+            urls.push(endpoint);
+        }
+        if urls.is_empty() {
+            bail!("{} does not list any endpoints", path.display());
+        }
+    }
 
-    // Get overall speed rating
-    let (speed_rating, rating_color) = get_speed_rating(overall_avg_duration);
+    if urls.is_empty() {
+        urls.push(DEFAULT_URL.parse().map_err(|e: String| anyhow!(e))?);
+    }
+    Ok(urls)
+}
 
-    // Print header with fancy box
-    println!("\n{}", "╔═══════════════════════════════════════════════════════════════╗".bright_blue());
-    println!("{}", "║                      RPC PERFORMANCE REPORT                    ║".bright_blue());
-    println!("{}", "╚═══════════════════════════════════════════════════════════════╝".bright_blue());
+/// The getProgramAccounts query of the --gpa flags, the sample wallet's token accounts unless
+/// a filter or slice is given or --gpa-no-filters asks for the bare query
+fn program_accounts_query(args: &Args) -> ProgramAccountsQuery {
+    let custom = args.gpa_data_size.is_some() || !args.gpa_memcmp.is_empty() || args.gpa_data_slice.is_some();
+    if !custom && !args.gpa_no_filters {
+        return ProgramAccountsQuery::sample_token_accounts(args.gpa_program);
+    }
+    ProgramAccountsQuery {
+        program: args.gpa_program,
+        data_size: args.gpa_data_size,
+        memcmp: args.gpa_memcmp.clone(),
+        data_slice: args.gpa_data_slice,
+    }
+}
 
-    println!("{}", format!("📊 Timestamp: {}", Utc::now()).dimmed());
-    println!("{}", format!("🔍 Overall Success Rate: {:.1}%", overall_success_rate).color(
-        if overall_success_rate == 100.0 { "bright_green" }
-        else if overall_success_rate >= 80.0 { "green" }
-        else if overall_success_rate >= 50.0 { "yellow" }
-        else { "red" }
-    ));
-    println!("{}", format!("⚡ Overall Speed Rating: {} ({} ms avg)",
-        speed_rating, overall_avg_duration).color(rating_color));
-    println!();
+fn parse_json(s: &str) -> Result<serde_json::Value, String> {
+    serde_json::from_str(s).map_err(|e| format!("invalid JSON: {}", e))
+}
 
-    // Print divider
-    println!("{}", "─".repeat(65).dimmed());
-    println!();
+fn parse_rate(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(rate) if rate.is_finite() && rate > 0.0 => Ok(rate),
+        _ => Err(format!("'{}' is not a positive request rate", s)),
+    }
+}
 
-    // Sort tests by average duration (fastest first)
-    let mut sorted_tests: Vec<_> = grouped_results.iter().collect();
-    sorted_tests.sort_by(|(_, a_results), (_, b_results)| {
-        let a_avg = if !a_results.is_empty() && a_results.iter().any(|r| r.success) {
-            a_results.iter().filter(|r| r.success).map(|r| r.duration_ms).sum::<u128>() /
-            a_results.iter().filter(|r| r.success).count() as u128
-        } else { u128::MAX };
-
-        let b_avg = if !b_results.is_empty() && b_results.iter().any(|r| r.success) {
-            b_results.iter().filter(|r| r.success).map(|r| r.duration_ms).sum::<u128>() /
-            b_results.iter().filter(|r| r.success).count() as u128
-        } else { u128::MAX };
-
-        a_avg.cmp(&b_avg)
-    });
-
-    for (test_name, test_results) in sorted_tests {
-        let success_count = test_results.iter().filter(|r| r.success).count();
-        let total_count = test_results.len();
-        let success_rate = (success_count as f64 / total_count as f64) * 100.0;
-
-        let avg_duration: u128 = if success_count > 0 {
-            test_results.iter().filter(|r| r.success).map(|r| r.duration_ms).sum::<u128>() / success_count as u128
-        } else {
-            0
-        };
+fn parse_alpha(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(alpha) if alpha > 0.0 && alpha <= 1.0 => Ok(alpha),
+        _ => Err(format!("'{}' is not a weight above 0 and at most 1", s)),
+    }
+}
 
-        let min_duration = test_results
-            .iter()
-            .filter(|r| r.success)
-            .map(|r| r.duration_ms)
-            .min()
-            .unwrap_or(0);
+/// Job summary and annotations when running as a GitHub Actions step
+fn report_to_github_actions(report: &Report, interactive: bool) -> Result<()> {
+    if let Some(path) = std::env::var_os("GITHUB_STEP_SUMMARY") {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .context("Failed to open $GITHUB_STEP_SUMMARY")?;
+        writeln!(file, "{}", report.render(OutputFormat::Markdown)?)?;
+    }
 
-        let max_duration = test_results
-            .iter()
-            .filter(|r| r.success)
-            .map(|r| r.duration_ms)
-            .max()
-            .unwrap_or(0);
-
-        let status_color = if success_rate == 100.0 {
-            "bright_green"
-        } else if success_rate >= 80.0 {
-            "green"
-        } else if success_rate >= 50.0 {
-            "yellow"
+    // Workflow commands are read from stderr too, which keeps structured stdout clean
+    for line in report::github::annotations(report) {
+        if interactive {
+            println!("{}", line);
         } else {
-            "red"
-        };
+            eprintln!("{}", line);
+        }
+    }
 
-        let (speed_rating, rating_color) = get_speed_rating(avg_duration);
+    Ok(())
+}
 
-        println!(
-            "🔹 {} {} ({}/{})",
-            test_name.bold(),
-            format!("{:.1}%", success_rate).color(status_color),
-            success_count,
-            total_count
-        );
+/// Write via a temporary file and rename, so collectors never read a partial file
+fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
 
-        if success_count > 0 {
-            println!(
-                "  ⏱️  Response time: avg {}ms | min {}ms | max {}ms",
-                avg_duration.to_string().cyan(),
-                min_duration.to_string().green(),
-                max_duration.to_string().yellow()
-            );
-            println!(
-                "  💨 Speed rating: {}",
-                speed_rating.color(rating_color)
-            );
-        }
+    std::fs::write(&tmp, contents).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to move {} into place", path.display()))?;
 
-        // Print errors if any
-        for result in test_results.iter().filter(|r| !r.success) {
-            if let Some(error) = &result.error {
-                println!("  ❌ {}: {}", "Error".red(), error);
-            }
-        }
+    Ok(())
+}
 
+fn print_welcome_screen() {
+    if style::is_plain() {
+        println!("SOLANA RPC PERFORMANCE CHECKER v1.0.0");
         println!();
+        return;
     }
 
-    // Print footer
-    println!("{}", "═".repeat(65).bright_blue());
-    println!("{}", "Thank you for using Solana RPC Performance Checker!".bright_green());
-    println!("{}", "Created by Nitriot (@nitriotsol) | Twitter | Telegram: vitualsolana | Discord: nitriot".dimmed());
-}
-
-fn print_welcome_screen() {
     println!("{}", "╔═══════════════════════════════════════════════════════════════╗".bright_blue());
     println!("{}", "║                                                               ║".bright_blue());
     println!("{}", "║                SOLANA RPC PERFORMANCE CHECKER                 ║".bright_blue());
@@ -446,8 +835,12 @@ fn print_welcome_screen() {
     println!("{}", "Version 1.0.0 | Created by Nitriot (@nitriotsol)".bright_green());
     println!();
     println!("{}", "This tool will test various RPC methods and provide detailed performance metrics.".cyan());
-    println!("{}", "Tests include: getLatestBlockhash, getSlot, getBalance, getAccountInfo,".cyan());
-    println!("{}", "getBlock, getTokenAccountsByOwner, and getHealth.".cyan());
+    println!("{}", "Tests include: getLatestBlockhash, getSlot, getBalance (single and batched), getAccountInfo,".cyan());
+    println!("{}", "getBlock, getTokenAccountsByOwner, getTokenAccountBalance, getProgramAccounts,".cyan());
+    println!("{}", "getRecentPerformanceSamples, getSupply, getInflationRate, getInflationReward,".cyan());
+    println!("{}", "getStakeMinimumDelegation, getFirstAvailableBlock, minimumLedgerSlot,".cyan());
+    println!("{}", "getHighestSnapshotSlot, getGenesisHash, getRecentPrioritizationFees,".cyan());
+    println!("{}", "getEpochSchedule, getIdentity, simulateTransaction and getHealth.".cyan());
     println!();
     println!("{}", "Starting tests in 2 seconds...".green());
 
@@ -457,42 +850,203 @@ fn print_welcome_screen() {
     println!();
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let args = Args::parse();
+/// Sending costs fees, so it is limited to clusters known not to be mainnet unless overridden
+fn check_send_allowed(args: &Args, cluster: Option<&node::ClusterInfo>) -> Result<()> {
+    if !args.enable_send || args.allow_mainnet_send {
+        return Ok(());
+    }
+    match cluster {
+        Some(info) if info.cluster != Some(node::Cluster::MainnetBeta) => Ok(()),
+        Some(_) => bail!("--enable-send refuses to spend mainnet SOL without --allow-mainnet-send"),
+        None => bail!("--enable-send needs the cluster to be identified (getGenesisHash failed)"),
+    }
+}
 
-    // Show welcome screen
-    print_welcome_screen();
+/// Probe one endpoint, run the whole suite against it and build its report
+async fn run_endpoint(
+    args: &Args,
+    endpoint: &Endpoint,
+    settings: &TestSettings,
+    har: Option<har::HarRecorder>,
+    verbose: bool,
+) -> Result<Report> {
+    let url = endpoint.url.as_str();
+    let node = match node::probe(endpoint).await {
+        Ok(node) => Some(node),
+        Err(e) => {
+            if verbose {
+                eprintln!("getVersion failed: {}", e);
+            }
+            None
+        }
+    };
 
-    // Print test configuration
-    println!("{}", "╔═══════════════════════════════════════════════════════════════╗".bright_blue());
-    println!("{}", "║                   TEST CONFIGURATION                          ║".bright_blue());
-    println!("{}", "╚═══════════════════════════════════════════════════════════════╝".bright_blue());
-    println!("🔗 RPC endpoint: {}", args.url.cyan());
-    println!("🔄 Iterations per test: {}", args.iterations.to_string().yellow());
-    println!("⚙️  Mode: {}", if args.parallel { "Parallel".green() } else { "Sequential".yellow() });
-    println!();
-    println!("{}", "Starting tests now...".green());
-    println!();
+    // Network throughput gives context to the latencies, it is not part of the score
+    let network = node::network_tps(endpoint).await.ok();
+    let ledger = node::ledger_depth(endpoint).await.ok();
+    let cluster = node::identify_cluster(endpoint).await.ok();
+    check_send_allowed(args, cluster.as_ref())?;
+
+    let priority_fees = node::priority_fees(endpoint, &args.fee_accounts).await.ok();
+    let backends = match args.backend_burst {
+        Some(requests) => Some(backends::detect(endpoint, requests).await),
+        None => None,
+    };
+    let stickiness = match args.sticky_check {
+        Some(requests) => Some(backends::stickiness(endpoint, requests).await),
+        None => None,
+    };
+    let batch_scaling = if args.batch_scaling {
+        match batch::scaling(endpoint, args.iterations).await {
+            Ok(scaling) => Some(scaling),
+            Err(e) => {
+                if verbose {
+                    eprintln!("Batch scaling test failed: {}", e);
+                }
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Goes to stderr regardless of --quiet: benchmarking the wrong cluster makes every number moot
+    if let Some(expected) = args.expect_cluster {
+        let detected = cluster.as_ref().map(|c| c.describe()).unwrap_or_else(|| "unknown (getGenesisHash failed)".to_string());
+        if cluster.as_ref().and_then(|c| c.cluster) != Some(expected) {
+            eprintln!(
+                "{}",
+                format!("{}Expected {} but {} serves {}!", icon("🚨"), expected, url, detected).red().bold()
+            );
+        }
+    }
 
-    let client = RpcClient::new(args.url.clone());
+    if verbose {
+        println!("{}RPC endpoint: {}", icon("🔗"), url.cyan());
+        if let Some(node) = &node {
+            println!("{}Node version: {}", icon("🧬"), node.describe().cyan());
+            if let Some(identity) = &node.identity {
+                println!("{}Node identity: {}", icon("🪪"), identity.cyan());
+            }
+        }
+        if let Some(cluster) = &cluster {
+            println!("{}Cluster: {}", icon("🪐"), cluster.describe().cyan());
+        }
+        println!();
+        println!("{}", "Starting tests now...".green());
+        println!();
+    }
+
+    // Measured before the run so pooled connections can't hide the setup cost
+    let connection = if args.connection_timing {
+        match phases::measure(url).await {
+            Ok(timing) => Some(timing),
+            Err(e) => {
+                eprintln!("Connection timing failed: {:#}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
 
-    // Define all the tests
-    let tests: Vec<(&str, fn(&RpcClient) -> futures::future::BoxFuture<'_, Result<TestResult>>)> = vec![
-        ("getLatestBlockhash", |client| Box::pin(test_get_latest_blockhash(client))),
-        ("getSlot", |client| Box::pin(test_get_slot(client))),
-        ("getBalance", |client| Box::pin(test_get_balance(client))),
-        ("getAccountInfo", |client| Box::pin(test_get_account_info(client))),
-        ("getBlock", |client| Box::pin(test_get_block(client))),
-        ("getTokenAccountsByOwner", |client| Box::pin(test_get_token_accounts_by_owner(client))),
-    ];
+    let show_progress = args.progress && !args.quiet;
 
-    let mut all_results = Vec::new();
+    let mut settings = settings.clone();
+    // Plain RPC nodes don't implement DAS, which is a reason to skip rather than fail
+    if settings.das {
+        let ctx = TestContext::new(endpoint, None, rate_limit::RateLimitTracker::default(), Arc::new(settings.clone()));
+        if !rpc_tests::supports_das(&ctx).await {
+            settings.das = false;
+            if verbose {
+                println!("{}DAS API not supported by this endpoint, skipping its tests", icon("⏭️ "));
+            }
+        }
+    }
+
+    // The deadline covers the endpoint's whole run, so start counting before any test
+    let options = RunOptions {
+        iterations: args.iterations,
+        duration: args.duration,
+        warmup: args.warmup,
+        echo: verbose && !show_progress && !args.parallel,
+        deadline: args.max_duration.map(|d| tokio::time::Instant::now() + d),
+        stream: args.stream,
+        endpoint: endpoint.clone(),
+        har,
+        rate_limits: rate_limit::RateLimitTracker::default(),
+        settings: Arc::new(settings),
+    };
+    let mut tests = rpc_tests::all_tests(&options.settings);
+    let mode = match &args.command {
+        Some(Command::Load { rps, concurrency, duration, ramp, profile_file, methods, mix, mix_file, workload, .. }) => {
+            tests = match (mix, mix_file, workload) {
+                (Some(mix), _, _) => load::select_mix(&tests, mix)?,
+                (_, Some(path), _) => load::select_mix(&tests, &read_mix(path)?)?,
+                (_, _, Some(workload)) => load::select_mix(&tests, &workload.mix())?,
+                _ => load::select(&tests, methods)?,
+            };
+            match (rps, concurrency, ramp, profile_file) {
+                (Some(rps), _, _, _) => Mode::Load(load::LoadProfile::constant(*rps, *duration)),
+                (_, Some(workers), _, _) => Mode::ClosedLoop((*workers).max(1), *duration),
+                (_, _, Some(ramp), _) => Mode::Load(ramp.clone()),
+                (_, _, _, Some(path)) => Mode::Load(read_profile(path)?),
+                _ => unreachable!("clap requires --rps, --concurrency, --ramp or --profile-file"),
+            }
+        }
+        Some(Command::Sweep { max_concurrency, step, method }) => {
+            tests = load::select(&tests, std::slice::from_ref(method))?;
+            Mode::Sweep(load::SweepConfig { max_concurrency: *max_concurrency, step: *step })
+        }
+        Some(Command::Capacity { min_rps, max_rps, step, max_error_pct, max_p99, probes, methods }) => {
+            if !(*min_rps > 0.0 && min_rps < max_rps) {
+                bail!("--min-rps must be positive and below --max-rps");
+            }
+            tests = load::select(&tests, methods)?;
+            Mode::Capacity(load::CapacityConfig {
+                min_rps: *min_rps,
+                max_rps: *max_rps,
+                step: *step,
+                max_error_pct: *max_error_pct,
+                max_p99_ms: max_p99.as_millis(),
+                max_probes: *probes,
+            })
+        }
+        Some(Command::RateLimit { start_rps, max_rps, growth, step, recovery_window }) => {
+            if !(*start_rps > 0.0 && *growth > 1.0) {
+                bail!("--start-rps must be positive and --growth above 1");
+            }
+            Mode::RateLimit(rate_limit::ProbeConfig {
+                start_rps: *start_rps,
+                max_rps: *max_rps,
+                growth: *growth,
+                step: *step,
+                recovery_window: *recovery_window,
+            })
+        }
+        _ => Mode::Suite,
+    };
 
     // Only show progress bar if requested
-    if args.progress {
-        // Create a progress bar
-        let total_tests = tests.len() * args.iterations + args.iterations; // +args.iterations for getHealth
+    let pb = if show_progress && args.duration.is_some() {
+        // A duration-based run has no known total, so count iterations without a bar
+        let pb = ProgressBar::new_spinner();
+        pb.set_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner:.green} [{elapsed_precise}] {pos} iterations {msg}")
+                .unwrap(),
+        );
+        pb.enable_steady_tick(Duration::from_millis(100));
+        pb
+    } else if show_progress {
+        let total_tests = match &mode {
+            Mode::Suite => tests.len() * args.iterations,
+            Mode::Load(profile) => profile.planned(),
+            Mode::ClosedLoop(_, duration) => duration.as_secs() as usize,
+            Mode::Sweep(sweep) => sweep.levels().len(),
+            Mode::Capacity(capacity) => capacity.max_probes,
+            Mode::RateLimit(probe) => probe.rates().len(),
+        };
         let pb = ProgressBar::new(total_tests as u64);
         pb.set_style(
             ProgressStyle::default_bar()
@@ -500,192 +1054,523 @@ async fn main() -> Result<()> {
                 .unwrap()
                 .progress_chars("█▓▒░"),
         );
+        pb
+    } else {
+        if verbose {
+            println!("Running tests...");
+        }
+        ProgressBar::hidden()
+    };
 
-        if args.parallel {
-            // Run tests in parallel
-            let mut futures = Vec::new();
-
-            for (test_name, test_fn) in tests {
-                let client_clone = RpcClient::new(args.url.clone());
-                let test_name_clone = test_name.to_string();
-                let pb_clone = pb.clone();
-                let iterations = args.iterations;
-
-                futures.push(tokio::spawn(async move {
-                    run_test(
-                        test_fn,
-                        &client_clone,
-                        iterations,
-                        &test_name_clone,
-                        &pb_clone,
-                    ).await
-                }));
-            }
+    // Sampled alongside the tests, so the lag reflects the endpoint under load
+    let slot_lag = args.reference_url.as_deref().map(|reference| node::spawn_slot_lag(endpoint, reference));
+
+    let mut throughput = Vec::new();
+    let mut subscription_limit = None;
+    let ws_url = endpoint.ws_url.clone().or_else(|| args.ws_url.clone()).unwrap_or_else(|| ws::derive_url(url));
+
+    // Subscriptions are watched on their own first, so their delivery under load has a reference
+    let stream_monitor = match &args.command {
+        Some(Command::Load { ws_subscriptions: Some(subscriptions), ws_baseline, .. }) => {
+            pb.set_message(format!("Opening {} subscriptions on {}", subscriptions, ws_url));
+            let monitor = ws::StreamMonitor::start(&ws_url, *subscriptions)
+                .await
+                .with_context(|| format!("Failed to open {} subscriptions", subscriptions))?;
+            let started = std::time::Instant::now();
+            pb.set_message(format!("Watching {} subscriptions before the load", subscriptions));
+            tokio::time::sleep(*ws_baseline).await;
+            Some((monitor, started))
+        }
+        _ => None,
+    };
+    let load_start = std::time::Instant::now();
+
+    let mut load_summary = None;
+    let mut sweep_curve = None;
+    let mut capacity_search = None;
+    let mut rate_limit_probe = None;
+    let mut closed_loop_summary = None;
+    let mut all_results = match &mode {
+        Mode::Suite => runner::run_suite(tests, args.parallel, &options, &pb).await,
+        Mode::Load(profile) => {
+            let (results, summary) = load::run(&tests, profile, &options, &pb).await;
+            load_summary = Some(summary);
+            results
+        }
+        Mode::ClosedLoop(workers, duration) => {
+            let (results, summary) = load::run_closed(&tests, *workers, *duration, &options, &pb).await;
+            closed_loop_summary = Some(summary);
+            results
+        }
+        Mode::Sweep(sweep) => {
+            let (results, curve) = load::sweep(tests[0].clone(), *sweep, &options, &pb).await;
+            sweep_curve = Some(curve);
+            results
+        }
+        Mode::Capacity(capacity) => {
+            let (results, search) = load::capacity(&tests, *capacity, &options, &pb).await;
+            capacity_search = Some(search);
+            results
+        }
+        Mode::RateLimit(config) => {
+            let (results, probe) = rate_limit::probe(endpoint, *config, &pb).await;
+            rate_limit_probe = Some(probe);
+            results
+        }
+    };
 
-            // Also test getHealth separately since it uses a different client
-            let url_clone = args.url.clone();
-            let pb_clone = pb.clone();
-            let iterations = args.iterations;
-            futures.push(tokio::spawn(async move {
-                let mut results = Vec::new();
-                for i in 0..iterations {
-                    pb_clone.set_message(format!("Running getHealth test {}/{}", i + 1, iterations));
-                    match test_get_health(&url_clone).await {
-                        Ok(result) => results.push(result),
-                        Err(e) => results.push(TestResult {
-                            name: "getHealth".to_string(),
-                            success: false,
-                            duration_ms: 0,
-                            error: Some(e.to_string()),
-                        }),
-                    }
-                    sleep(Duration::from_millis(100)).await;
-                    pb_clone.inc(1);
-                }
-                results
-            }));
+    let streaming_under_load = match stream_monitor {
+        Some((monitor, started)) => Some(monitor.finish(started, load_start, std::time::Instant::now()).await),
+        None => None,
+    };
 
-            // Wait for all tests to complete
-            let results = join_all(futures).await;
+    if args.websocket {
+        pb.set_message(format!("Running WebSocket tests against {}", ws_url));
+        if options.echo {
+            println!("Running WebSocket tests against {}...", ws_url);
+        }
+        all_results.extend(ws::slot_subscribe(&ws_url, args.ws_notifications).await);
+        all_results.extend(ws::account_subscribe(&ws_url, &args.ws_account.to_string(), args.ws_notifications).await);
+        all_results.extend(ws::block_subscribe(&ws_url, endpoint, args.ws_notifications).await);
+        let (results, program_throughput) =
+            ws::program_subscribe(&ws_url, &args.ws_program.to_string(), args.ws_window).await;
+        all_results.extend(results);
+        throughput.extend(program_throughput);
+        all_results.extend(ws::reconnect(&ws_url).await);
+        if let Some(duration) = args.ws_stress {
+            let (results, stress_throughput) = ws::logs_stress(&ws_url, duration).await;
+            all_results.extend(results);
+            throughput.extend(stress_throughput);
+        }
+        if let Some(max) = args.ws_max_subscriptions {
+            subscription_limit = Some(ws::subscription_limit(&ws_url, max).await);
+        }
+    }
 
-            // Collect results
-            for result in results {
-                match result {
-                    Ok(test_results) => all_results.extend(test_results),
-                    Err(e) => eprintln!("Error running test: {}", e),
-                }
-            }
-        } else {
-            // Run tests sequentially
-            for (test_name, test_fn) in tests {
-                let results = run_test(
-                    test_fn,
-                    &client,
-                    args.iterations,
-                    test_name,
-                    &pb,
-                ).await;
-
-                all_results.extend(results);
-            }
+    #[cfg(feature = "geyser")]
+    if let Some(grpc_url) = &args.grpc_url {
+        let config = geyser::GeyserConfig { url: grpc_url.clone(), token: args.grpc_token.clone() };
+        pb.set_message(format!("Running gRPC tests against {}", grpc_url));
+        if options.echo {
+            println!("Running gRPC tests against {}...", grpc_url);
+        }
+        let (results, account_throughput) =
+            geyser::benchmark(&config, args.ws_notifications, &args.ws_program.to_string(), args.ws_window).await;
+        all_results.extend(results);
+        throughput.extend(account_throughput);
+    }
 
-            // Also test getHealth
-            for i in 0..args.iterations {
-                pb.set_message(format!("Running getHealth test {}/{}", i + 1, args.iterations));
-                match test_get_health(&args.url).await {
-                    Ok(result) => all_results.push(result),
-                    Err(e) => all_results.push(TestResult {
-                        name: "getHealth".to_string(),
-                        success: false,
-                        duration_ms: 0,
-                        error: Some(e.to_string()),
-                    }),
-                }
-                sleep(Duration::from_millis(100)).await;
-                pb.inc(1);
-            }
+    let slot_lag = match slot_lag {
+        Some((stop, handle)) => {
+            let _ = stop.send(());
+            handle.await.ok().flatten()
         }
+        None => None,
+    };
 
+    if show_progress {
         pb.finish_with_message("Testing completed!");
-    } else {
-        // Run without progress bar
-        println!("Running tests...");
-
-        if args.parallel {
-            // Run tests in parallel
-            let mut futures = Vec::new();
-
-            for (test_name, test_fn) in tests {
-                let client_clone = RpcClient::new(args.url.clone());
-                let iterations = args.iterations;
-
-                futures.push(tokio::spawn(async move {
-                    let mut results = Vec::new();
-                    for _i in 0..iterations {
-                        match test_fn(&client_clone).await {
-                            Ok(result) => results.push(result),
-                            Err(e) => results.push(TestResult {
-                                name: test_name.to_string(),
-                                success: false,
-                                duration_ms: 0,
-                                error: Some(e.to_string()),
-                            }),
-                        }
-                        sleep(Duration::from_millis(100)).await;
-                    }
-                    results
-                }));
+    } else if verbose {
+        println!("Testing completed!                                ");
+    }
+
+    let mut report = Report::new(
+        EndpointInfo { url: url.to_string() },
+        RunConfig {
+            mode: match mode {
+                Mode::Suite if args.parallel => "parallel",
+                Mode::Suite => "sequential",
+                Mode::Load(_) => "open-loop",
+                Mode::ClosedLoop(..) => "closed-loop",
+                Mode::Sweep(_) => "sweep",
+                Mode::Capacity(_) => "capacity",
+                Mode::RateLimit(_) => "rate-limit",
+            },
+            iterations: args.iterations,
+            duration_ms: args.duration.map(|d| d.as_millis()),
+            warmup: args.warmup,
+            max_duration_ms: args.max_duration.map(|d| d.as_millis()),
+            speed_thresholds: args.rating_thresholds,
+            nagios: report::nagios::Thresholds {
+                warn_latency_ms: args.warn_latency,
+                crit_latency_ms: args.crit_latency,
+                warn_success: args.warn_success,
+                crit_success: args.crit_success,
+            },
+            rating_metric: args.rating_metric,
+            score_weights: args.score_weights,
+            outlier_mads: args.outlier_mads,
+            trim_pct: args.trim_pct,
+        },
+        &all_results,
+    );
+    report.connection = connection;
+    report.node = node;
+    report.network = network;
+    report.ledger = ledger;
+    report.cluster = cluster;
+    report.backends = backends;
+    report.stickiness = stickiness;
+    report.batch_scaling = batch_scaling;
+    report.slot_lag = slot_lag;
+    report.load = load_summary;
+    report.closed_loop = closed_loop_summary;
+    report.streaming_under_load = streaming_under_load;
+    report.bandwidth = report
+        .tests
+        .iter()
+        .filter(|test| rpc_tests::BANDWIDTH_TESTS.contains(&test.name.as_str()))
+        .filter_map(report::Bandwidth::from_stats)
+        .collect();
+    report.sweep = sweep_curve;
+    report.capacity = capacity_search;
+    report.rate_limit_probe = rate_limit_probe;
+    report.priority_fees = priority_fees;
+    report.expected_cluster = args.expect_cluster;
+    report.rate_limits = options.rate_limits.summary();
+    report.throughput = throughput;
+    report.subscription_limit = subscription_limit;
+    report.include_samples = args.samples;
+
+    Ok(report)
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+
+    if let Some(Command::GrafanaDashboard { datasource, influx_bucket }) = &args.command {
+        println!("{}", serde_json::to_string_pretty(&grafana::dashboard(*datasource, influx_bucket))?);
+        return Ok(());
+    }
+
+    if let Some(Command::Diff { old, new, noise }) = &args.command {
+        style::set_plain(args.plain || !std::io::stdout().is_terminal());
+        let diffs = report::diff::diff(&report::diff::load(old)?, &report::diff::load(new)?)?;
+        match args.format {
+            OutputFormat::Text => print!("{}", report::diff::render(&diffs, *noise)),
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&diffs)?),
+            other => bail!("diff prints text or json, not {:?}", other),
+        }
+        return Ok(());
+    }
+
+    #[cfg(feature = "sqlite")]
+    if let Some(Command::History { runs }) = &args.command {
+        style::set_plain(args.plain || !std::io::stdout().is_terminal());
+        let path = args.db.as_ref().ok_or_else(|| anyhow!("history reads the runs stored with --db, pass its path"))?;
+        let endpoints: Vec<String> = args.urls.iter().map(|endpoint| endpoint.url.clone()).collect();
+        let stored = sinks::sqlite::history(path, &endpoints, *runs)?;
+        if stored.is_empty() {
+            bail!("{} holds no runs{}", path.display(), if endpoints.is_empty() { "" } else { " of these endpoints" });
+        }
+        let trends = report::history::trends(&stored);
+        match args.format {
+            OutputFormat::Text => print!("{}", report::history::render(&stored, &trends)),
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&trends)?),
+            other => bail!("history prints text or json, not {:?}", other),
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Scan { seed, concurrency, timeout, limit }) = &args.command {
+        style::set_plain(args.plain || !std::io::stdout().is_terminal());
+        let seed = seed
+            .clone()
+            .or_else(|| args.urls.first().map(|endpoint| endpoint.url.clone()))
+            .unwrap_or_else(|| DEFAULT_URL.to_string());
+        let config = scan::ScanConfig { concurrency: *concurrency, timeout: *timeout, limit: *limit };
+        let survey = scan::scan(&seed, &config).await?;
+        match args.format {
+            OutputFormat::Text => print!("{}", survey.render()),
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&survey)?),
+            other => bail!("scan prints text or json, not {:?}", other),
+        }
+        return Ok(());
+    }
+
+    let endpoints = endpoints(&args)?;
+    let compare = matches!(args.command, Some(Command::Compare { .. }));
+    if args.race.is_some() && endpoints.len() < 2 {
+        bail!("--race needs at least two endpoints");
+    }
+    if args.failover.is_some() && endpoints.len() < 2 {
+        bail!("--failover needs at least two endpoints to fall back to");
+    }
+
+    // Structured output on stdout must not be mixed with the interactive output
+    let interactive = args.stream.is_none() && (args.format == OutputFormat::Text || args.output.is_some());
+    // Status lines around the report, which --quiet drops
+    let verbose = interactive && !args.quiet;
+
+    // Logs and pipes get plain text instead of ANSI codes and box drawing
+    style::set_plain(args.plain || !std::io::stdout().is_terminal());
+
+    if verbose {
+        // Show welcome screen
+        print_welcome_screen();
+    }
+
+    let keypair = match &args.keypair {
+        Some(path) => Some(Arc::new(
+            read_keypair_file(path).map_err(|e| anyhow!("Failed to read keypair {}: {}", path.display(), e))?,
+        )),
+        None => None,
+    };
+
+    let monitoring = matches!(args.command, Some(Command::Monitor(_)));
+    if verbose && !monitoring {
+        // Print test configuration
+        println!("{}", style::banner("TEST CONFIGURATION"));
+        if endpoints.len() > 1 {
+            println!("{}Endpoints: {}", icon("🔗"), endpoints.len().to_string().yellow());
+        }
+        match args.duration {
+            Some(duration) => println!(
+                "{}Duration per test: {}",
+                icon("🔄"),
+                humantime::format_duration(duration).to_string().yellow()
+            ),
+            None => println!("{}Iterations per test: {}", icon("🔄"), args.iterations.to_string().yellow()),
+        }
+        if args.warmup > 0 {
+            println!("{}Warm-up iterations: {}", icon("🔥"), args.warmup.to_string().yellow());
+        }
+        println!("{}Mode: {}", icon("⚙️ "), if args.parallel { "Parallel".green() } else { "Sequential".yellow() });
+        if let Some(max_duration) = args.max_duration {
+            println!("{}Max duration: {}", icon("⏳"), humantime::format_duration(max_duration).to_string().yellow());
+        }
+        println!();
+    }
+
+    let settings = TestSettings {
+        program_accounts: program_accounts_query(&args),
+        vote_account: args.vote_account,
+        stake_account: args.stake_account,
+        fee_accounts: args.fee_accounts.clone(),
+        keypair,
+        send: args.enable_send,
+        das: args.das,
+        bandwidth: args.bandwidth,
+        custom: args.method.as_ref().map(|method| CustomMethod {
+            method: method.clone(),
+            params: args.params.clone(),
+        }),
+    };
+    if let Some(custom) = &settings.custom {
+        // Results are keyed by name, so a clash would be merged into the built-in's stats
+        if rpc_tests::all_tests(&settings).iter().filter(|(name, _)| *name == custom.method).count() > 1 {
+            bail!("--method {} is already a built-in test", custom.method);
+        }
+    }
+
+    if let Some(Command::Monitor(monitor_args)) = &args.command {
+        // Monitor never goes through run_endpoint, and would send again every interval
+        if args.enable_send {
+            for endpoint in &endpoints {
+                check_send_allowed(&args, node::identify_cluster(endpoint).await.ok().as_ref())?;
             }
+        }
+        let tests = load::select(&rpc_tests::all_tests(&settings), &monitor_args.methods)?;
+        let config = monitor::MonitorConfig {
+            interval: monitor_args.interval,
+            window: monitor_args.window,
+            min_success_pct: monitor_args.min_success,
+            max_p95: monitor_args.max_p95,
+            fail_after: monitor_args.fail_after,
+            anomaly_z: monitor_args.anomaly_z,
+            ewma_alpha: monitor_args.ewma_alpha,
+            rounds: monitor_args.rounds,
+            json: args.format == OutputFormat::Json,
+            metrics_addr: monitor_args.metrics_addr,
+        };
+        let destinations = monitor_args
+            .webhooks
+            .iter()
+            .cloned()
+            .map(alerts::Destination::Webhook)
+            .chain(monitor_args.slack_webhooks.iter().cloned().map(alerts::Destination::Slack))
+            .chain(monitor_args.discord_webhooks.iter().cloned().map(alerts::Destination::Discord))
+            .chain(monitor_args.pagerduty_key.iter().cloned().map(alerts::Destination::PagerDuty))
+            .collect();
+        let dispatcher = alerts::Dispatcher::new(destinations, monitor_args.alert_cooldown);
+        #[cfg(feature = "email")]
+        let dispatcher = match monitor_args.email.config() {
+            Some(email) => dispatcher.with_email(email, monitor_args.email.digest_interval),
+            None => dispatcher,
+        };
+        return monitor::run(&endpoints, tests, Arc::new(settings), config, dispatcher).await;
+    }
 
-            // Also test getHealth
-            let url_clone = args.url.clone();
-            let iterations = args.iterations;
-            futures.push(tokio::spawn(async move {
-                let mut results = Vec::new();
-                for _i in 0..iterations {
-                    match test_get_health(&url_clone).await {
-                        Ok(result) => results.push(result),
-                        Err(e) => results.push(TestResult {
-                            name: "getHealth".to_string(),
-                            success: false,
-                            duration_ms: 0,
-                            error: Some(e.to_string()),
-                        }),
-                    }
-                    sleep(Duration::from_millis(100)).await;
-                }
-                results
-            }));
+    // One capture for the whole run, its entries carry their URLs
+    let har = args.har.as_ref().map(|_| har::HarRecorder::new(args.har_max_body));
 
-            // Wait for all tests to complete
-            let results = join_all(futures).await;
+    // Endpoints run one after another so they don't compete for bandwidth
+    let mut reports = Vec::new();
+    for endpoint in &endpoints {
+        reports.push(run_endpoint(&args, endpoint, &settings, har.clone(), verbose).await?);
+    }
 
-            // Collect results
-            for result in results {
-                match result {
-                    Ok(test_results) => all_results.extend(test_results),
-                    Err(e) => eprintln!("Error running test: {}", e),
-                }
+    // Raced after the suites, so no endpoint is still busy with its own tests
+    if let Some(rounds) = args.race {
+        if verbose {
+            println!("{}Racing {} endpoints over {} rounds...", icon("🏁"), endpoints.len(), rounds);
+        }
+        for (report, result) in reports.iter_mut().zip(race::race(&endpoints, rounds).await) {
+            report.race = Some(result);
+        }
+    }
+
+    if let Some(rounds) = args.failover {
+        if verbose {
+            println!("{}Simulating failover across {} endpoints over {} rounds...", icon("🛟"), endpoints.len(), rounds);
+        }
+        let config = failover::FailoverConfig {
+            rounds,
+            inject: args.failover_inject,
+            failure_pct: args.failover_rate,
+            timeout: args.failover_timeout,
+        };
+        // The chain's result belongs to its primary
+        reports[0].failover = Some(failover::simulate(&endpoints, &config).await);
+    }
+
+    match &args.output {
+        Some(path) => {
+            // Files never get ANSI colors, the terminal keeps its summary
+            colored::control::set_override(false);
+            let rendered = report::render_all(&reports, args.format)?;
+            style::restore_colors();
+
+            std::fs::write(path, rendered)
+                .with_context(|| format!("Failed to write report to {}", path.display()))?;
+
+            if interactive && compare {
+                print!("{}", report::compare::render(&reports));
+            } else if interactive {
+                print!("{}", report::render_all(&reports, OutputFormat::Text)?);
             }
-        } else {
-            // Run tests sequentially
-            for (test_name, test_fn) in tests {
-                for i in 0..args.iterations {
-                    print!("Running {} test {}/{}...\r", test_name, i + 1, args.iterations);
-                    match test_fn(&client).await {
-                        Ok(result) => all_results.push(result),
-                        Err(e) => all_results.push(TestResult {
-                            name: test_name.to_string(),
-                            success: false,
-                            duration_ms: 0,
-                            error: Some(e.to_string()),
-                        }),
-                    }
-                    sleep(Duration::from_millis(100)).await;
-                }
+            if verbose {
+                println!("{}Report written to {}", icon("📝"), path.display().to_string().cyan());
             }
+        }
+        // When streaming, stdout only carries the lines for the consumer
+        None if args.stream.is_some() => {}
+        None if compare && args.format == OutputFormat::Text => print!("{}", report::compare::render(&reports)),
+        None => print!("{}", report::render_all(&reports, args.format)?),
+    }
 
-            // Also test getHealth
-            for i in 0..args.iterations {
-                print!("Running getHealth test {}/{}...\r", i + 1, args.iterations);
-                match test_get_health(&args.url).await {
-                    Ok(result) => all_results.push(result),
-                    Err(e) => all_results.push(TestResult {
-                        name: "getHealth".to_string(),
-                        success: false,
-                        duration_ms: 0,
-                        error: Some(e.to_string()),
-                    }),
-                }
-                sleep(Duration::from_millis(100)).await;
+    if std::env::var("GITHUB_ACTIONS").as_deref() == Ok("true") {
+        for report in &reports {
+            report_to_github_actions(report, interactive)?;
+        }
+    }
+
+    if let (Some(path), Some(recorder)) = (&args.har, &har) {
+        recorder.write(path)?;
+        if verbose {
+            println!("{}HAR capture written to {}", icon("🧾"), path.display().to_string().cyan());
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    if let Some(path) = &args.db {
+        for report in &reports {
+            let run_id = sinks::sqlite::append(path, report)?;
+            if verbose {
+                println!("{}Run #{} saved to {}", icon("🗄️ "), run_id, path.display().to_string().cyan());
             }
         }
+    }
 
-        println!("Testing completed!                                ");
+    #[cfg(feature = "postgres")]
+    if let Some(dsn) = &args.pg_dsn {
+        for report in &reports {
+            let run_id = sinks::postgres::append(dsn, report).await?;
+            if verbose {
+                println!("{}Run #{} saved to PostgreSQL", icon("🗄️ "), run_id);
+            }
+        }
+    }
+
+    #[cfg(feature = "parquet")]
+    if let Some(path) = &args.parquet {
+        report::write_parquet(&reports, path)?;
+        if verbose {
+            println!("{}Parquet samples written to {}", icon("🗃️ "), path.display().to_string().cyan());
+        }
     }
 
-    // Print summary
-    print_test_summary(&all_results);
+    if let Some(path) = &args.prom_textfile {
+        write_atomic(path, &report::render_all(&reports, OutputFormat::Prometheus)?)?;
+        if verbose {
+            println!("{}Prometheus metrics written to {}", icon("📈"), path.display().to_string().cyan());
+        }
+    }
+
+    if let (Some(url), Some(bucket)) = (&args.influx_url, &args.influx_bucket) {
+        let config = InfluxConfig {
+            url: url.clone(),
+            bucket: bucket.clone(),
+            org: args.influx_org.clone(),
+            token: args.influx_token.clone(),
+        };
+        for report in &reports {
+            sinks::influx::push(&config, report).await?;
+        }
+        if verbose {
+            println!("{}Pushed measurements to InfluxDB bucket {}", icon("📤"), config.bucket.cyan());
+        }
+    }
+
+    if let Some(addr) = &args.statsd {
+        let config = StatsdConfig { addr: addr.clone(), prefix: args.statsd_prefix.clone() };
+        for report in &reports {
+            sinks::statsd::send(&config, report).await?;
+        }
+        if verbose {
+            println!("{}Sent metrics to StatsD at {}", icon("📤"), config.addr.cyan());
+        }
+    }
+
+    if let Some(endpoint) = &args.otlp_endpoint {
+        for report in &reports {
+            sinks::otlp::export(endpoint, report).await?;
+        }
+        if verbose {
+            println!("{}Exported trace to OTLP collector at {}", icon("📤"), endpoint.cyan());
+        }
+    }
+
+    if let (Some(server), Some(host)) = (&args.zabbix_server, &args.zabbix_host) {
+        let config = ZabbixConfig { server: server.clone(), host: host.clone() };
+        for report in &reports {
+            sinks::zabbix::send(&config, report).await?;
+        }
+        if verbose {
+            println!("{}Sent items to Zabbix at {}", icon("📤"), config.server.cyan());
+        }
+    }
+
+    if !args.assertions.is_empty() {
+        let failures: Vec<String> =
+            reports.iter().flat_map(|report| report::assertions::check(report, &args.assertions)).collect();
+        if !failures.is_empty() {
+            for failure in &failures {
+                eprintln!("{}Assertion failed: {}", icon("❌"), failure.red());
+            }
+            std::process::exit(1);
+        }
+        if verbose {
+            println!("{}All {} assertions passed", icon("✅"), args.assertions.len());
+        }
+    }
+
+    // A plugin's state is its exit code, wherever the status line went
+    if args.format == OutputFormat::Nagios {
+        let (_, state) = report::nagios::check_all(&reports);
+        std::process::exit(state.exit_code());
+    }
 
     Ok(())
 }
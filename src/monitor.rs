@@ -0,0 +1,540 @@
+use crate::alerts::Dispatcher;
+use crate::endpoint::Endpoint;
+use crate::rate_limit::RateLimitTracker;
+use crate::report::{self, histogram::LatencyHistogram, prometheus};
+use crate::rpc_tests::{TestContext, TestFn, TestResult, TestSettings};
+use crate::style::{self, icon};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use colored::*;
+use futures::future::join_all;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::MissedTickBehavior;
+
+/// Methods probed every round unless --method picks others, cheap enough to send every few seconds
+pub const DEFAULT_PROBES: [&str; 4] = ["getSlot", "getLatestBlockhash", "getBalance", "getHealth"];
+
+/// Successful probes a latency baseline needs before spikes against it are flagged
+const ANOMALY_WARMUP: usize = 10;
+
+/// Smallest deviation assumed, as a share of the baseline, so a very steady method isn't
+/// flagged for a few ms of jitter
+const MIN_DEVIATION: f64 = 0.05;
+
+/// How often to probe and when a method counts as unhealthy
+#[derive(Clone, Debug)]
+pub struct MonitorConfig {
+    pub interval: Duration,
+    /// Span the rolling stats cover
+    pub window: Duration,
+    /// Lowest success rate in percent over the window before a method is degraded
+    pub min_success_pct: f64,
+    /// Highest p95 over the window before a method is degraded
+    pub max_p95: Duration,
+    /// Failed probes in a row before a method is down
+    pub fail_after: usize,
+    /// z-score above the latency baseline at which a probe counts as a spike, 0 to disable
+    pub anomaly_z: f64,
+    /// Weight of each new probe in the latency baseline
+    pub ewma_alpha: f64,
+    /// Stop after this many rounds instead of running until interrupted
+    pub rounds: Option<usize>,
+    /// Print alerts as JSON lines instead of text
+    pub json: bool,
+    /// Serve live metrics for Prometheus on this address
+    pub metrics_addr: Option<SocketAddr>,
+}
+
+/// Health of one method on one endpoint
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Health {
+    Up,
+    Degraded,
+    Down,
+}
+
+impl Health {
+    /// Exported as the value of the health gauge
+    pub fn level(self) -> u8 {
+        match self {
+            Health::Up => 0,
+            Health::Degraded => 1,
+            Health::Down => 2,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Health::Up => "UP",
+            Health::Degraded => "DEGRADED",
+            Health::Down => "DOWN",
+        }
+    }
+
+    fn icon(self) -> &'static str {
+        match self {
+            Health::Up => "🟢",
+            Health::Degraded => "🟡",
+            Health::Down => "🔴",
+        }
+    }
+
+    fn colored(self) -> ColoredString {
+        match self {
+            Health::Up => self.label().green(),
+            Health::Degraded => self.label().yellow(),
+            Health::Down => self.label().red(),
+        }
+    }
+}
+
+/// What moved a method to its new health
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    /// `fail_after` probes in a row failed
+    Failures,
+    /// The window's success rate fell below the minimum
+    SuccessRate,
+    /// The window's p95 rose above the limit
+    P95,
+    /// The latest probe was far slower than the method's baseline
+    Anomaly,
+    /// None of the above apply any more
+    Recovered,
+}
+
+/// Latency and success of one method over the rolling window
+#[derive(Serialize, Clone, Debug)]
+pub struct SeriesStats {
+    pub total: usize,
+    pub success_rate: f64,
+    pub p50_ms: Option<u128>,
+    pub p95_ms: Option<u128>,
+    /// Latency of the latest probe, if it succeeded
+    pub last_ms: Option<u128>,
+    /// Exponentially weighted mean latency, once warmed up
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline_ms: Option<f64>,
+    /// Standard deviations the latest probe was above the baseline before it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub z_score: Option<f64>,
+}
+
+/// A change in a method's health
+#[derive(Serialize, Clone, Debug)]
+pub struct Alert {
+    pub timestamp: DateTime<Utc>,
+    pub endpoint: String,
+    pub method: String,
+    pub from: Health,
+    pub to: Health,
+    pub trigger: Trigger,
+    pub reason: String,
+    pub stats: SeriesStats,
+    /// Error of the latest failed probe in the window
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Alert {
+    pub fn render(&self) -> String {
+        let mut line = format!(
+            "{} {}{} {} {}: {}",
+            self.timestamp.format("%Y-%m-%d %H:%M:%S").to_string().dimmed(),
+            icon(self.to.icon()),
+            report::host(&self.endpoint).cyan(),
+            self.method,
+            self.to.colored(),
+            self.reason
+        );
+        if let Some(error) = &self.error {
+            line += &format!("\n    {}", error.dimmed());
+        }
+        line
+    }
+}
+
+/// One probe's outcome, all a series keeps of it
+struct Sample {
+    timestamp: DateTime<Utc>,
+    success: bool,
+    duration_ms: u128,
+}
+
+/// Every probe since monitoring started, for counters that must never go down
+#[derive(Default)]
+pub struct Totals {
+    pub succeeded: u64,
+    pub failed: u64,
+    /// Successful probes at or below each of `prometheus::BUCKETS`
+    pub buckets: [u64; prometheus::BUCKETS.len()],
+    pub sum_seconds: f64,
+}
+
+/// Exponentially weighted mean and variance of a method's successful latencies
+#[derive(Default)]
+pub struct Baseline {
+    pub mean: f64,
+    variance: f64,
+    samples: usize,
+    /// z-score of the latest probe against the baseline before it, once warmed up
+    pub last_z: Option<f64>,
+    /// The baseline the latest probe was compared with
+    pub expected_ms: f64,
+}
+
+impl Baseline {
+    pub fn is_warm(&self) -> bool {
+        self.samples >= ANOMALY_WARMUP
+    }
+
+    fn update(&mut self, ms: f64, alpha: f64) {
+        if self.samples == 0 {
+            self.mean = ms;
+        } else {
+            let deviation = self.variance.sqrt().max(self.mean * MIN_DEVIATION).max(1.0);
+            self.last_z = self.is_warm().then(|| (ms - self.mean) / deviation);
+            self.expected_ms = self.mean;
+
+            let diff = ms - self.mean;
+            let step = alpha * diff;
+            self.mean += step;
+            self.variance = (1.0 - alpha) * (self.variance + diff * step);
+        }
+        self.samples += 1;
+    }
+}
+
+/// Rolling probe results of one method on one endpoint and the health they add up to
+pub struct Series {
+    pub endpoint: String,
+    pub method: String,
+    samples: VecDeque<Sample>,
+    pub totals: Totals,
+    pub baseline: Baseline,
+    pub health: Health,
+    /// When the method last stopped being up, or when monitoring started
+    pub since: DateTime<Utc>,
+    pub last_error: Option<String>,
+}
+
+impl Series {
+    fn new(endpoint: &str, method: &str) -> Self {
+        Series {
+            endpoint: endpoint.to_string(),
+            method: method.to_string(),
+            samples: VecDeque::new(),
+            totals: Totals::default(),
+            baseline: Baseline::default(),
+            health: Health::Up,
+            since: Utc::now(),
+            last_error: None,
+        }
+    }
+
+    pub fn stats(&self) -> SeriesStats {
+        let histogram: LatencyHistogram =
+            self.samples.iter().filter(|s| s.success).map(|s| s.duration_ms).collect();
+        SeriesStats {
+            total: self.samples.len(),
+            success_rate: histogram.len() as f64 / self.samples.len().max(1) as f64 * 100.0,
+            p50_ms: histogram.percentile(50.0),
+            p95_ms: histogram.percentile(95.0),
+            last_ms: self.samples.back().filter(|s| s.success).map(|s| s.duration_ms),
+            baseline_ms: self.baseline.is_warm().then_some(self.baseline.mean),
+            z_score: self.baseline.last_z,
+        }
+    }
+
+    /// The health the window points to, and why
+    fn assess(&self, config: &MonitorConfig) -> (Health, Trigger, String) {
+        let failed_in_row = self.samples.iter().rev().take_while(|s| !s.success).count();
+        if failed_in_row >= config.fail_after.max(1) {
+            return (Health::Down, Trigger::Failures, format!("{} probes in a row failed", failed_in_row));
+        }
+
+        // Too few samples for the window's rates to mean anything yet
+        if self.samples.len() < config.fail_after.max(1) {
+            return (Health::Up, Trigger::Recovered, "probes answering".to_string());
+        }
+        let window = humantime::format_duration(config.window);
+        let stats = self.stats();
+        if stats.success_rate < config.min_success_pct {
+            return (
+                Health::Degraded,
+                Trigger::SuccessRate,
+                format!("success {:.1}% over {} below {}%", stats.success_rate, window, config.min_success_pct),
+            );
+        }
+        if let Some(p95) = stats.p95_ms.filter(|ms| *ms > config.max_p95.as_millis()) {
+            return (
+                Health::Degraded,
+                Trigger::P95,
+                format!("p95 {}ms over {} above {}ms", p95, window, config.max_p95.as_millis()),
+            );
+        }
+        if let Some(z) = self.baseline.last_z.filter(|z| config.anomaly_z > 0.0 && *z > config.anomaly_z) {
+            return (
+                Health::Degraded,
+                Trigger::Anomaly,
+                format!(
+                    "{}ms is {:.1}σ above the {:.0}ms baseline",
+                    stats.last_ms.unwrap_or_default(),
+                    z,
+                    self.baseline.expected_ms
+                ),
+            );
+        }
+        (
+            Health::Up,
+            Trigger::Recovered,
+            format!("success {:.1}%, p95 {}ms", stats.success_rate, stats.p95_ms.unwrap_or(0)),
+        )
+    }
+}
+
+/// Rolling health of every method on every endpoint
+pub struct Monitor {
+    pub config: MonitorConfig,
+    pub series: Vec<Series>,
+}
+
+impl Monitor {
+    pub fn new(endpoints: &[Endpoint], methods: &[&str], config: MonitorConfig) -> Self {
+        let series = endpoints
+            .iter()
+            .flat_map(|endpoint| methods.iter().map(|method| Series::new(&endpoint.url, method)))
+            .collect();
+        Monitor { config, series }
+    }
+
+    /// Add a probe result, returning an alert if it changed the method's health
+    pub fn observe(&mut self, endpoint: &str, result: TestResult) -> Option<Alert> {
+        let config = &self.config;
+        let series = self.series.iter_mut().find(|s| s.endpoint == endpoint && s.method == result.name)?;
+
+        if result.success {
+            series.baseline.update(result.duration_ms as f64, config.ewma_alpha);
+            let seconds = result.duration_ms as f64 / 1000.0;
+            series.totals.succeeded += 1;
+            series.totals.sum_seconds += seconds;
+            for (count, bound) in series.totals.buckets.iter_mut().zip(prometheus::BUCKETS) {
+                if seconds <= bound {
+                    *count += 1;
+                }
+            }
+        } else {
+            series.totals.failed += 1;
+            // A failure says nothing about latency, and must not leave an old spike standing
+            series.baseline.last_z = None;
+        }
+        if let Some(error) = &result.error {
+            series.last_error = Some(error.clone());
+        }
+        series.samples.push_back(Sample {
+            timestamp: result.timestamp,
+            success: result.success,
+            duration_ms: result.duration_ms,
+        });
+        let expired = |s: &Sample| (result.timestamp - s.timestamp).to_std().is_ok_and(|age| age >= config.window);
+        while series.samples.front().is_some_and(expired) {
+            series.samples.pop_front();
+        }
+        if series.samples.iter().all(|s| s.success) {
+            series.last_error = None;
+        }
+
+        let (health, trigger, mut reason) = series.assess(config);
+        if health == series.health {
+            return None;
+        }
+        if health == Health::Up {
+            let outage = (result.timestamp - series.since).to_std().unwrap_or_default();
+            let outage = humantime::format_duration(Duration::from_secs(outage.as_secs()));
+            reason = format!("recovered after {}, {}", outage, reason);
+        }
+
+        let alert = Alert {
+            timestamp: result.timestamp,
+            endpoint: series.endpoint.clone(),
+            method: series.method.clone(),
+            from: series.health,
+            to: health,
+            trigger,
+            reason,
+            stats: series.stats(),
+            error: series.last_error.clone().filter(|_| health != Health::Up),
+        };
+        if series.health == Health::Up {
+            series.since = result.timestamp;
+        }
+        series.health = health;
+        Some(alert)
+    }
+
+    /// One line per method with its current health and window stats
+    pub fn render_status(&self) -> String {
+        let mut out = String::new();
+        for series in &self.series {
+            let stats = series.stats();
+            out += &format!(
+                "{}{:<28} {:<24} {:<9} {:>6.1}% p50 {:>5} p95 {:>5}\n",
+                icon(series.health.icon()),
+                report::host(&series.endpoint),
+                series.method,
+                series.health.colored(),
+                stats.success_rate,
+                stats.p50_ms.map(|ms| format!("{}ms", ms)).unwrap_or_else(|| "-".to_string()),
+                stats.p95_ms.map(|ms| format!("{}ms", ms)).unwrap_or_else(|| "-".to_string()),
+            );
+        }
+        out
+    }
+}
+
+/// Send every probe once, turning errors into failed results like the suite does
+async fn probe(ctx: &TestContext, tests: &[(Cow<'static, str>, TestFn)]) -> Vec<TestResult> {
+    let mut results = Vec::new();
+    for (name, test_fn) in tests {
+        results.push(match test_fn(ctx).await {
+            Ok(result) => result,
+            Err(e) => TestResult::failure(name, Duration::ZERO, e),
+        });
+    }
+    results
+}
+
+/// Answer one scrape: the live metrics on /metrics, 404 for anything else
+async fn respond(mut stream: TcpStream, monitor: &Mutex<Monitor>) -> Result<()> {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < 8192 {
+        let read = stream.read(&mut buf).await?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..read]);
+    }
+
+    let head = String::from_utf8_lossy(&request);
+    let path = head.split_whitespace().nth(1).unwrap_or_default();
+    let (status, body) = if path == "/metrics" || path.starts_with("/metrics?") {
+        let monitor = monitor.lock().unwrap();
+        ("200 OK", prometheus::render_monitor(&monitor))
+    } else {
+        ("404 Not Found", "Not found, metrics are served on /metrics\n".to_string())
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    Ok(())
+}
+
+/// Serve the monitor's live metrics until the process exits
+async fn serve_metrics(listener: TcpListener, monitor: Arc<Mutex<Monitor>>) {
+    loop {
+        let Ok((stream, _)) = listener.accept().await else { continue };
+        let monitor = monitor.clone();
+        tokio::spawn(async move {
+            // A scraper hanging up early is its own problem
+            let _ = respond(stream, &monitor).await;
+        });
+    }
+}
+
+/// Probe every endpoint each interval until interrupted, printing only health changes
+pub async fn run(
+    endpoints: &[Endpoint],
+    tests: Vec<(Cow<'static, str>, TestFn)>,
+    settings: Arc<TestSettings>,
+    config: MonitorConfig,
+    mut dispatcher: Dispatcher,
+) -> Result<()> {
+    // Contexts live for the whole run so probes reuse warm connections, as a client would
+    let contexts: Vec<TestContext> = endpoints
+        .iter()
+        .map(|endpoint| TestContext::new(endpoint, None, RateLimitTracker::default(), settings.clone()))
+        .collect();
+    let methods: Vec<&str> = tests.iter().map(|(name, _)| name.as_ref()).collect();
+    let monitor = Arc::new(Mutex::new(Monitor::new(endpoints, &methods, config.clone())));
+
+    if !config.json {
+        println!(
+            "{}Monitoring {} on {} endpoint(s) every {}, stats over {}. Ctrl-C to stop.",
+            icon("👀"),
+            methods.join(", ").yellow(),
+            endpoints.len(),
+            humantime::format_duration(config.interval).to_string().yellow(),
+            humantime::format_duration(config.window).to_string().yellow()
+        );
+    }
+
+    if let Some(addr) = config.metrics_addr {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to listen for metrics scrapes on {}", addr))?;
+        if !config.json {
+            println!("{}Serving metrics on http://{}/metrics", icon("📈"), addr.to_string().cyan());
+        }
+        tokio::spawn(serve_metrics(listener, monitor.clone()));
+    }
+
+    let mut ticks = tokio::time::interval(config.interval);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut rounds = 0;
+    loop {
+        let round = async {
+            ticks.tick().await;
+            join_all(contexts.iter().map(|ctx| probe(ctx, &tests))).await
+        };
+        let results = tokio::select! {
+            results = round => results,
+            _ = tokio::signal::ctrl_c() => break,
+        };
+
+        let alerts: Vec<Alert> = {
+            let mut monitor = monitor.lock().unwrap();
+            endpoints
+                .iter()
+                .zip(results)
+                .flat_map(|(endpoint, results)| results.into_iter().map(move |result| (endpoint, result)))
+                .filter_map(|(endpoint, result)| monitor.observe(&endpoint.url, result))
+                .collect()
+        };
+        for alert in &alerts {
+            if config.json {
+                println!("{}", serde_json::to_string(alert)?);
+            } else {
+                println!("{}", alert.render());
+            }
+        }
+        dispatcher.dispatch(alerts);
+        #[cfg(feature = "email")]
+        dispatcher.digest(&monitor.lock().unwrap());
+
+        rounds += 1;
+        if config.rounds.is_some_and(|limit| rounds >= limit) {
+            break;
+        }
+    }
+
+    dispatcher.flush().await;
+    if !config.json {
+        println!("\n{}", style::rule(false));
+        print!("{}", monitor.lock().unwrap().render_status());
+    }
+    Ok(())
+}
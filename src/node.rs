@@ -0,0 +1,274 @@
+use crate::endpoint::Endpoint;
+use anyhow::{bail, Result};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::fmt;
+use std::time::Duration;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// What the endpoint reports about the node behind it, for fleet auditing
+#[derive(Serialize, Clone, Debug)]
+pub struct NodeInfo {
+    /// solana-core (or Agave/Firedancer compatible) version string
+    pub solana_core: String,
+    /// Identifier of the feature set the node runs with
+    pub feature_set: Option<u32>,
+    /// Identity pubkey of the node, hidden behind some load balancers
+    pub identity: Option<String>,
+}
+
+impl NodeInfo {
+    /// `1.17.20 (feature set 3580551090)`
+    pub fn describe(&self) -> String {
+        match self.feature_set {
+            Some(feature_set) => format!("{} (feature set {})", self.solana_core, feature_set),
+            None => self.solana_core.clone(),
+        }
+    }
+}
+
+/// Ask the node once which software it runs, before the measured tests start
+pub async fn probe(endpoint: &Endpoint) -> Result<NodeInfo> {
+    let client = endpoint.rpc_client();
+    let version = client.get_version().await?;
+    let identity = client.get_identity().await.ok().map(|pubkey| pubkey.to_string());
+
+    Ok(NodeInfo { solana_core: version.solana_core, feature_set: version.feature_set, identity })
+}
+
+/// Network throughput derived from getRecentPerformanceSamples, as context for the run
+#[derive(Serialize, Clone, Debug)]
+pub struct NetworkTps {
+    pub tps: f64,
+    /// Absent on nodes that don't split out vote transactions
+    pub non_vote_tps: Option<f64>,
+    /// Seconds covered by the samples
+    pub window_secs: u64,
+}
+
+/// Samples (of 60s each) averaged into the network TPS
+const TPS_SAMPLES: usize = 10;
+
+/// Average transactions per second over the most recent performance samples
+pub async fn network_tps(endpoint: &Endpoint) -> Result<NetworkTps> {
+    let client = endpoint.rpc_client();
+    let samples = client.get_recent_performance_samples(Some(TPS_SAMPLES)).await?;
+
+    let window_secs: u64 = samples.iter().map(|s| s.sample_period_secs as u64).sum();
+    if window_secs == 0 {
+        bail!("no performance samples returned");
+    }
+
+    let transactions: u64 = samples.iter().map(|s| s.num_transactions).sum();
+    let non_vote: Option<u64> = samples.iter().map(|s| s.num_non_vote_transactions).sum();
+
+    Ok(NetworkTps {
+        tps: transactions as f64 / window_secs as f64,
+        non_vote_tps: non_vote.map(|n| n as f64 / window_secs as f64),
+        window_secs,
+    })
+}
+
+/// How far back the endpoint's ledger goes
+#[derive(Serialize, Clone, Debug)]
+pub struct LedgerDepth {
+    pub first_available_block: u64,
+    pub minimum_ledger_slot: u64,
+    pub current_slot: u64,
+    /// Rough age of the oldest available block, at 400ms per slot
+    pub history_days: f64,
+    /// Whether the ledger reaches back to the first blocks of the cluster
+    pub archive: bool,
+    /// Highest full and incremental snapshot slots, if the node serves snapshots
+    pub full_snapshot_slot: Option<u64>,
+    pub incremental_snapshot_slot: Option<u64>,
+}
+
+/// Average slot time the history estimate is based on
+const SLOT_SECS: f64 = 0.4;
+
+/// Blocks below this slot are only kept by archive (Bigtable-backed) nodes
+const ARCHIVE_MAX_FIRST_BLOCK: u64 = 1_000_000;
+
+/// Ask the node for the oldest block and ledger slot it can serve
+pub async fn ledger_depth(endpoint: &Endpoint) -> Result<LedgerDepth> {
+    let client = endpoint.rpc_client();
+    let first_available_block = client.get_first_available_block().await?;
+    let minimum_ledger_slot = client.minimum_ledger_slot().await?;
+    let current_slot = client.get_slot().await?;
+    // Nodes without snapshots answer with an error, which doesn't invalidate the rest
+    let snapshot = client.get_highest_snapshot_slot().await.ok();
+
+    let slots = current_slot.saturating_sub(first_available_block);
+    Ok(LedgerDepth {
+        first_available_block,
+        minimum_ledger_slot,
+        current_slot,
+        history_days: slots as f64 * SLOT_SECS / 86_400.0,
+        archive: first_available_block < ARCHIVE_MAX_FIRST_BLOCK,
+        full_snapshot_slot: snapshot.as_ref().map(|s| s.full),
+        incremental_snapshot_slot: snapshot.and_then(|s| s.incremental),
+    })
+}
+
+/// Public Solana clusters, told apart by their genesis hash
+#[derive(ValueEnum, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Cluster {
+    MainnetBeta,
+    Devnet,
+    Testnet,
+}
+
+impl Cluster {
+    pub fn from_genesis_hash(hash: &str) -> Option<Cluster> {
+        match hash {
+            "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d" => Some(Cluster::MainnetBeta),
+            "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG" => Some(Cluster::Devnet),
+            "4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY" => Some(Cluster::Testnet),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Cluster {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Cluster::MainnetBeta => "mainnet-beta",
+            Cluster::Devnet => "devnet",
+            Cluster::Testnet => "testnet",
+        })
+    }
+}
+
+/// Which cluster the endpoint serves
+#[derive(Serialize, Clone, Debug)]
+pub struct ClusterInfo {
+    pub genesis_hash: String,
+    /// None for local validators and private clusters
+    pub cluster: Option<Cluster>,
+}
+
+impl ClusterInfo {
+    pub fn describe(&self) -> String {
+        match self.cluster {
+            Some(cluster) => cluster.to_string(),
+            None => format!("unknown (genesis {})", self.genesis_hash),
+        }
+    }
+}
+
+pub async fn identify_cluster(endpoint: &Endpoint) -> Result<ClusterInfo> {
+    let client = endpoint.rpc_client();
+    let genesis_hash = client.get_genesis_hash().await?.to_string();
+
+    Ok(ClusterInfo { cluster: Cluster::from_genesis_hash(&genesis_hash), genesis_hash })
+}
+
+/// Prioritization fees paid in recent slots, in micro-lamports per compute unit
+#[derive(Serialize, Clone, Debug)]
+pub struct PriorityFees {
+    /// Accounts the fees were scoped to, empty for the whole cluster
+    pub accounts: Vec<String>,
+    pub slots: usize,
+    pub min: u64,
+    pub p50: u64,
+    pub p75: u64,
+    pub p90: u64,
+    pub max: u64,
+}
+
+/// Nearest-rank percentile of an ascending slice
+fn nearest_rank(sorted: &[u64], p: f64) -> u64 {
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Fee percentiles over the slots getRecentPrioritizationFees returns (up to 150)
+pub async fn priority_fees(endpoint: &Endpoint, accounts: &[Pubkey]) -> Result<PriorityFees> {
+    let client = endpoint.rpc_client();
+    let mut fees: Vec<u64> = client
+        .get_recent_prioritization_fees(accounts)
+        .await?
+        .into_iter()
+        .map(|f| f.prioritization_fee)
+        .collect();
+    if fees.is_empty() {
+        bail!("no prioritization fees returned");
+    }
+    fees.sort_unstable();
+
+    Ok(PriorityFees {
+        accounts: accounts.iter().map(|a| a.to_string()).collect(),
+        slots: fees.len(),
+        min: fees[0],
+        p50: nearest_rank(&fees, 50.0),
+        p75: nearest_rank(&fees, 75.0),
+        p90: nearest_rank(&fees, 90.0),
+        max: fees[fees.len() - 1],
+    })
+}
+
+/// Slot difference to a reference endpoint, sampled in the background for the whole run
+#[derive(Serialize, Clone, Debug)]
+pub struct SlotLag {
+    pub reference: String,
+    pub samples: usize,
+    /// Positive when the endpoint is behind the reference
+    pub avg_slots: f64,
+    pub min_slots: i64,
+    pub max_slots: i64,
+    /// The average lag in time, at 400ms per slot
+    pub avg_behind_ms: f64,
+}
+
+/// Average lag from which an endpoint is reported as lagging, about two seconds
+pub const LAGGING_SLOTS: f64 = 5.0;
+
+/// Time between two slot lag samples
+const LAG_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Compare processed slots of the endpoint and the reference until told to stop.
+/// The task yields None if not a single pair of slots could be fetched.
+pub fn spawn_slot_lag(endpoint: &Endpoint, reference: &str) -> (oneshot::Sender<()>, JoinHandle<Option<SlotLag>>) {
+    let (stop, mut stopped) = oneshot::channel();
+    let client = endpoint.rpc_client_with(CommitmentConfig::processed());
+    let reference_client = RpcClient::new_with_commitment(reference.to_string(), CommitmentConfig::processed());
+    let reference = reference.to_string();
+
+    let handle = tokio::spawn(async move {
+        let mut deltas: Vec<i64> = Vec::new();
+        let mut interval = tokio::time::interval(LAG_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = &mut stopped => break,
+                _ = interval.tick() => {
+                    // Both asked at once, so the delta is not skewed by the time between the calls
+                    if let (Ok(slot), Ok(reference_slot)) = tokio::join!(client.get_slot(), reference_client.get_slot()) {
+                        deltas.push(reference_slot as i64 - slot as i64);
+                    }
+                }
+            }
+        }
+
+        if deltas.is_empty() {
+            return None;
+        }
+        let avg_slots = deltas.iter().sum::<i64>() as f64 / deltas.len() as f64;
+        Some(SlotLag {
+            reference,
+            samples: deltas.len(),
+            avg_slots,
+            min_slots: deltas.iter().copied().min().unwrap_or_default(),
+            max_slots: deltas.iter().copied().max().unwrap_or_default(),
+            avg_behind_ms: avg_slots * SLOT_SECS * 1000.0,
+        })
+    });
+
+    (stop, handle)
+}
@@ -0,0 +1,91 @@
+use anyhow::{anyhow, bail, Context, Result};
+use reqwest::Url;
+use serde::Serialize;
+use std::time::Instant;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{lookup_host, TcpStream};
+
+/// How long each phase of a fresh connection took, in milliseconds
+#[derive(Serialize, Clone, Debug)]
+pub struct ConnectionTiming {
+    pub remote_addr: String,
+    pub dns_ms: f64,
+    pub tcp_ms: f64,
+    /// Absent for plain `http://` endpoints
+    pub tls_ms: Option<f64>,
+    /// From sending the request to the first response byte
+    pub request_ms: f64,
+    pub total_ms: f64,
+}
+
+fn ms(since: Instant) -> f64 {
+    since.elapsed().as_secs_f64() * 1000.0
+}
+
+/// Send a getHealth request and wait for the first byte of the response
+async fn first_byte<S: AsyncRead + AsyncWrite + Unpin>(stream: &mut S, url: &Url) -> Result<()> {
+    let body = r#"{"jsonrpc":"2.0","id":1,"method":"getHealth"}"#;
+    let path = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        path,
+        url.host_str().unwrap_or_default(),
+        body.len(),
+        body
+    );
+
+    stream.write_all(request.as_bytes()).await?;
+    let mut byte = [0u8; 1];
+    if stream.read(&mut byte).await? == 0 {
+        bail!("Connection closed before any response");
+    }
+    Ok(())
+}
+
+/// Open a brand-new connection to the endpoint and time DNS, TCP, TLS and the first request
+pub async fn measure(endpoint: &str) -> Result<ConnectionTiming> {
+    let url = Url::parse(endpoint).context("Invalid RPC endpoint URL")?;
+    let host = url.host_str().ok_or_else(|| anyhow!("RPC endpoint URL has no host"))?.to_string();
+    let port = url.port_or_known_default().unwrap_or(443);
+    let start = Instant::now();
+
+    let dns_start = Instant::now();
+    let addr = lookup_host((host.as_str(), port))
+        .await
+        .with_context(|| format!("Failed to resolve {}", host))?
+        .next()
+        .ok_or_else(|| anyhow!("{} resolved to no addresses", host))?;
+    let dns_ms = ms(dns_start);
+
+    let tcp_start = Instant::now();
+    let tcp = TcpStream::connect(addr).await.with_context(|| format!("Failed to connect to {}", addr))?;
+    let tcp_ms = ms(tcp_start);
+
+    let (tls_ms, request_ms) = if url.scheme() == "https" {
+        let tls_start = Instant::now();
+        let connector = tokio_native_tls::TlsConnector::from(native_tls::TlsConnector::new()?);
+        let mut tls = connector.connect(&host, tcp).await.context("TLS handshake failed")?;
+        let tls_ms = ms(tls_start);
+
+        let request_start = Instant::now();
+        first_byte(&mut tls, &url).await?;
+        (Some(tls_ms), ms(request_start))
+    } else {
+        let mut tcp = tcp;
+        let request_start = Instant::now();
+        first_byte(&mut tcp, &url).await?;
+        (None, ms(request_start))
+    };
+
+    Ok(ConnectionTiming {
+        remote_addr: addr.to_string(),
+        dns_ms,
+        tcp_ms,
+        tls_ms,
+        request_ms,
+        total_ms: ms(start),
+    })
+}
@@ -0,0 +1,99 @@
+use crate::endpoint::Endpoint;
+use crate::rate_limit::RateLimitTracker;
+use crate::rpc_tests::SAMPLE_WALLET;
+use crate::transport::Transport;
+use futures::future::join_all;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+/// A method and a builder for its params
+type RaceRequest = (&'static str, fn() -> Value);
+
+/// Requests raced in rotation, cheap enough that the network dominates the timing
+const RACE_REQUESTS: [RaceRequest; 3] = [
+    ("getSlot", || json!([])),
+    ("getLatestBlockhash", || json!([])),
+    ("getBalance", || json!([SAMPLE_WALLET])),
+];
+
+/// Pause between rounds so one round's responses can't delay the next one's requests
+const ROUND_GAP: Duration = Duration::from_millis(100);
+
+/// How often an endpoint answered first when the same request went to all endpoints at once
+#[derive(Serialize, Clone, Debug)]
+pub struct RaceResult {
+    pub rounds: usize,
+    pub wins: usize,
+    pub win_pct: f64,
+    /// Average ms behind the winner in the rounds it answered but lost
+    pub avg_behind_ms: Option<f64>,
+    /// Rounds in which this endpoint failed or errored
+    pub failures: usize,
+}
+
+/// Fire identical requests at every endpoint simultaneously and count who answers first.
+/// Results are in the order of `endpoints`; a round where every endpoint failed has no winner.
+pub async fn race(endpoints: &[Endpoint], rounds: usize) -> Vec<RaceResult> {
+    let transports: Vec<Transport> =
+        endpoints.iter().map(|endpoint| endpoint.transport(None, RateLimitTracker::default())).collect();
+
+    // Open every connection first, a cold TLS handshake would decide the first round
+    let warmup = request("getSlot", json!([]), 0);
+    join_all(transports.iter().map(|t| t.post_json(&warmup))).await;
+
+    let mut wins = vec![0; endpoints.len()];
+    let mut failures = vec![0; endpoints.len()];
+    let mut behind: Vec<Vec<f64>> = vec![Vec::new(); endpoints.len()];
+
+    for round in 0..rounds {
+        let (method, params) = RACE_REQUESTS[round % RACE_REQUESTS.len()];
+        let body = request(method, params(), round as u64 + 1);
+
+        // One shared start, so a later-polled future is not credited with a head start
+        let start = Instant::now();
+        let outcomes = join_all(transports.iter().map(|transport| {
+            let body = &body;
+            async move {
+                let answered = match transport.post_json(body).await {
+                    Ok(response) if response.status.is_success() => {
+                        serde_json::from_str::<Value>(&response.body).is_ok_and(|json| json.get("result").is_some())
+                    }
+                    _ => false,
+                };
+                answered.then(|| start.elapsed())
+            }
+        }))
+        .await;
+
+        let fastest = outcomes.iter().enumerate().filter_map(|(i, d)| d.map(|d| (i, d))).min_by_key(|(_, d)| *d);
+        if let Some((winner, winning)) = fastest {
+            wins[winner] += 1;
+            for (i, outcome) in outcomes.iter().enumerate() {
+                match outcome {
+                    Some(d) if i != winner => behind[i].push((*d - winning).as_secs_f64() * 1000.0),
+                    Some(_) => {}
+                    None => failures[i] += 1,
+                }
+            }
+        } else {
+            failures.iter_mut().for_each(|f| *f += 1);
+        }
+
+        tokio::time::sleep(ROUND_GAP).await;
+    }
+
+    (0..endpoints.len())
+        .map(|i| RaceResult {
+            rounds,
+            wins: wins[i],
+            win_pct: if rounds > 0 { wins[i] as f64 * 100.0 / rounds as f64 } else { 0.0 },
+            avg_behind_ms: (!behind[i].is_empty()).then(|| behind[i].iter().sum::<f64>() / behind[i].len() as f64),
+            failures: failures[i],
+        })
+        .collect()
+}
+
+fn request(method: &str, params: Value, id: u64) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
@@ -0,0 +1,261 @@
+use crate::endpoint::Endpoint;
+use crate::rpc_tests::TestResult;
+use futures::future::join_all;
+use indicatif::ProgressBar;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::{sleep, sleep_until, Instant};
+
+/// Whether a response header carries rate-limit or quota information
+fn is_rate_limit_header(name: &str) -> bool {
+    name == "retry-after" || name.contains("ratelimit") || name.contains("rate-limit") || name.contains("quota")
+}
+
+/// What the endpoint told us about its limits over the run
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct RateLimitSummary {
+    /// Number of HTTP 429 responses
+    pub throttled: u64,
+    /// `Retry-After` of the most recent 429
+    pub retry_after: Option<String>,
+    /// Latest value of every rate-limit or quota header seen, e.g. `x-ratelimit-remaining`
+    pub headers: BTreeMap<String, String>,
+}
+
+impl RateLimitSummary {
+    pub fn is_empty(&self) -> bool {
+        self.throttled == 0 && self.headers.is_empty()
+    }
+}
+
+/// Collects rate-limit headers from every response, shared by all transports of a run
+#[derive(Clone, Default)]
+pub struct RateLimitTracker {
+    inner: Arc<Mutex<RateLimitSummary>>,
+}
+
+impl RateLimitTracker {
+    pub fn observe(&self, status: StatusCode, headers: &HeaderMap) {
+        let mut summary = self.inner.lock().unwrap();
+        for (name, value) in headers {
+            if is_rate_limit_header(name.as_str()) {
+                if let Ok(value) = value.to_str() {
+                    summary.headers.insert(name.as_str().to_string(), value.to_string());
+                }
+            }
+        }
+
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            summary.throttled += 1;
+            summary.retry_after = headers
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+        }
+    }
+
+    pub fn summary(&self) -> RateLimitSummary {
+        self.inner.lock().unwrap().clone()
+    }
+}
+
+/// How a rate-limit probe ramps up and how long it watches the recovery
+#[derive(Clone, Copy, Debug)]
+pub struct ProbeConfig {
+    pub start_rps: f64,
+    pub max_rps: f64,
+    /// Each step's rate over the previous one
+    pub growth: f64,
+    /// How long each rate is held
+    pub step: Duration,
+    /// How long to keep retrying for a success after backing off
+    pub recovery_window: Duration,
+}
+
+impl ProbeConfig {
+    /// Rates the probe steps through when it is never throttled
+    pub fn rates(&self) -> Vec<f64> {
+        std::iter::successors(Some(self.start_rps), |rps| Some(rps * self.growth))
+            .take_while(|rps| *rps <= self.max_rps)
+            .collect()
+    }
+}
+
+/// Outcome of the requests sent at one rate
+#[derive(Serialize, Clone, Debug)]
+pub struct ProbeStep {
+    pub rps: f64,
+    pub sent: usize,
+    pub ok: usize,
+    pub throttled: usize,
+    /// Failures other than 429s
+    pub errors: usize,
+}
+
+/// What happened once the probe backed off after the first 429s
+#[derive(Serialize, Clone, Debug)]
+pub struct Recovery {
+    /// How long the probe paused before retrying, the `Retry-After` if one was given
+    pub waited_ms: u128,
+    /// Whether the first request after the pause succeeded
+    pub honoured: bool,
+    /// Time from backing off to the first success, `None` if none came within the window
+    pub recovered_after_ms: Option<u128>,
+    /// 429s received while retrying
+    pub throttled_while_retrying: usize,
+}
+
+impl Recovery {
+    /// The limit lifted when the endpoint said it would
+    pub fn clean(&self) -> bool {
+        self.honoured && self.throttled_while_retrying == 0
+    }
+}
+
+/// The request rate at which an endpoint starts throttling and how it behaves when it does
+#[derive(Serialize, Clone, Debug)]
+pub struct RateLimitProbe {
+    pub steps: Vec<ProbeStep>,
+    /// First rate that drew a 429, `None` if the probe reached its maximum unthrottled
+    pub throttled_at_rps: Option<f64>,
+    /// Highest rate without any 429
+    pub last_clean_rps: Option<f64>,
+    /// Distinct `Retry-After` values sent with the 429s
+    pub retry_after: Vec<String>,
+    /// 429s that came without a `Retry-After`
+    pub missing_retry_after: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery: Option<Recovery>,
+}
+
+/// Pause used when a 429 gives no usable `Retry-After`
+const DEFAULT_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Longest `Retry-After` the probe waits out, the header is whatever the server sends
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// `Retry-After` in seconds; HTTP dates are rare on JSON-RPC endpoints and not worth the parser
+fn retry_after_secs(value: &str) -> Option<Duration> {
+    let secs = value.trim().parse::<f64>().ok().filter(|secs| *secs >= 0.0)?;
+    // Only a value too large for a Duration fails here
+    Some(Duration::try_from_secs_f64(secs).map_or(MAX_BACKOFF, |backoff| backoff.min(MAX_BACKOFF)))
+}
+
+/// Send getSlot open-loop at rising rates until the endpoint answers 429, then back off
+/// for the `Retry-After` it asked for and retry once a second to see whether the limit
+/// lifts when promised. Every request is also returned as a test result.
+pub async fn probe(endpoint: &Endpoint, config: ProbeConfig, progress_bar: &ProgressBar) -> (Vec<TestResult>, RateLimitProbe) {
+    let transport = Arc::new(endpoint.transport(None, RateLimitTracker::default()));
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "getSlot" });
+
+    let send = |transport: Arc<crate::transport::Transport>, request: serde_json::Value| async move {
+        let start = std::time::Instant::now();
+        let response = transport.post_json(&request).await;
+        let elapsed = start.elapsed();
+        match response {
+            Ok(response) if response.status.is_success() => (TestResult::success("getSlot", elapsed), None),
+            Ok(response) => {
+                let retry_after =
+                    response.headers.get(reqwest::header::RETRY_AFTER).and_then(|v| v.to_str().ok()).map(str::to_string);
+                let error = format!("HTTP {}", response.status);
+                let throttled = (response.status == StatusCode::TOO_MANY_REQUESTS).then_some(retry_after);
+                (TestResult::failure("getSlot", elapsed, error), throttled)
+            }
+            Err(e) => (TestResult::failure("getSlot", elapsed, e), None),
+        }
+    };
+
+    let mut results = Vec::new();
+    let mut steps = Vec::new();
+    let mut retry_after: Vec<String> = Vec::new();
+    let mut missing_retry_after = 0;
+    let mut throttled_at_rps = None;
+
+    for rps in config.rates() {
+        progress_bar.set_message(format!("Probing rate limits at {:.0} rps", rps));
+        let start = Instant::now();
+        let interval = Duration::from_secs_f64(1.0 / rps);
+        let count = (rps * config.step.as_secs_f64()).round().max(1.0) as u32;
+        let handles: Vec<_> = (0..count)
+            .map(|i| {
+                let (transport, request) = (transport.clone(), request.clone());
+                tokio::spawn(async move {
+                    sleep_until(start + interval * i).await;
+                    send(transport, request).await
+                })
+            })
+            .collect();
+
+        let mut step = ProbeStep { rps, sent: handles.len(), ok: 0, throttled: 0, errors: 0 };
+        for (result, throttled) in join_all(handles).await.into_iter().flatten() {
+            match throttled {
+                Some(Some(value)) => {
+                    step.throttled += 1;
+                    if !retry_after.contains(&value) {
+                        retry_after.push(value);
+                    }
+                }
+                Some(None) => {
+                    step.throttled += 1;
+                    missing_retry_after += 1;
+                }
+                None if result.success => step.ok += 1,
+                None => step.errors += 1,
+            }
+            results.push(result);
+        }
+        progress_bar.inc(1);
+
+        let throttled = step.throttled > 0;
+        steps.push(step);
+        if throttled {
+            throttled_at_rps = Some(rps);
+            break;
+        }
+    }
+
+    let last_clean_rps = steps.iter().filter(|step| step.throttled == 0).map(|step| step.rps).next_back();
+
+    let recovery = match throttled_at_rps {
+        Some(_) => {
+            let backoff = retry_after.iter().filter_map(|value| retry_after_secs(value)).max().unwrap_or(DEFAULT_BACKOFF);
+            progress_bar.set_message(format!("Backing off for {}", humantime::format_duration(backoff)));
+            let backed_off = Instant::now();
+            sleep(backoff).await;
+
+            let mut honoured = None;
+            let mut recovered_after_ms = None;
+            let mut throttled_while_retrying = 0;
+            while backed_off.elapsed() < backoff + config.recovery_window {
+                let (result, throttled) = send(transport.clone(), request.clone()).await;
+                let success = result.success;
+                results.push(result);
+                honoured.get_or_insert(success);
+                if success {
+                    recovered_after_ms = Some(backed_off.elapsed().as_millis());
+                    break;
+                }
+                if throttled.is_some() {
+                    throttled_while_retrying += 1;
+                }
+                sleep(Duration::from_secs(1)).await;
+            }
+
+            Some(Recovery {
+                waited_ms: backoff.as_millis(),
+                honoured: honoured.unwrap_or(false),
+                recovered_after_ms,
+                throttled_while_retrying,
+            })
+        }
+        None => None,
+    };
+
+    let probe = RateLimitProbe { steps, throttled_at_rps, last_clean_rps, retry_after, missing_retry_after, recovery };
+    (results, probe)
+}
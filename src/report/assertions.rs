@@ -0,0 +1,197 @@
+use super::{OverallStats, Report, TestStats};
+use std::fmt;
+use std::str::FromStr;
+
+/// Which part of the report an assertion looks at
+#[derive(Clone, Debug, PartialEq)]
+pub enum Subject {
+    Overall,
+    Method(String),
+}
+
+/// The statistic an assertion limits: latencies in ms, the success rate in percent
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    Avg,
+    Min,
+    Max,
+    P50,
+    P90,
+    P95,
+    P99,
+    SuccessRate,
+    Failed,
+}
+
+impl Metric {
+    fn parse(name: &str) -> Option<Metric> {
+        Some(match name {
+            "avg" => Metric::Avg,
+            "min" => Metric::Min,
+            "max" => Metric::Max,
+            "p50" => Metric::P50,
+            "p90" => Metric::P90,
+            "p95" => Metric::P95,
+            "p99" => Metric::P99,
+            "success_rate" => Metric::SuccessRate,
+            "failed" => Metric::Failed,
+            _ => return None,
+        })
+    }
+
+    fn is_latency(self) -> bool {
+        !matches!(self, Metric::SuccessRate | Metric::Failed)
+    }
+
+    fn of_test(self, test: &TestStats) -> Option<f64> {
+        let ms = match self {
+            Metric::Avg => test.avg_ms,
+            Metric::Min => test.min_ms,
+            Metric::Max => test.max_ms,
+            Metric::P50 => test.p50_ms,
+            Metric::P90 => test.p90_ms,
+            Metric::P95 => test.p95_ms,
+            Metric::P99 => test.p99_ms,
+            Metric::SuccessRate => return Some(test.success_rate),
+            Metric::Failed => return Some(test.failed as f64),
+        };
+        ms.map(|ms| ms as f64)
+    }
+
+    fn of_overall(self, overall: &OverallStats) -> Option<f64> {
+        let ms = match self {
+            Metric::Avg => Some(overall.avg_ms).filter(|_| overall.successful > 0),
+            Metric::P50 => overall.p50_ms,
+            Metric::P90 => overall.p90_ms,
+            Metric::P95 => overall.p95_ms,
+            Metric::P99 => overall.p99_ms,
+            Metric::SuccessRate => return Some(overall.success_rate),
+            Metric::Failed => return Some(overall.failed as f64),
+            Metric::Min | Metric::Max => None,
+        };
+        ms.map(|ms| ms as f64)
+    }
+
+    /// The value as written in a message, with its unit
+    fn format(self, value: f64) -> String {
+        match self {
+            Metric::SuccessRate => format!("{:.1}%", value),
+            Metric::Failed => format!("{}", value),
+            _ => format!("{}ms", value),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    fn holds(self, actual: f64, limit: f64) -> bool {
+        match self {
+            Op::Lt => actual < limit,
+            Op::Le => actual <= limit,
+            Op::Gt => actual > limit,
+            Op::Ge => actual >= limit,
+        }
+    }
+}
+
+/// A limit the run must stay within, e.g. `getSlot.p95<200ms` or `overall.success_rate>99`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Assertion {
+    pub subject: Subject,
+    pub metric: Metric,
+    pub op: Op,
+    /// In ms for latencies, percent for the success rate
+    pub value: f64,
+    /// As given on the command line, for messages
+    text: String,
+}
+
+impl fmt::Display for Assertion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl FromStr for Assertion {
+    type Err = String;
+
+    /// `SUBJECT.METRIC` `<`, `<=`, `>` or `>=` a value; latencies take `ms` (default) or `s`,
+    /// the success rate an optional `%`
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let at = s.find(['<', '>']).ok_or_else(|| format!("'{}' has no <, <=, > or >=", s))?;
+        let (left, rest) = s.split_at(at);
+        let (op, value) = match rest.as_bytes() {
+            [b'<', b'=', ..] => (Op::Le, &rest[2..]),
+            [b'>', b'=', ..] => (Op::Ge, &rest[2..]),
+            [b'<', ..] => (Op::Lt, &rest[1..]),
+            _ => (Op::Gt, &rest[1..]),
+        };
+
+        // Methods may contain dots, metrics never do
+        let (subject, metric) = left
+            .trim()
+            .rsplit_once('.')
+            .ok_or_else(|| format!("'{}' should be METHOD.METRIC or overall.METRIC", left.trim()))?;
+        let metric = Metric::parse(metric).ok_or_else(|| {
+            format!("unknown metric '{}', expected avg, min, max, p50, p90, p95, p99, success_rate or failed", metric)
+        })?;
+        let subject = match subject {
+            "overall" if matches!(metric, Metric::Min | Metric::Max) => {
+                return Err("overall has no min or max, assert them per method".to_string())
+            }
+            "overall" => Subject::Overall,
+            method => Subject::Method(method.to_string()),
+        };
+
+        let value = value.trim();
+        let (number, scale) = if metric.is_latency() {
+            match value.strip_suffix("ms") {
+                Some(number) => (number, 1.0),
+                None => match value.strip_suffix('s') {
+                    Some(number) => (number, 1000.0),
+                    None => (value, 1.0),
+                },
+            }
+        } else if metric == Metric::SuccessRate {
+            (value.strip_suffix('%').unwrap_or(value), 1.0)
+        } else {
+            (value, 1.0)
+        };
+        let number: f64 = number.trim().parse().map_err(|_| format!("invalid value '{}'", value))?;
+
+        Ok(Assertion { subject, metric, op, value: number * scale, text: s.trim().to_string() })
+    }
+}
+
+/// One message per assertion a report breaks, naming the endpoint and the actual value
+pub fn check(report: &Report, assertions: &[Assertion]) -> Vec<String> {
+    let host = report.endpoint.host();
+    let mut failures = Vec::new();
+    for assertion in assertions {
+        let actual = match &assertion.subject {
+            Subject::Overall => assertion.metric.of_overall(&report.overall),
+            Subject::Method(method) => match report.tests.iter().find(|t| &t.name == method) {
+                Some(test) => assertion.metric.of_test(test),
+                None => {
+                    failures.push(format!("{} on {}: {} was not run", assertion, host, method));
+                    continue;
+                }
+            },
+        };
+        match actual {
+            Some(actual) if assertion.op.holds(actual, assertion.value) => {}
+            Some(actual) => {
+                failures.push(format!("{} on {}: was {}", assertion, host, assertion.metric.format(actual)))
+            }
+            None => failures.push(format!("{} on {}: no successful iterations to measure", assertion, host)),
+        }
+    }
+    failures
+}
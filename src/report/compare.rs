@@ -0,0 +1,128 @@
+use super::Report;
+use crate::style::{banner, icon, is_plain, rule};
+use colored::*;
+
+/// Width of each p50, p95 and success sub-column
+const CELL: usize = 8;
+
+/// Which way a column improves
+#[derive(Clone, Copy)]
+enum Better {
+    Lower,
+    Higher,
+}
+
+/// Per-endpoint p50, p95 and success rate of one row
+type Row = Vec<Option<(Option<u128>, Option<u128>, f64)>>;
+
+/// Index of every endpoint holding the best value, when at least two have one
+fn best<T: PartialOrd + Copy>(values: &[Option<T>], better: Better) -> Vec<usize> {
+    let present: Vec<T> = values.iter().flatten().copied().collect();
+    if present.len() < 2 {
+        return Vec::new();
+    }
+
+    let top = present
+        .iter()
+        .copied()
+        .reduce(|a, b| match better {
+            Better::Lower if b < a => b,
+            Better::Higher if b > a => b,
+            _ => a,
+        })
+        .expect("at least two values");
+    values.iter().enumerate().filter(|(_, v)| **v == Some(top)).map(|(i, _)| i).collect()
+}
+
+/// A padded cell, highlighted (or starred in plain mode) when it is the best of its row
+fn cell(text: String, highlight: bool) -> String {
+    if !highlight {
+        return format!("{:>width$}", text, width = CELL);
+    }
+    if is_plain() {
+        format!("{:>width$}", format!("{}*", text), width = CELL)
+    } else {
+        format!("{:>width$}", text, width = CELL).bright_green().bold().to_string()
+    }
+}
+
+fn write_row(out: &mut String, name: &str, name_width: usize, row: &Row) {
+    let p50: Vec<_> = row.iter().map(|v| v.and_then(|(p50, _, _)| p50)).collect();
+    let p95: Vec<_> = row.iter().map(|v| v.and_then(|(_, p95, _)| p95)).collect();
+    let success: Vec<_> = row.iter().map(|v| v.map(|(_, _, success)| success)).collect();
+    let (best_p50, best_p95, best_success) =
+        (best(&p50, Better::Lower), best(&p95, Better::Lower), best(&success, Better::Higher));
+
+    out.push_str(&format!("{:<width$}", name, width = name_width));
+    for i in 0..row.len() {
+        let ms = |v: Option<u128>| v.map(|v| v.to_string()).unwrap_or_else(|| "–".to_string());
+        out.push_str(" │");
+        out.push_str(&cell(ms(p50[i]), best_p50.contains(&i)));
+        out.push_str(&cell(ms(p95[i]), best_p95.contains(&i)));
+        out.push_str(&cell(
+            success[i].map(|v| format!("{:.1}%", v)).unwrap_or_else(|| "–".to_string()),
+            best_success.contains(&i),
+        ));
+    }
+    out.push('\n');
+}
+
+/// Methods × endpoints matrix of p50, p95 and success rate, best value per row highlighted
+pub fn render(reports: &[Report]) -> String {
+    // Every method any endpoint ran, in the order they were first run
+    let mut methods: Vec<&str> = Vec::new();
+    for test in reports.iter().flat_map(|r| &r.tests) {
+        if !methods.contains(&test.name.as_str()) {
+            methods.push(&test.name);
+        }
+    }
+    let name_width = methods.iter().map(|m| m.chars().count()).max().unwrap_or(0).max("Overall".len());
+    let column_width = CELL * 3;
+
+    let mut out = format!("\n{}\n", banner("ENDPOINT COMPARISON"));
+    for (i, report) in reports.iter().enumerate() {
+        out.push_str(&format!("{}[{}] {}\n", icon("🔗"), i + 1, report.endpoint.url).dimmed().to_string());
+    }
+    out.push('\n');
+
+    out.push_str(&" ".repeat(name_width));
+    for (i, report) in reports.iter().enumerate() {
+        let mut label = format!("[{}] {}", i + 1, report.endpoint.host());
+        if label.chars().count() > column_width {
+            label = label.chars().take(column_width - 1).chain(['…']).collect();
+        }
+        out.push_str(&format!(" │{:>width$}", label, width = column_width).bold().to_string());
+    }
+    out.push('\n');
+    out.push_str(&" ".repeat(name_width));
+    for _ in reports {
+        out.push_str(&format!(" │{:>width$}{:>width$}{:>width$}", "p50 ms", "p95 ms", "ok", width = CELL).dimmed().to_string());
+    }
+    out.push('\n');
+    out.push_str(&format!("{}\n", rule(false)));
+
+    for method in &methods {
+        let row: Row = reports
+            .iter()
+            .map(|r| {
+                r.tests
+                    .iter()
+                    .find(|t| t.name == *method && t.total > 0)
+                    .map(|t| (t.p50_ms, t.p95_ms, t.success_rate))
+            })
+            .collect();
+        write_row(&mut out, method, name_width, &row);
+    }
+
+    out.push_str(&format!("{}\n", rule(false)));
+    let overall: Row = reports
+        .iter()
+        .map(|r| Some((r.overall.p50_ms, r.overall.p95_ms, r.overall.success_rate)))
+        .collect();
+    write_row(&mut out, "Overall", name_width, &overall);
+
+    let legend = if is_plain() { "* best of the row" } else { "highlighted: best of the row" };
+    out.push_str(&format!("\n{}\n", legend.dimmed()));
+    out.push_str(&super::leaderboard::render(reports));
+    out
+}
@@ -0,0 +1,125 @@
+use super::Report;
+
+/// Quote a field if it contains anything that would break the row
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+const HEADER: &str = "test,iteration,success,skipped,duration_ms,ttfb_ms,download_ms,bytes,outlier,error_category,error";
+
+const SUMMARY_HEADER: &str = "test,total,successful,failed,skipped,success_rate,avg_ms,min_ms,max_ms,p50_ms,p90_ms,p95_ms,p99_ms,stddev_ms,cv,jitter_ms,avg_ttfb_ms,avg_download_ms,avg_bytes,throughput_mbps";
+
+/// One row per iteration, in the order tests were run
+fn rows(report: &Report) -> Vec<String> {
+    let mut rows = Vec::new();
+
+    for test in &report.tests {
+        for sample in &test.samples {
+            rows.push(format!(
+                "{},{},{},{},{},{},{},{},{},{},{}",
+                escape(&test.name),
+                sample.iteration,
+                sample.success,
+                sample.skipped,
+                sample.duration_ms,
+                sample.ttfb_ms,
+                sample.download_ms,
+                sample.bytes,
+                sample.outlier,
+                sample.error_category.as_deref().unwrap_or(""),
+                escape(sample.error.as_deref().unwrap_or(""))
+            ));
+        }
+    }
+
+    rows
+}
+
+fn opt(value: Option<u128>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// One row per test with its aggregate stats, followed by an `overall` row
+fn summary_rows(report: &Report) -> Vec<String> {
+    let mut rows = Vec::new();
+
+    for test in &report.tests {
+        rows.push(format!(
+            "{},{},{},{},{},{:.1},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            escape(&test.name),
+            test.total,
+            test.successful,
+            test.failed,
+            test.skipped,
+            test.success_rate,
+            opt(test.avg_ms),
+            opt(test.min_ms),
+            opt(test.max_ms),
+            opt(test.p50_ms),
+            opt(test.p90_ms),
+            opt(test.p95_ms),
+            opt(test.p99_ms),
+            test.stddev_ms.map(|v| format!("{:.1}", v)).unwrap_or_default(),
+            test.cv.map(|v| format!("{:.3}", v)).unwrap_or_default(),
+            opt(test.jitter_ms),
+            opt(test.avg_ttfb_ms),
+            opt(test.avg_download_ms),
+            test.avg_bytes.map(|v| v.to_string()).unwrap_or_default(),
+            test.throughput_mbps.map(|v| format!("{:.3}", v)).unwrap_or_default()
+        ));
+    }
+
+    let overall = &report.overall;
+    rows.push(format!(
+        "overall,{},{},{},{},{:.1},{},,,{},{},{},{},,,,,,,",
+        overall.total,
+        overall.successful,
+        overall.failed,
+        overall.skipped,
+        overall.success_rate,
+        overall.avg_ms,
+        opt(overall.p50_ms),
+        opt(overall.p90_ms),
+        opt(overall.p95_ms),
+        opt(overall.p99_ms)
+    ));
+
+    rows
+}
+
+fn document(header: &str, rows: Vec<String>) -> String {
+    let mut out = format!("{}\n", header);
+    for row in rows {
+        out.push_str(&row);
+        out.push('\n');
+    }
+    out
+}
+
+pub fn render(report: &Report) -> String {
+    document(HEADER, rows(report))
+}
+
+pub fn render_summary(report: &Report) -> String {
+    document(SUMMARY_HEADER, summary_rows(report))
+}
+
+/// Rows of every endpoint in one file, told apart by a leading `endpoint` column
+pub fn render_all(reports: &[Report], summary: bool) -> String {
+    let (header, rows): (_, fn(&Report) -> Vec<String>) =
+        if summary { (SUMMARY_HEADER, summary_rows) } else { (HEADER, rows) };
+
+    let rows = reports
+        .iter()
+        .flat_map(|report| {
+            let endpoint = escape(&report.endpoint.url);
+            rows(report).into_iter().map(move |row| format!("{},{}", endpoint, row))
+        })
+        .collect();
+    document(&format!("endpoint,{}", header), rows)
+}
+
@@ -0,0 +1,283 @@
+use super::host;
+use crate::style::{banner, icon, is_plain, rule};
+use anyhow::{bail, Context, Result};
+use colored::*;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// The numbers of one method (or the overall stats) a diff compares
+#[derive(Serialize, Clone, Debug)]
+pub struct Stats {
+    pub p50_ms: Option<u128>,
+    pub p95_ms: Option<u128>,
+    pub success_rate: Option<f64>,
+}
+
+impl Stats {
+    fn from_value(value: &Value) -> Stats {
+        let ms = |key: &str| value[key].as_u64().map(u128::from);
+        Stats { p50_ms: ms("p50_ms"), p95_ms: ms("p95_ms"), success_rate: value["success_rate"].as_f64() }
+    }
+}
+
+/// What a JSON report holds for one endpoint, as far as a diff needs it
+pub struct Snapshot {
+    pub endpoint: String,
+    pub tests: Vec<(String, Stats)>,
+    pub overall: Stats,
+}
+
+/// Every endpoint of a report written with `--format json`, one object or an array of them
+pub fn load(path: &Path) -> Result<Vec<Snapshot>> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let json: Value = serde_json::from_str(&text).with_context(|| format!("{} is not JSON", path.display()))?;
+    let reports = match json {
+        Value::Array(reports) => reports,
+        report => vec![report],
+    };
+
+    reports
+        .iter()
+        .map(|report| {
+            let Some(tests) = report["tests"].as_array() else {
+                bail!("{} is not a JSON report of this tool", path.display());
+            };
+            Ok(Snapshot {
+                endpoint: report["endpoint"]["url"].as_str().unwrap_or_default().to_string(),
+                tests: tests
+                    .iter()
+                    .map(|test| (test["name"].as_str().unwrap_or_default().to_string(), Stats::from_value(test)))
+                    .collect(),
+                overall: Stats::from_value(&report["overall"]),
+            })
+        })
+        .collect()
+}
+
+/// One method's numbers before and after, with relative latency and absolute success changes
+#[derive(Serialize, Clone, Debug)]
+pub struct Delta {
+    pub method: String,
+    pub old: Option<Stats>,
+    pub new: Option<Stats>,
+    /// Percent change of p50, negative is faster
+    pub p50_pct: Option<f64>,
+    pub p95_pct: Option<f64>,
+    /// Percentage points gained or lost
+    pub success_pp: Option<f64>,
+}
+
+impl Delta {
+    fn new(method: &str, old: Option<&Stats>, new: Option<&Stats>) -> Delta {
+        let pct = |old: Option<u128>, new: Option<u128>| match (old, new) {
+            (Some(old), Some(new)) if old > 0 => Some((new as f64 - old as f64) / old as f64 * 100.0),
+            _ => None,
+        };
+        Delta {
+            method: method.to_string(),
+            p50_pct: pct(old.and_then(|s| s.p50_ms), new.and_then(|s| s.p50_ms)),
+            p95_pct: pct(old.and_then(|s| s.p95_ms), new.and_then(|s| s.p95_ms)),
+            success_pp: old
+                .and_then(|s| s.success_rate)
+                .zip(new.and_then(|s| s.success_rate))
+                .map(|(old, new)| new - old),
+            old: old.cloned(),
+            new: new.cloned(),
+        }
+    }
+}
+
+/// The comparison of one endpoint's reports
+#[derive(Serialize, Clone, Debug)]
+pub struct EndpointDiff {
+    pub old_endpoint: String,
+    pub new_endpoint: String,
+    pub methods: Vec<Delta>,
+    pub overall: Delta,
+}
+
+/// Pair the endpoints of both reports: by URL, or the two sole endpoints with each other so
+/// two providers can be compared
+pub fn diff(old: &[Snapshot], new: &[Snapshot]) -> Result<Vec<EndpointDiff>> {
+    let pairs: Vec<(&Snapshot, &Snapshot)> = match (old, new) {
+        ([old], [new]) => vec![(old, new)],
+        _ => old
+            .iter()
+            .filter_map(|o| new.iter().find(|n| n.endpoint == o.endpoint).map(|n| (o, n)))
+            .collect(),
+    };
+    if pairs.is_empty() {
+        bail!("The reports have no endpoint in common");
+    }
+
+    Ok(pairs
+        .into_iter()
+        .map(|(old, new)| {
+            // Methods of the old report first, then those only the new one ran
+            let mut methods: Vec<&str> = old.tests.iter().map(|(name, _)| name.as_str()).collect();
+            for (name, _) in &new.tests {
+                if !methods.contains(&name.as_str()) {
+                    methods.push(name);
+                }
+            }
+            let find = |snapshot: &Snapshot, method: &str| {
+                snapshot.tests.iter().find(|(name, _)| name == method).map(|(_, stats)| stats.clone())
+            };
+            EndpointDiff {
+                old_endpoint: old.endpoint.clone(),
+                new_endpoint: new.endpoint.clone(),
+                methods: methods
+                    .iter()
+                    .map(|method| Delta::new(method, find(old, method).as_ref(), find(new, method).as_ref()))
+                    .collect(),
+                overall: Delta::new("Overall", Some(&old.overall), Some(&new.overall)),
+            }
+        })
+        .collect())
+}
+
+/// Whether a change is better, worse or within the noise band
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Verdict {
+    Better,
+    Worse,
+    Same,
+}
+
+fn latency_verdict(pct: Option<f64>, noise_pct: f64) -> Verdict {
+    match pct {
+        Some(pct) if pct < -noise_pct => Verdict::Better,
+        Some(pct) if pct > noise_pct => Verdict::Worse,
+        _ => Verdict::Same,
+    }
+}
+
+fn success_verdict(pp: Option<f64>) -> Verdict {
+    match pp {
+        Some(pp) if pp >= 0.1 => Verdict::Better,
+        Some(pp) if pp <= -0.1 => Verdict::Worse,
+        _ => Verdict::Same,
+    }
+}
+
+/// A right-aligned change, green when better and red when worse (or marked * / ! in plain mode)
+fn change(text: String, verdict: Verdict, width: usize) -> String {
+    let padded = format!("{:>width$}", text, width = width);
+    match (verdict, is_plain()) {
+        (Verdict::Same, _) => padded.dimmed().to_string(),
+        (Verdict::Better, true) => format!("{:>width$}", format!("{}*", text), width = width),
+        (Verdict::Worse, true) => format!("{:>width$}", format!("{}!", text), width = width),
+        (Verdict::Better, false) => padded.bright_green().to_string(),
+        (Verdict::Worse, false) => padded.red().bold().to_string(),
+    }
+}
+
+fn ms(value: Option<u128>) -> String {
+    value.map(|ms| ms.to_string()).unwrap_or_else(|| "–".to_string())
+}
+
+fn pct(value: Option<f64>) -> String {
+    value.map(|pct| format!("{:+.1}%", pct)).unwrap_or_else(|| "–".to_string())
+}
+
+fn rate(value: Option<f64>) -> String {
+    value.map(|rate| format!("{:.1}%", rate)).unwrap_or_else(|| "–".to_string())
+}
+
+/// Write one method's row, returning its verdicts for the summary
+fn write_row(out: &mut String, delta: &Delta, name_width: usize, noise_pct: f64) -> [Verdict; 3] {
+    let (old, new) = (delta.old.as_ref(), delta.new.as_ref());
+    let verdicts = [
+        latency_verdict(delta.p50_pct, noise_pct),
+        latency_verdict(delta.p95_pct, noise_pct),
+        success_verdict(delta.success_pp),
+    ];
+    let mut name = delta.method.clone();
+    if old.is_none() {
+        name.push_str(" (new)");
+    } else if new.is_none() {
+        name.push_str(" (gone)");
+    }
+
+    out.push_str(&format!(
+        "{:<name_width$} │{:>7}{:>7}{} │{:>7}{:>7}{} │{:>8}{:>8}{}\n",
+        name,
+        ms(old.and_then(|s| s.p50_ms)),
+        ms(new.and_then(|s| s.p50_ms)),
+        change(pct(delta.p50_pct), verdicts[0], 9),
+        ms(old.and_then(|s| s.p95_ms)),
+        ms(new.and_then(|s| s.p95_ms)),
+        change(pct(delta.p95_pct), verdicts[1], 9),
+        rate(old.and_then(|s| s.success_rate)),
+        rate(new.and_then(|s| s.success_rate)),
+        change(delta.success_pp.map(|pp| format!("{:+.1}pp", pp)).unwrap_or_else(|| "–".to_string()), verdicts[2], 9),
+        name_width = name_width
+    ));
+    verdicts
+}
+
+/// Per-method table of p50, p95 and success before and after, changes beyond `noise_pct`
+/// percent (latency) or 0.1 points (success) colored
+pub fn render(diffs: &[EndpointDiff], noise_pct: f64) -> String {
+    let mut out = format!("\n{}\n", banner("REPORT DIFF"));
+    for diff in diffs {
+        let name_width = diff
+            .methods
+            .iter()
+            .map(|d| d.method.chars().count() + " (gone)".len())
+            .max()
+            .unwrap_or(0)
+            .max("Overall".len());
+
+        let title = if diff.old_endpoint == diff.new_endpoint {
+            host(&diff.old_endpoint)
+        } else {
+            format!("{} → {}", host(&diff.old_endpoint), host(&diff.new_endpoint))
+        };
+        out.push_str(&format!("\n{}{}\n\n", icon("🔗"), title.bold()));
+        out.push_str(
+            &format!(
+                "{:<name_width$} │{:>7}{:>7}{:>9} │{:>7}{:>7}{:>9} │{:>8}{:>8}{:>9}\n",
+                "",
+                "p50 old",
+                "new",
+                "Δ",
+                "p95 old",
+                "new",
+                "Δ",
+                "ok old",
+                "new",
+                "Δ",
+                name_width = name_width
+            )
+            .dimmed()
+            .to_string(),
+        );
+        out.push_str(&format!("{}\n", rule(false)));
+
+        let (mut improved, mut regressed) = (0, 0);
+        for delta in &diff.methods {
+            let verdicts = write_row(&mut out, delta, name_width, noise_pct);
+            if verdicts.contains(&Verdict::Worse) {
+                regressed += 1;
+            } else if verdicts.contains(&Verdict::Better) {
+                improved += 1;
+            }
+        }
+        out.push_str(&format!("{}\n", rule(false)));
+        write_row(&mut out, &diff.overall, name_width, noise_pct);
+
+        out.push_str(&format!(
+            "\n{} improved, {} regressed, {} unchanged (latency noise band ±{}%)\n",
+            improved.to_string().green(),
+            regressed.to_string().red(),
+            diff.methods.len() - improved - regressed,
+            noise_pct
+        ));
+    }
+    if is_plain() {
+        out.push_str(&format!("{}\n", "* better, ! worse".dimmed()));
+    }
+    out
+}
@@ -0,0 +1,44 @@
+use super::Report;
+
+/// Workflow command data must have `%`, CR and LF escaped
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Properties additionally escape `:` and `,`
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// `::error` for methods with failed iterations, `::warning` for slow ones
+pub fn annotations(report: &Report) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for test in &report.tests {
+        let title = escape_property(&format!("{} on {}", test.name, report.endpoint.url));
+
+        if test.failed > 0 {
+            let first_error = test.errors.first().map(String::as_str).unwrap_or("unknown error");
+            lines.push(format!(
+                "::error title={}::{} of {} iterations failed: {}",
+                title,
+                test.failed,
+                test.total,
+                escape_data(first_error)
+            ));
+        }
+
+        // Anything rated Slow or worse
+        if let Some(rating_ms) = test.rating_ms.filter(|ms| *ms > report.config.speed_thresholds.average) {
+            lines.push(format!(
+                "::warning title={}::{} latency {} ms ({})",
+                title,
+                report.config.rating_metric.label(),
+                rating_ms,
+                test.speed_rating.unwrap_or_default()
+            ));
+        }
+    }
+
+    lines
+}
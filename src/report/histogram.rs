@@ -0,0 +1,104 @@
+use hdrhistogram::Histogram;
+
+/// Significant decimal digits kept for every recorded value
+const SIGFIG: u8 = 3;
+
+/// Latencies in ms recorded into an HDR histogram, so memory stays constant
+/// however many iterations a test runs
+#[derive(Clone)]
+pub struct LatencyHistogram {
+    inner: Histogram<u64>,
+    sum: u128,
+    sum_squares: f64,
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        LatencyHistogram {
+            inner: Histogram::new(SIGFIG).expect("3 significant figures is a valid precision"),
+            sum: 0,
+            sum_squares: 0.0,
+        }
+    }
+
+    pub fn record(&mut self, duration_ms: u128) {
+        // The histogram auto-resizes, so recording can only fail for values beyond u64
+        let value = u64::try_from(duration_ms).unwrap_or(u64::MAX);
+        self.inner.saturating_record(value);
+        self.sum += duration_ms;
+        self.sum_squares += (duration_ms as f64).powi(2);
+    }
+
+    pub fn len(&self) -> u64 {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Exact mean, from the running sum rather than the bucketed values
+    pub fn mean(&self) -> Option<u128> {
+        (!self.is_empty()).then(|| self.sum / self.len() as u128)
+    }
+
+    /// Population standard deviation, exact like the mean
+    pub fn stddev(&self) -> Option<f64> {
+        if self.is_empty() {
+            return None;
+        }
+        let n = self.len() as f64;
+        let mean = self.sum as f64 / n;
+        Some((self.sum_squares / n - mean * mean).max(0.0).sqrt())
+    }
+
+    pub fn min(&self) -> Option<u128> {
+        (!self.is_empty()).then(|| self.inner.min() as u128)
+    }
+
+    pub fn max(&self) -> Option<u128> {
+        (!self.is_empty()).then(|| self.inner.max() as u128)
+    }
+
+    /// Value at the given percentile (0–100), accurate to the histogram precision
+    pub fn percentile(&self, pct: f64) -> Option<u128> {
+        (!self.is_empty()).then(|| self.inner.value_at_percentile(pct) as u128)
+    }
+}
+
+impl LatencyHistogram {
+    /// Split the recorded range into `buckets` equal-width ranges of (low, high, count)
+    pub fn buckets(&self, buckets: usize) -> Vec<(u128, u128, u64)> {
+        let (Some(min), Some(max)) = (self.min(), self.max()) else {
+            return Vec::new();
+        };
+
+        let width = ((max - min) / buckets as u128).max(1);
+        let mut ranges = Vec::new();
+        let mut low = min;
+        while low <= max && ranges.len() < buckets {
+            // The last bucket absorbs the remainder of the division
+            let high = if ranges.len() + 1 == buckets { max } else { (low + width - 1).min(max) };
+            let count = self.inner.count_between(low as u64, high as u64);
+            ranges.push((low, high, count));
+            low = high + 1;
+        }
+        ranges
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        LatencyHistogram::new()
+    }
+}
+
+impl FromIterator<u128> for LatencyHistogram {
+    fn from_iter<I: IntoIterator<Item = u128>>(iter: I) -> Self {
+        let mut histogram = LatencyHistogram::new();
+        for duration_ms in iter {
+            histogram.record(duration_ms);
+        }
+        histogram
+    }
+}
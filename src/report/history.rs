@@ -0,0 +1,210 @@
+use super::host;
+use crate::sinks::sqlite::HistoryRun;
+use crate::style::{banner, icon, is_plain, rule};
+use colored::*;
+use serde::Serialize;
+
+/// Fitted p50 change over the runs, in percent of the mean, beyond which a method is trending
+const TREND_PCT: f64 = 10.0;
+
+/// Fitted success rate points lost over the runs beyond which a method is degrading regardless of latency
+const SUCCESS_DROP_PP: f64 = 1.0;
+
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const PLAIN_BARS: [char; 8] = ['_', '.', '-', '~', '=', '+', '*', '#'];
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Improving,
+    Degrading,
+    Stable,
+}
+
+/// One method of one endpoint across its stored runs, oldest first
+#[derive(Serialize, Clone, Debug)]
+pub struct Trend {
+    pub endpoint: String,
+    pub method: String,
+    /// Median latency per run, missing where the run had no successful iteration of the method
+    pub p50_ms: Vec<Option<u128>>,
+    /// Missing where the run did not include the method
+    pub success_rate: Vec<Option<f64>>,
+    /// Change from the first to the last run along a least-squares fit, in percent of the mean
+    pub latency_change_pct: Option<f64>,
+    /// Change along the fit in percentage points
+    pub success_change_pp: Option<f64>,
+    pub direction: Direction,
+}
+
+/// Change from the first to the last point of a least-squares line through the present values,
+/// with their mean; needs at least three of them
+fn fitted_change(values: &[Option<f64>]) -> Option<(f64, f64)> {
+    let points: Vec<(f64, f64)> =
+        values.iter().enumerate().filter_map(|(i, v)| v.map(|v| (i as f64, v))).collect();
+    if points.len() < 3 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if variance == 0.0 {
+        return None;
+    }
+    Some((covariance / variance * (values.len() - 1) as f64, mean_y))
+}
+
+/// The trend of every method of every endpoint in the runs
+pub fn trends(runs: &[HistoryRun]) -> Vec<Trend> {
+    let mut endpoints: Vec<&str> = Vec::new();
+    for run in runs {
+        if !endpoints.contains(&run.endpoint.as_str()) {
+            endpoints.push(&run.endpoint);
+        }
+    }
+
+    let mut trends = Vec::new();
+    for endpoint in endpoints {
+        let runs: Vec<&HistoryRun> = runs.iter().filter(|r| r.endpoint == endpoint).collect();
+        let mut methods: Vec<&str> = Vec::new();
+        for test in runs.iter().flat_map(|r| &r.tests) {
+            if !methods.contains(&test.name.as_str()) {
+                methods.push(&test.name);
+            }
+        }
+
+        for method in methods {
+            let tests: Vec<_> = runs.iter().map(|r| r.tests.iter().find(|t| t.name == method)).collect();
+            let p50_ms: Vec<Option<u128>> = tests.iter().map(|t| t.and_then(|t| t.p50_ms)).collect();
+            let success_rate: Vec<Option<f64>> = tests.iter().map(|t| t.map(|t| t.success_rate)).collect();
+
+            let latency_change_pct = fitted_change(&p50_ms.iter().map(|v| v.map(|ms| ms as f64)).collect::<Vec<_>>())
+                .filter(|(_, mean)| *mean > 0.0)
+                .map(|(change, mean)| change / mean * 100.0);
+            let success_change_pp = fitted_change(&success_rate).map(|(change, _)| change);
+
+            let direction = match (latency_change_pct, success_change_pp) {
+                (_, Some(pp)) if pp < -SUCCESS_DROP_PP => Direction::Degrading,
+                (Some(pct), _) if pct > TREND_PCT => Direction::Degrading,
+                (Some(pct), _) if pct < -TREND_PCT => Direction::Improving,
+                (_, Some(pp)) if pp > SUCCESS_DROP_PP => Direction::Improving,
+                _ => Direction::Stable,
+            };
+            trends.push(Trend {
+                endpoint: endpoint.to_string(),
+                method: method.to_string(),
+                p50_ms,
+                success_rate,
+                latency_change_pct,
+                success_change_pp,
+                direction,
+            });
+        }
+    }
+    trends
+}
+
+/// One bar per run scaled between the lowest and highest p50, blank where there is none
+fn sparkline(values: &[Option<u128>]) -> String {
+    let bars = if is_plain() { PLAIN_BARS } else { BARS };
+    let present = values.iter().flatten();
+    let (Some(low), Some(high)) = (present.clone().min(), present.max()) else {
+        return " ".repeat(values.len());
+    };
+    let span = (high - low).max(1) as f64;
+    values
+        .iter()
+        .map(|v| match v {
+            Some(v) => bars[((*v - low) as f64 / span * (bars.len() - 1) as f64).round() as usize],
+            None => ' ',
+        })
+        .collect()
+}
+
+fn direction(direction: Direction) -> ColoredString {
+    match direction {
+        Direction::Improving => "improving".green(),
+        Direction::Degrading => "degrading".red().bold(),
+        Direction::Stable => "stable".dimmed(),
+    }
+}
+
+/// Per endpoint, a p50 sparkline, the latest p50 and success rate, and the direction of each method
+pub fn render(runs: &[HistoryRun], trends: &[Trend]) -> String {
+    let mut out = format!("\n{}\n", banner("HISTORY"));
+    let name_width = trends.iter().map(|t| t.method.chars().count()).max().unwrap_or(0).max("Method".len());
+
+    let mut endpoints: Vec<&str> = Vec::new();
+    for trend in trends {
+        if !endpoints.contains(&trend.endpoint.as_str()) {
+            endpoints.push(&trend.endpoint);
+        }
+    }
+    for endpoint in endpoints {
+        let stored: Vec<&HistoryRun> = runs.iter().filter(|r| r.endpoint == endpoint).collect();
+        let date = |run: Option<&&HistoryRun>| {
+            run.and_then(|r| r.timestamp.get(..10)).unwrap_or_default().to_string()
+        };
+        out.push_str(&format!(
+            "\n{}{} {}\n\n",
+            icon("🔗"),
+            host(endpoint).bold(),
+            format!("({} runs, {} → {})", stored.len(), date(stored.first()), date(stored.last())).dimmed()
+        ));
+
+        let width = stored.len().max("p50 per run".len());
+        out.push_str(
+            &format!(
+                "{:<name_width$}  {:<width$}  {:>8}  {:>8}  {:>7}  {}\n",
+                "Method",
+                "p50 per run",
+                "last p50",
+                "Δ p50",
+                "ok",
+                "trend",
+                name_width = name_width,
+                width = width
+            )
+            .dimmed()
+            .to_string(),
+        );
+        out.push_str(&format!("{}\n", rule(false)));
+
+        for trend in trends.iter().filter(|t| t.endpoint == endpoint) {
+            let last_p50 = trend.p50_ms.iter().rev().flatten().next();
+            let last_success = trend.success_rate.iter().rev().flatten().next();
+            out.push_str(&format!(
+                "{:<name_width$}  {:<width$}  {:>8}  {:>8}  {:>7}  {}\n",
+                trend.method,
+                sparkline(&trend.p50_ms),
+                last_p50.map(|ms| format!("{}ms", ms)).unwrap_or_else(|| "–".to_string()),
+                trend.latency_change_pct.map(|pct| format!("{:+.1}%", pct)).unwrap_or_else(|| "–".to_string()),
+                last_success.map(|rate| format!("{:.1}%", rate)).unwrap_or_else(|| "–".to_string()),
+                direction(trend.direction),
+                name_width = name_width,
+                width = width
+            ));
+        }
+    }
+
+    let count = |d: Direction| trends.iter().filter(|t| t.direction == d).count();
+    out.push_str(&format!(
+        "\n{} improving, {} degrading, {} stable\n",
+        count(Direction::Improving).to_string().green(),
+        count(Direction::Degrading).to_string().red(),
+        count(Direction::Stable)
+    ));
+    for trend in trends.iter().filter(|t| t.direction == Direction::Degrading) {
+        out.push_str(&format!(
+            "{}{} on {}: p50 {}, success {}\n",
+            icon("📉"),
+            trend.method.yellow(),
+            host(&trend.endpoint),
+            trend.latency_change_pct.map(|pct| format!("{:+.1}%", pct)).unwrap_or_else(|| "–".to_string()),
+            trend.success_change_pp.map(|pp| format!("{:+.1}pp", pp)).unwrap_or_else(|| "–".to_string())
+        ));
+    }
+    out
+}
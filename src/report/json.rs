@@ -0,0 +1,32 @@
+use super::Report;
+use anyhow::Result;
+use serde_json::Value;
+
+fn to_value(report: &Report) -> Result<Value> {
+    let mut value = serde_json::to_value(report)?;
+
+    // Aggregates only, for compact reports
+    if !report.include_samples {
+        if let Some(tests) = value["tests"].as_array_mut() {
+            for test in tests.iter_mut().filter_map(Value::as_object_mut) {
+                test.remove("samples");
+            }
+        }
+    }
+
+    Ok(value)
+}
+
+pub fn render(report: &Report) -> Result<String> {
+    let mut json = serde_json::to_string_pretty(&to_value(report)?)?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// An array with one full report per endpoint, in the order they were tested
+pub fn render_all(reports: &[Report]) -> Result<String> {
+    let values = reports.iter().map(to_value).collect::<Result<Vec<_>>>()?;
+    let mut json = serde_json::to_string_pretty(&values)?;
+    json.push('\n');
+    Ok(json)
+}
@@ -0,0 +1,97 @@
+use super::Report;
+
+/// Escape text for use in XML attributes and character data
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn seconds(ms: u128) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+/// Failed and skipped methods, plus the summed duration of every iteration
+fn totals(report: &Report) -> (usize, usize, u128) {
+    let failures = report.tests.iter().filter(|t| t.failed > 0).count();
+    let skipped = report.tests.iter().filter(|t| t.total == 0).count();
+    let total_ms = report.tests.iter().flat_map(|t| &t.samples).map(|s| s.duration_ms).sum();
+    (failures, skipped, total_ms)
+}
+
+/// One test suite per endpoint, with one test case per RPC method failing if any of its iterations failed
+pub fn render(reports: &[Report]) -> String {
+    let (mut failures, mut skipped, mut total_ms) = (0, 0, 0);
+    for report in reports {
+        let (f, s, ms) = totals(report);
+        failures += f;
+        skipped += s;
+        total_ms += ms;
+    }
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuites name=\"solana-rpc-checker\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+        reports.iter().map(|r| r.tests.len()).sum::<usize>(),
+        failures,
+        skipped,
+        seconds(total_ms)
+    ));
+    for report in reports {
+        write_testsuite(&mut out, report);
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+fn write_testsuite(out: &mut String, report: &Report) {
+    let overall = &report.overall;
+    let (failures, skipped, total_ms) = totals(report);
+
+    out.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\" timestamp=\"{}\">\n",
+        escape(&report.endpoint.url),
+        report.tests.len(),
+        failures,
+        skipped,
+        seconds(total_ms),
+        report.timestamp.format("%Y-%m-%dT%H:%M:%S")
+    ));
+    out.push_str("    <properties>\n");
+    out.push_str(&format!("      <property name=\"mode\" value=\"{}\"/>\n", report.config.mode));
+    out.push_str(&format!("      <property name=\"iterations\" value=\"{}\"/>\n", report.config.iterations));
+    if let Some(duration_ms) = report.config.duration_ms {
+        out.push_str(&format!("      <property name=\"duration\" value=\"{}\"/>\n", seconds(duration_ms)));
+    }
+    out.push_str(&format!("      <property name=\"success_rate\" value=\"{:.1}\"/>\n", overall.success_rate));
+    out.push_str(&format!("      <property name=\"avg_ms\" value=\"{}\"/>\n", overall.avg_ms));
+    out.push_str("    </properties>\n");
+
+    for test in &report.tests {
+        let time_ms: u128 = test.samples.iter().map(|s| s.duration_ms).sum();
+        out.push_str(&format!(
+            "    <testcase classname=\"rpc\" name=\"{}\" time=\"{}\"",
+            escape(&test.name),
+            seconds(time_ms)
+        ));
+
+        if test.total == 0 {
+            out.push_str(">\n      <skipped message=\"max duration exceeded\"/>\n    </testcase>\n");
+        } else if test.failed > 0 {
+            out.push_str(">\n");
+            out.push_str(&format!(
+                "      <failure message=\"{}/{} iterations failed\" type=\"RpcError\">{}</failure>\n",
+                test.failed,
+                test.total,
+                escape(&test.errors.join("\n"))
+            ));
+            out.push_str("    </testcase>\n");
+        } else {
+            out.push_str("/>\n");
+        }
+    }
+
+    out.push_str("  </testsuite>\n");
+}
@@ -0,0 +1,128 @@
+use super::Report;
+use crate::style::{banner, is_plain, rule};
+use colored::*;
+
+/// Reports ordered by composite score, best first; ties keep the order endpoints were tested in
+fn ranked(reports: &[Report]) -> Vec<&Report> {
+    let mut ranked: Vec<&Report> = reports.iter().collect();
+    ranked.sort_by(|a, b| b.score.score.total_cmp(&a.score.score));
+    ranked
+}
+
+/// Medal for the podium, the plain position for everyone else
+fn position(rank: usize) -> String {
+    match rank {
+        1 if !is_plain() => "🥇".to_string(),
+        2 if !is_plain() => "🥈".to_string(),
+        3 if !is_plain() => "🥉".to_string(),
+        _ => format!("{}.", rank),
+    }
+}
+
+/// Points behind the leader, or a dash for the leader itself
+fn delta(leader: f64, score: f64, rank: usize) -> String {
+    if rank == 1 {
+        "–".to_string()
+    } else {
+        format!("-{:.1}", leader - score)
+    }
+}
+
+/// Share of race rounds won, for endpoints that were raced
+fn win_pct(report: &Report) -> String {
+    report.race.as_ref().map(|race| format!("{:.1}%", race.win_pct)).unwrap_or_else(|| "–".to_string())
+}
+
+/// Endpoints ranked by composite score, with the distance to the leader
+pub fn render(reports: &[Report]) -> String {
+    let ranked = ranked(reports);
+    let leader = ranked.first().map(|r| r.score.score).unwrap_or_default();
+    let host_width = ranked.iter().map(|r| r.endpoint.host().chars().count()).max().unwrap_or(0).max("Endpoint".len());
+
+    let raced = reports.iter().any(|r| r.race.is_some());
+
+    let mut out = format!("\n{}\n", banner("LEADERBOARD"));
+    let mut header = format!(
+        "{:<4} {:<width$} {:>7} {:>7} {:>8} {:>8}",
+        "#",
+        "Endpoint",
+        "Score",
+        "Δ",
+        "Success",
+        "p50 ms",
+        width = host_width
+    );
+    if raced {
+        header.push_str(&format!(" {:>7}", "Wins"));
+    }
+    out.push_str(&format!("{}\n", header.bold()));
+    out.push_str(&format!("{}\n", rule(false)));
+
+    for (i, report) in ranked.iter().enumerate() {
+        let rank = i + 1;
+        // Emoji take two columns, so their cell is one character narrower
+        let position = position(rank);
+        let position_width = if position.ends_with('.') { 4 } else { 3 };
+        let mut line = format!(
+            "{:<pw$} {:<width$} {:>7.1} {:>7} {:>7.1}% {:>8}",
+            position,
+            report.endpoint.host(),
+            report.score.score,
+            delta(leader, report.score.score, rank),
+            report.overall.success_rate,
+            report.overall.p50_ms.map(|v| v.to_string()).unwrap_or_else(|| "–".to_string()),
+            pw = position_width,
+            width = host_width
+        );
+        if raced {
+            line.push_str(&format!(" {:>7}", win_pct(report)));
+        }
+        if rank == 1 {
+            out.push_str(&format!("{}\n", line.bright_green().bold()));
+        } else {
+            out.push_str(&format!("{}\n", line));
+        }
+    }
+
+    out
+}
+
+/// The leaderboard as a Markdown table
+pub fn render_markdown(reports: &[Report]) -> String {
+    let ranked = ranked(reports);
+    let leader = ranked.first().map(|r| r.score.score).unwrap_or_default();
+
+    let raced = reports.iter().any(|r| r.race.is_some());
+
+    let mut out = String::from("## Leaderboard\n\n");
+    if raced {
+        out.push_str("| # | Endpoint | Score | Δ | Success | P50 | Race wins |\n");
+        out.push_str("|---|----------|------:|--:|--------:|----:|----------:|\n");
+    } else {
+        out.push_str("| # | Endpoint | Score | Δ | Success | P50 |\n");
+        out.push_str("|---|----------|------:|--:|--------:|----:|\n");
+    }
+    for (i, report) in ranked.iter().enumerate() {
+        let rank = i + 1;
+        let medal = match rank {
+            1 => "🥇".to_string(),
+            2 => "🥈".to_string(),
+            3 => "🥉".to_string(),
+            _ => rank.to_string(),
+        };
+        out.push_str(&format!(
+            "| {} | `{}` | {:.1} | {} | {:.1}% | {} |",
+            medal,
+            report.endpoint.host(),
+            report.score.score,
+            delta(leader, report.score.score, rank),
+            report.overall.success_rate,
+            report.overall.p50_ms.map(|v| format!("{} ms", v)).unwrap_or_else(|| "–".to_string())
+        ));
+        if raced {
+            out.push_str(&format!(" {} |", win_pct(report)));
+        }
+        out.push('\n');
+    }
+    out
+}
@@ -0,0 +1,98 @@
+use super::Report;
+
+fn ms(value: Option<u128>) -> String {
+    value.map(|v| format!("{} ms", v)).unwrap_or_else(|| "–".to_string())
+}
+
+/// Escape characters that would break a table cell
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+pub fn render(report: &Report) -> String {
+    let overall = &report.overall;
+    let mut out = String::new();
+
+    out.push_str("## Solana RPC Performance Report\n\n");
+    out.push_str(&format!("- **Endpoint:** `{}`\n", report.endpoint.url));
+    out.push_str(&format!("- **Timestamp:** {}\n", report.timestamp));
+    out.push_str(&format!(
+        "- **Overall:** {:.1}% success, {} ms avg, {} by {} ({} ms)\n",
+        overall.success_rate,
+        overall.avg_ms,
+        overall.speed_rating,
+        report.config.rating_metric.label(),
+        overall.rating_ms.unwrap_or(0)
+    ));
+    out.push_str(&format!("- **Score:** {:.0}/100\n", report.score.score));
+    if let (Some(p50), Some(p95), Some(p99)) = (overall.p50_ms, overall.p95_ms, overall.p99_ms) {
+        out.push_str(&format!("- **Percentiles:** p50 {} ms, p95 {} ms, p99 {} ms\n", p50, p95, p99));
+    }
+    if overall.skipped > 0 {
+        out.push_str(&format!("- **Skipped iterations:** {}\n", overall.skipped));
+    }
+    if report.rate_limits.throttled > 0 {
+        out.push_str(&format!("- **Throttled (HTTP 429):** {}\n", report.rate_limits.throttled));
+    }
+    for (name, value) in &report.rate_limits.headers {
+        out.push_str(&format!("- **`{}`:** {}\n", name, value));
+    }
+    out.push('\n');
+
+    out.push_str("| Test | Success | Avg | Min | Max | P50 | P90 | P95 | P99 | Stddev | Jitter | Rating |\n");
+    out.push_str("|------|--------:|----:|----:|----:|----:|----:|----:|----:|-------:|-------:|--------|\n");
+    for test in &report.tests {
+        let success = if test.total == 0 {
+            "skipped".to_string()
+        } else {
+            format!("{:.1}% ({}/{})", test.success_rate, test.successful, test.total)
+        };
+        out.push_str(&format!(
+            "| `{}` | {} | {} | {} | {} | {} | {} | {} | {} | {} | {} | {} |\n",
+            test.name,
+            success,
+            ms(test.avg_ms),
+            ms(test.min_ms),
+            ms(test.max_ms),
+            ms(test.p50_ms),
+            ms(test.p90_ms),
+            ms(test.p95_ms),
+            ms(test.p99_ms),
+            test.stddev_ms.map(|v| format!("{:.1} ms", v)).unwrap_or_else(|| "–".to_string()),
+            ms(test.jitter_ms),
+            test.speed_rating.unwrap_or("–"),
+        ));
+    }
+
+    let inconsistent: Vec<_> = report.tests.iter().filter(|t| t.inconsistent).collect();
+    if !inconsistent.is_empty() {
+        out.push('\n');
+        for test in inconsistent {
+            out.push_str(&format!(
+                "> ⚠️ `{}` is fast on average but inconsistent (CV {:.2})\n",
+                test.name,
+                test.cv.unwrap_or_default()
+            ));
+        }
+    }
+
+    let failing: Vec<_> = report.tests.iter().filter(|t| !t.errors.is_empty()).collect();
+    if !failing.is_empty() {
+        out.push_str("\n<details>\n<summary>Errors</summary>\n\n");
+        out.push_str("| Test | Category | Count |\n|------|----------|------:|\n");
+        for test in &failing {
+            for (category, count) in &test.error_categories {
+                out.push_str(&format!("| `{}` | `{}` | {} |\n", test.name, category, count));
+            }
+        }
+        out.push('\n');
+        for test in failing {
+            for error in &test.errors {
+                out.push_str(&format!("- `{}`: {}\n", test.name, cell(error)));
+            }
+        }
+        out.push_str("\n</details>\n");
+    }
+
+    out
+}
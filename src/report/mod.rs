@@ -0,0 +1,697 @@
+pub mod assertions;
+pub mod compare;
+mod csv;
+pub mod diff;
+pub mod github;
+pub mod histogram;
+#[cfg(feature = "sqlite")]
+pub mod history;
+mod json;
+mod junit;
+mod leaderboard;
+mod markdown;
+mod outliers;
+pub mod nagios;
+#[cfg(feature = "parquet")]
+mod parquet;
+pub mod prometheus;
+mod rolling;
+mod score;
+mod tap;
+mod text;
+
+use crate::backends::{BackendSpread, Stickiness};
+use crate::batch::BatchScaling;
+use crate::errors::ErrorCategory;
+use crate::failover::FailoverResult;
+use crate::load::{Capacity, ClosedLoopSummary, LoadSummary, Sweep};
+use crate::node::{Cluster, ClusterInfo, LedgerDepth, NetworkTps, NodeInfo, PriorityFees, SlotLag};
+use crate::phases::ConnectionTiming;
+use crate::race::RaceResult;
+use crate::rate_limit::{RateLimitProbe, RateLimitSummary};
+use crate::rpc_tests::TestResult;
+use crate::ws::{StreamingUnderLoad, SubscriptionLimit, Throughput};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use histogram::LatencyHistogram;
+use rolling::WindowStats;
+pub use score::ScoreWeights;
+use score::EndpointScore;
+use serde::Serialize;
+use std::collections::BTreeMap;
+#[cfg(feature = "parquet")]
+use std::path::Path;
+use std::str::FromStr;
+
+/// How the final report is rendered
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Colorful human-readable summary
+    Text,
+    /// Full structured report as JSON
+    Json,
+    /// One row per iteration, for spreadsheets and pandas
+    Csv,
+    /// One row per test with aggregate stats and percentiles
+    CsvSummary,
+    /// GitHub-flavored summary table for PRs and issues
+    Markdown,
+    /// JUnit XML, one test case per RPC method, for CI test reports
+    Junit,
+    /// Prometheus text exposition, e.g. for the node_exporter textfile collector
+    Prometheus,
+    /// Test Anything Protocol, one test point per iteration
+    Tap,
+    /// Nagios/Icinga plugin output: one status line with perfdata, exiting with its state
+    Nagios,
+}
+
+/// The endpoint a report was produced for
+#[derive(Serialize)]
+pub struct EndpointInfo {
+    pub url: String,
+}
+
+impl EndpointInfo {
+    pub fn host(&self) -> String {
+        host(&self.url)
+    }
+}
+
+/// Host name of an endpoint, short enough for table headers and without the API keys paths and queries carry
+pub fn host(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(String::from))
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// Settings the run was performed with
+#[derive(Serialize)]
+pub struct RunConfig {
+    pub mode: &'static str,
+    pub iterations: usize,
+    /// Time budget per test of a duration-based run, which replaces the fixed iteration count
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u128>,
+    /// Unrecorded iterations run per test before the measured ones
+    pub warmup: usize,
+    pub max_duration_ms: Option<u128>,
+    pub speed_thresholds: SpeedThresholds,
+    /// Limits of the --format nagios check
+    pub nagios: nagios::Thresholds,
+    pub rating_metric: RatingMetric,
+    pub score_weights: ScoreWeights,
+    /// Successful iterations further than this many MADs from the median are outliers
+    pub outlier_mads: f64,
+    /// Percent trimmed from each end for the trimmed mean, if requested
+    pub trim_pct: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct OverallStats {
+    pub total: usize,
+    pub successful: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub success_rate: f64,
+    pub avg_ms: u128,
+    pub p50_ms: Option<u128>,
+    pub p90_ms: Option<u128>,
+    pub p95_ms: Option<u128>,
+    pub p99_ms: Option<u128>,
+    /// The latency the speed rating was derived from, per `config.rating_metric`
+    pub rating_ms: Option<u128>,
+    pub speed_rating: &'static str,
+}
+
+/// A single iteration of a test
+#[derive(Serialize)]
+pub struct Sample {
+    pub iteration: usize,
+    pub success: bool,
+    pub skipped: bool,
+    pub duration_ms: u128,
+    pub error: Option<String>,
+    pub error_category: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    pub bytes: u64,
+    pub ttfb_ms: u128,
+    pub download_ms: u128,
+    pub outlier: bool,
+}
+
+#[derive(Serialize)]
+pub struct TestStats {
+    pub name: String,
+    pub total: usize,
+    pub successful: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub success_rate: f64,
+    pub avg_ms: Option<u128>,
+    /// Mean without the slowest and fastest `config.trim_pct` percent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trimmed_avg_ms: Option<u128>,
+    /// Iteration numbers flagged as outliers
+    pub outliers: Vec<usize>,
+    pub min_ms: Option<u128>,
+    pub max_ms: Option<u128>,
+    pub p50_ms: Option<u128>,
+    pub p90_ms: Option<u128>,
+    pub p95_ms: Option<u128>,
+    pub p99_ms: Option<u128>,
+    pub stddev_ms: Option<f64>,
+    /// Coefficient of variation (stddev / mean), a scale-free stability indicator
+    pub cv: Option<f64>,
+    /// Fast on average but with a CV above `INCONSISTENT_CV`
+    pub inconsistent: bool,
+    /// Largest change in latency between consecutive successful iterations
+    pub jitter_ms: Option<u128>,
+    /// Average response size of successful iterations
+    pub avg_bytes: Option<u64>,
+    /// Megabytes per second received over the successful iterations
+    pub throughput_mbps: Option<f64>,
+    /// Pearson correlation between response size and duration of successful iterations
+    pub size_correlation: Option<f64>,
+    /// Extra ms per KB of response, from a least-squares fit
+    pub ms_per_kb: Option<f64>,
+    /// Average time to first byte of successful iterations
+    pub avg_ttfb_ms: Option<u128>,
+    /// Average body download time of successful iterations
+    pub avg_download_ms: Option<u128>,
+    /// Average compute units of simulated transactions, where the simulation executed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_compute_units: Option<u64>,
+    /// Average time from send to confirmation of submitted transactions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_confirm_ms: Option<u128>,
+    /// The latency the speed rating was derived from, per `config.rating_metric`
+    pub rating_ms: Option<u128>,
+    pub speed_rating: Option<&'static str>,
+    pub errors: Vec<String>,
+    /// Recent 1m/5m/15m windows, for runs long enough to fill them
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rolling: Vec<WindowStats>,
+    /// Failed iterations per error category, e.g. `http_429` or `rpc_-32005`
+    pub error_categories: BTreeMap<String, usize>,
+    pub samples: Vec<Sample>,
+    /// Successful latencies, for charts and further percentiles
+    #[serde(skip)]
+    pub histogram: LatencyHistogram,
+}
+
+/// What holds a large response back: the node building it or the pipe carrying it
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Limit {
+    Compute,
+    Bandwidth,
+}
+
+/// Sustained transfer rate of one of the large-response tests, from --bandwidth
+#[derive(Serialize, Clone, Debug)]
+pub struct Bandwidth {
+    pub test: String,
+    pub avg_mb: f64,
+    /// MB/s over whole requests, waiting for the first byte included
+    pub sustained_mbps: f64,
+    /// MB/s while bodies were downloading, the rate of the pipe alone
+    pub transfer_mbps: Option<f64>,
+    /// Share of each request spent waiting for the first byte
+    pub ttfb_pct: f64,
+    /// Compute when most of the time goes to waiting for the first byte
+    pub limit: Limit,
+}
+
+impl Bandwidth {
+    pub fn from_stats(test: &TestStats) -> Option<Bandwidth> {
+        let bytes = test.avg_bytes?;
+        let (ttfb, download) = (test.avg_ttfb_ms?, test.avg_download_ms?);
+        let total = ttfb + download;
+        if total == 0 {
+            return None;
+        }
+        let ttfb_pct = ttfb as f64 / total as f64 * 100.0;
+        Some(Bandwidth {
+            test: test.name.clone(),
+            avg_mb: bytes as f64 / 1_000_000.0,
+            sustained_mbps: test.throughput_mbps?,
+            transfer_mbps: throughput_mbps(bytes, download),
+            ttfb_pct,
+            limit: if ttfb_pct >= 50.0 { Limit::Compute } else { Limit::Bandwidth },
+        })
+    }
+}
+
+/// Everything known about a finished run, shared by all output formats
+#[derive(Serialize)]
+pub struct Report {
+    pub tool_version: &'static str,
+    /// Commit the binary was built from, if known
+    pub git_commit: Option<&'static str>,
+    pub timestamp: DateTime<Utc>,
+    pub endpoint: EndpointInfo,
+    pub config: RunConfig,
+    /// Software version reported by getVersion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<NodeInfo>,
+    /// Network TPS from getRecentPerformanceSamples at the start of the run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkTps>,
+    /// Oldest block and ledger slot the endpoint can serve
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger: Option<LedgerDepth>,
+    /// Cluster identified from getGenesisHash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cluster: Option<ClusterInfo>,
+    /// Cluster the run was expected to hit, per --expect-cluster
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_cluster: Option<Cluster>,
+    /// Backends seen behind the endpoint in a burst of fingerprint requests, from --backend-burst
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backends: Option<BackendSpread>,
+    /// Whether a kept-alive connection sticks to one backend, from --sticky-check
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stickiness: Option<Stickiness>,
+    /// getMultipleAccounts latency per batch size and the largest batch accepted, from --batch-scaling
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batch_scaling: Option<BatchScaling>,
+    /// Slot difference to --reference-url over the run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slot_lag: Option<SlotLag>,
+    /// Recent prioritization fee percentiles, optionally scoped to --fee-account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority_fees: Option<PriorityFees>,
+    /// Phases of a fresh connection, when measured with --connection-timing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connection: Option<ConnectionTiming>,
+    /// Rate-limit headers and 429s seen during the run
+    #[serde(skip_serializing_if = "RateLimitSummary::is_empty")]
+    pub rate_limits: RateLimitSummary,
+    /// Notification rates of the WebSocket throughput tests
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub throughput: Vec<Throughput>,
+    /// Practical number of subscriptions per connection, from --ws-max-subscriptions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_limit: Option<SubscriptionLimit>,
+    /// How often this endpoint answered first when raced against the others, from --race
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub race: Option<RaceResult>,
+    /// Failover down the endpoint list, from --failover; only on the primary's report
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failover: Option<FailoverResult>,
+    /// Offered and achieved request rate of the load subcommand
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load: Option<LoadSummary>,
+    /// Throughput of the load subcommand's closed-loop workers, from --concurrency
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub closed_loop: Option<ClosedLoopSummary>,
+    /// Slot notification delivery before and during the load, from --ws-subscriptions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub streaming_under_load: Option<StreamingUnderLoad>,
+    /// Transfer rates of the large-response tests, from --bandwidth
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub bandwidth: Vec<Bandwidth>,
+    /// Throughput and latency per concurrency level of the sweep subcommand
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sweep: Option<Sweep>,
+    /// Highest sustainable request rate found by the capacity subcommand
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<Capacity>,
+    /// Throttling threshold and recovery found by the rate-limit subcommand
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit_probe: Option<RateLimitProbe>,
+    pub overall: OverallStats,
+    pub score: EndpointScore,
+    pub tests: Vec<TestStats>,
+    /// Whether JSON output embeds every per-iteration sample
+    #[serde(skip)]
+    pub include_samples: bool,
+}
+
+/// Which latency statistic the speed rating is based on
+#[derive(ValueEnum, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RatingMetric {
+    Avg,
+    P50,
+    P90,
+    P95,
+    P99,
+}
+
+impl RatingMetric {
+    pub fn label(self) -> &'static str {
+        match self {
+            RatingMetric::Avg => "avg",
+            RatingMetric::P50 => "p50",
+            RatingMetric::P90 => "p90",
+            RatingMetric::P95 => "p95",
+            RatingMetric::P99 => "p99",
+        }
+    }
+
+    fn pick(self, histogram: &LatencyHistogram) -> Option<u128> {
+        match self {
+            RatingMetric::Avg => histogram.mean(),
+            RatingMetric::P50 => histogram.percentile(50.0),
+            RatingMetric::P90 => histogram.percentile(90.0),
+            RatingMetric::P95 => histogram.percentile(95.0),
+            RatingMetric::P99 => histogram.percentile(99.0),
+        }
+    }
+}
+
+/// Upper bounds in ms of the Excellent, Good, Average and Slow ratings
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpeedThresholds {
+    pub excellent: u128,
+    pub good: u128,
+    pub average: u128,
+    pub slow: u128,
+}
+
+impl Default for SpeedThresholds {
+    fn default() -> Self {
+        SpeedThresholds { excellent: 100, good: 300, average: 600, slow: 1000 }
+    }
+}
+
+impl FromStr for SpeedThresholds {
+    type Err = String;
+
+    /// Four ascending bounds, e.g. `50,150,300,600`
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let bounds = s
+            .split(',')
+            .map(|part| part.trim().parse::<u128>().map_err(|e| format!("invalid bound '{}': {}", part, e)))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        match bounds[..] {
+            [excellent, good, average, slow] if excellent < good && good < average && average < slow => {
+                Ok(SpeedThresholds { excellent, good, average, slow })
+            }
+            [_, _, _, _] => Err("bounds must be strictly ascending".to_string()),
+            _ => Err("expected four comma-separated bounds, e.g. 100,300,600,1000".to_string()),
+        }
+    }
+}
+
+impl SpeedThresholds {
+    /// Rating name and the color it is shown in
+    pub fn rate(&self, duration_ms: u128) -> (&'static str, &'static str) {
+        if duration_ms <= self.excellent {
+            ("Excellent", "bright_green")
+        } else if duration_ms <= self.good {
+            ("Good", "green")
+        } else if duration_ms <= self.average {
+            ("Average", "yellow")
+        } else if duration_ms <= self.slow {
+            ("Slow", "yellow")
+        } else {
+            ("Very Slow", "red")
+        }
+    }
+}
+
+/// CV above which a fast test is called out as inconsistent
+pub const INCONSISTENT_CV: f64 = 0.5;
+
+/// Largest absolute difference between consecutive successful iterations
+fn max_jump(results: &[&TestResult]) -> Option<u128> {
+    let durations: Vec<u128> = results.iter().filter(|r| r.success).map(|r| r.duration_ms).collect();
+    durations.windows(2).map(|pair| pair[0].abs_diff(pair[1])).max()
+}
+
+fn categorize(results: &[&TestResult]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for error in results.iter().filter_map(|r| r.error.as_deref()) {
+        *counts.entry(ErrorCategory::classify(error).to_string()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Pearson r and least-squares slope of duration over size, if there is variation in both
+fn size_fit(points: impl Iterator<Item = (f64, f64)>) -> Option<(f64, f64)> {
+    let points: Vec<(f64, f64)> = points.collect();
+    if points.len() < 3 {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let cov: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let var_x: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let var_y: f64 = points.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+
+    if var_x == 0.0 || var_y == 0.0 {
+        return None;
+    }
+    Some((cov / (var_x * var_y).sqrt(), cov / var_x))
+}
+
+fn throughput_mbps(bytes: u64, duration_ms: u128) -> Option<f64> {
+    (duration_ms > 0).then(|| bytes as f64 / 1_000_000.0 / (duration_ms as f64 / 1000.0))
+}
+
+/// Success rate in percent over the iterations that actually ran
+fn success_rate(successful: usize, total: usize) -> f64 {
+    if total > 0 {
+        (successful as f64 / total as f64) * 100.0
+    } else {
+        0.0
+    }
+}
+
+impl Report {
+    /// Whether --expect-cluster was given and the endpoint serves something else
+    pub fn cluster_mismatch(&self) -> bool {
+        self.expected_cluster.is_some() && self.expected_cluster != self.cluster.as_ref().and_then(|c| c.cluster)
+    }
+
+    pub fn new(endpoint: EndpointInfo, config: RunConfig, results: &[TestResult]) -> Self {
+        // Group results by test name, keeping the order tests were run in
+        let mut grouped_results: Vec<(&str, Vec<&TestResult>)> = Vec::new();
+        for result in results {
+            match grouped_results.iter_mut().find(|(name, _)| *name == result.name) {
+                Some((_, group)) => group.push(result),
+                None => grouped_results.push((&result.name, vec![result])),
+            }
+        }
+
+        let tests: Vec<TestStats> = grouped_results
+            .into_iter()
+            .map(|(name, group)| TestStats::new(name, &group, &config))
+            .collect();
+        let overall = OverallStats::new(results, &config);
+        let score = EndpointScore::new(&overall, &tests, &config.speed_thresholds, &config.score_weights);
+
+        Report {
+            tool_version: env!("CARGO_PKG_VERSION"),
+            git_commit: option_env!("GIT_HASH"),
+            timestamp: Utc::now(),
+            endpoint,
+            config,
+            node: None,
+            network: None,
+            ledger: None,
+            cluster: None,
+            expected_cluster: None,
+            backends: None,
+            stickiness: None,
+            batch_scaling: None,
+            slot_lag: None,
+            priority_fees: None,
+            connection: None,
+            rate_limits: RateLimitSummary::default(),
+            throughput: Vec::new(),
+            subscription_limit: None,
+            race: None,
+            failover: None,
+            load: None,
+            closed_loop: None,
+            streaming_under_load: None,
+            bandwidth: Vec::new(),
+            sweep: None,
+            capacity: None,
+            rate_limit_probe: None,
+            overall,
+            score,
+            tests,
+            include_samples: true,
+        }
+    }
+
+    pub fn render(&self, format: OutputFormat) -> Result<String> {
+        match format {
+            OutputFormat::Text => Ok(text::render(self)),
+            OutputFormat::Json => json::render(self),
+            OutputFormat::Csv => Ok(csv::render(self)),
+            OutputFormat::CsvSummary => Ok(csv::render_summary(self)),
+            OutputFormat::Markdown => Ok(markdown::render(self)),
+            OutputFormat::Junit => Ok(junit::render(std::slice::from_ref(self))),
+            OutputFormat::Prometheus => Ok(prometheus::render(std::slice::from_ref(self))),
+            OutputFormat::Tap => Ok(tap::render(std::slice::from_ref(self))),
+            OutputFormat::Nagios => Ok(nagios::render(std::slice::from_ref(self))),
+        }
+    }
+}
+
+/// One document covering every endpoint of the run, with a section per endpoint
+pub fn render_all(reports: &[Report], format: OutputFormat) -> Result<String> {
+    // A single endpoint keeps the exact shape of a plain run
+    if let [report] = reports {
+        return report.render(format);
+    }
+
+    match format {
+        OutputFormat::Text => {
+            let mut out: String = reports.iter().map(text::render).collect();
+            out.push_str(&leaderboard::render(reports));
+            Ok(out)
+        }
+        OutputFormat::Json => json::render_all(reports),
+        OutputFormat::Csv => Ok(csv::render_all(reports, false)),
+        OutputFormat::CsvSummary => Ok(csv::render_all(reports, true)),
+        OutputFormat::Markdown => {
+            let mut sections: Vec<String> = reports.iter().map(markdown::render).collect();
+            sections.push(leaderboard::render_markdown(reports));
+            Ok(sections.join("\n"))
+        }
+        OutputFormat::Junit => Ok(junit::render(reports)),
+        OutputFormat::Prometheus => Ok(prometheus::render(reports)),
+        OutputFormat::Tap => Ok(tap::render(reports)),
+        OutputFormat::Nagios => Ok(nagios::render(reports)),
+    }
+}
+
+/// Parquet is binary, so it is written straight to a file rather than rendered
+#[cfg(feature = "parquet")]
+pub fn write_parquet(reports: &[Report], path: &Path) -> Result<()> {
+    parquet::write(reports, path)
+}
+
+impl OverallStats {
+    fn new(results: &[TestResult], config: &RunConfig) -> Self {
+        // Iterations that never ran don't count towards the success rate
+        let total = results.iter().filter(|r| !r.skipped).count();
+        let successful = results.iter().filter(|r| r.success).count();
+
+        let histogram: LatencyHistogram = results.iter().filter(|r| r.success).map(|r| r.duration_ms).collect();
+        let avg_ms = histogram.mean().unwrap_or(0);
+        let rating_ms = config.rating_metric.pick(&histogram);
+
+        OverallStats {
+            total,
+            successful,
+            failed: total - successful,
+            skipped: results.len() - total,
+            success_rate: success_rate(successful, total),
+            avg_ms,
+            p50_ms: histogram.percentile(50.0),
+            p90_ms: histogram.percentile(90.0),
+            p95_ms: histogram.percentile(95.0),
+            p99_ms: histogram.percentile(99.0),
+            rating_ms,
+            speed_rating: config.speed_thresholds.rate(rating_ms.unwrap_or(0)).0,
+        }
+    }
+}
+
+impl TestStats {
+    fn new(name: &str, results: &[&TestResult], config: &RunConfig) -> Self {
+        let skipped = results.iter().filter(|r| r.skipped).count();
+        let total = results.len() - skipped;
+        let histogram: LatencyHistogram = results.iter().filter(|r| r.success).map(|r| r.duration_ms).collect();
+        let successful = histogram.len() as usize;
+        let avg_ms = histogram.mean();
+        let rating_ms = config.rating_metric.pick(&histogram);
+        let cv = match (histogram.stddev(), avg_ms) {
+            (Some(stddev), Some(avg)) if avg > 0 => Some(stddev / avg as f64),
+            _ => None,
+        };
+
+        let succeeded = results.iter().filter(|r| r.success);
+        let total_bytes: u64 = succeeded.clone().map(|r| r.bytes).sum();
+        let total_ms: u128 = succeeded.clone().map(|r| r.duration_ms).sum();
+        let total_ttfb_ms: u128 = succeeded.clone().map(|r| r.ttfb_ms).sum();
+        let total_download_ms: u128 = succeeded.clone().map(|r| r.download_ms).sum();
+        let compute_units: Vec<u64> = succeeded.clone().filter_map(|r| r.compute_units).collect();
+        let confirmations: Vec<u128> = succeeded.clone().filter_map(|r| r.confirm_ms).collect();
+        let size_fit = size_fit(succeeded.map(|r| (r.bytes as f64 / 1024.0, r.duration_ms as f64)));
+
+        // Outliers are judged among the successful iterations, then mapped back to positions
+        let successful_iterations: Vec<(usize, u128)> = results
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.success)
+            .map(|(i, r)| (i, r.duration_ms))
+            .collect();
+        let durations: Vec<u128> = successful_iterations.iter().map(|(_, d)| *d).collect();
+        let outlier_positions: Vec<usize> = outliers::detect(&durations, config.outlier_mads)
+            .into_iter()
+            .map(|i| successful_iterations[i].0)
+            .collect();
+
+        let samples = results
+            .iter()
+            .enumerate()
+            .map(|(i, r)| Sample {
+                iteration: i + 1,
+                success: r.success,
+                skipped: r.skipped,
+                duration_ms: r.duration_ms,
+                error: r.error.clone(),
+                error_category: r.error.as_deref().map(|e| ErrorCategory::classify(e).to_string()),
+                timestamp: r.timestamp,
+                bytes: r.bytes,
+                ttfb_ms: r.ttfb_ms,
+                download_ms: r.download_ms,
+                outlier: outlier_positions.contains(&i),
+            })
+            .collect();
+
+        TestStats {
+            name: name.to_string(),
+            total,
+            successful,
+            failed: total - successful,
+            skipped,
+            success_rate: success_rate(successful, total),
+            avg_ms,
+            trimmed_avg_ms: config.trim_pct.and_then(|pct| outliers::trimmed_mean(&durations, pct)),
+            outliers: outlier_positions.iter().map(|i| i + 1).collect(),
+            min_ms: histogram.min(),
+            max_ms: histogram.max(),
+            p50_ms: histogram.percentile(50.0),
+            p90_ms: histogram.percentile(90.0),
+            p95_ms: histogram.percentile(95.0),
+            p99_ms: histogram.percentile(99.0),
+            stddev_ms: histogram.stddev(),
+            cv,
+            inconsistent: cv.is_some_and(|cv| cv > INCONSISTENT_CV)
+                && rating_ms.is_some_and(|ms| ms <= config.speed_thresholds.good),
+            jitter_ms: max_jump(results),
+            avg_bytes: (successful > 0).then(|| total_bytes / successful as u64),
+            throughput_mbps: throughput_mbps(total_bytes, total_ms),
+            size_correlation: size_fit.map(|(r, _)| r),
+            ms_per_kb: size_fit.map(|(_, slope)| slope),
+            avg_ttfb_ms: (successful > 0).then(|| total_ttfb_ms / successful as u128),
+            avg_download_ms: (successful > 0).then(|| total_download_ms / successful as u128),
+            avg_compute_units: (!compute_units.is_empty())
+                .then(|| compute_units.iter().sum::<u64>() / compute_units.len() as u64),
+            avg_confirm_ms: (!confirmations.is_empty())
+                .then(|| confirmations.iter().sum::<u128>() / confirmations.len() as u128),
+            rating_ms,
+            speed_rating: rating_ms.map(|ms| config.speed_thresholds.rate(ms).0),
+            errors: results.iter().filter_map(|r| r.error.clone()).collect(),
+            error_categories: categorize(results),
+            rolling: rolling::windows(results),
+            samples,
+            histogram,
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use super::Report;
+use serde::Serialize;
+
+/// Limits a run is checked against, latency in ms and success rate in percent
+#[derive(Serialize, Clone, Copy, Debug)]
+pub struct Thresholds {
+    pub warn_latency_ms: u128,
+    pub crit_latency_ms: u128,
+    pub warn_success: f64,
+    pub crit_success: f64,
+}
+
+/// Plugin states, in order of severity for everything but `Unknown`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum State {
+    Ok,
+    Warning,
+    Critical,
+    Unknown,
+}
+
+impl State {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            State::Ok => 0,
+            State::Warning => 1,
+            State::Critical => 2,
+            State::Unknown => 3,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            State::Ok => "OK",
+            State::Warning => "WARNING",
+            State::Critical => "CRITICAL",
+            State::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+/// Perfdata labels are quoted, and a quote inside one is doubled
+fn perf_label(name: &str) -> String {
+    format!("'{}'", name.replace('\'', "''"))
+}
+
+/// A single status line with perfdata, plus the state to exit with
+pub fn check(report: &Report) -> (String, State) {
+    let thresholds = &report.config.nagios;
+    let mut state = State::Ok;
+    let mut problems = Vec::new();
+
+    for test in report.tests.iter().filter(|t| t.total > 0) {
+        let test_state = if test.success_rate < thresholds.crit_success
+            || test.avg_ms.is_some_and(|avg| avg > thresholds.crit_latency_ms)
+        {
+            State::Critical
+        } else if test.success_rate < thresholds.warn_success
+            || test.avg_ms.is_some_and(|avg| avg > thresholds.warn_latency_ms)
+        {
+            State::Warning
+        } else {
+            State::Ok
+        };
+
+        if test_state != State::Ok {
+            let latency = test.avg_ms.map(|avg| format!("{} ms", avg)).unwrap_or_else(|| "n/a".to_string());
+            problems.push(format!("{} {:.1}% {}", test.name, test.success_rate, latency));
+        }
+        state = state.max(test_state);
+    }
+
+    if report.overall.total == 0 {
+        state = State::Unknown;
+    }
+
+    let summary = match state {
+        State::Unknown => "no iterations completed".to_string(),
+        State::Ok => format!(
+            "{:.1}% success, {} ms avg over {} methods",
+            report.overall.success_rate,
+            report.overall.avg_ms,
+            report.tests.len()
+        ),
+        _ => problems.join(", "),
+    };
+
+    let mut perfdata = Vec::new();
+    for test in &report.tests {
+        if let Some(avg) = test.avg_ms {
+            perfdata.push(format!(
+                "{}={}ms;{};{};0",
+                perf_label(&test.name),
+                avg,
+                thresholds.warn_latency_ms,
+                thresholds.crit_latency_ms
+            ));
+        }
+        perfdata.push(format!(
+            "{}={:.1}%;{}:;{}:;0;100",
+            perf_label(&format!("{}_success", test.name)),
+            test.success_rate,
+            thresholds.warn_success,
+            thresholds.crit_success
+        ));
+    }
+
+    (format!("SOLANA RPC {} - {} | {}", state.label(), summary, perfdata.join(" ")), state)
+}
+
+/// Each endpoint checked on its own, the worst state wins and every endpoint gets a line of long output
+pub fn check_all(reports: &[Report]) -> (String, State) {
+    if let [report] = reports {
+        return check(report);
+    }
+
+    let checks: Vec<_> = reports.iter().map(|report| (report, check(report))).collect();
+    let state = checks.iter().map(|(_, (_, state))| *state).max().unwrap_or(State::Unknown);
+    let ok = checks.iter().filter(|(_, (_, state))| *state == State::Ok).count();
+
+    let mut out = format!("SOLANA RPC {} - {}/{} endpoints OK", state.label(), ok, reports.len());
+    for (report, (line, _)) in checks {
+        // Perfdata may only follow the first line, so the per-endpoint lines go without it
+        let status = line.split(" | ").next().unwrap_or_default();
+        out.push_str(&format!("\n{}: {}", report.endpoint.url, status));
+    }
+
+    (out, state)
+}
+
+/// The plugin output of `--format nagios`
+pub fn render(reports: &[Report]) -> String {
+    format!("{}\n", check_all(reports).0)
+}
@@ -0,0 +1,46 @@
+/// Median of already sorted values
+fn median(sorted: &[f64]) -> Option<f64> {
+    let n = sorted.len();
+    match n {
+        0 => None,
+        _ if n % 2 == 1 => Some(sorted[n / 2]),
+        _ => Some((sorted[n / 2 - 1] + sorted[n / 2]) / 2.0),
+    }
+}
+
+/// Indices of durations more than `mads` median absolute deviations away from the median
+pub fn detect(durations: &[u128], mads: f64) -> Vec<usize> {
+    let mut sorted: Vec<f64> = durations.iter().map(|d| *d as f64).collect();
+    sorted.sort_by(f64::total_cmp);
+    let Some(center) = median(&sorted) else {
+        return Vec::new();
+    };
+
+    let mut deviations: Vec<f64> = sorted.iter().map(|d| (d - center).abs()).collect();
+    deviations.sort_by(f64::total_cmp);
+    let mad = median(&deviations).unwrap_or(0.0);
+
+    // With half or more of the values identical there is no spread to measure against
+    if mad == 0.0 {
+        return Vec::new();
+    }
+
+    durations
+        .iter()
+        .enumerate()
+        .filter(|(_, d)| (**d as f64 - center).abs() > mads * mad)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Mean after dropping `pct` percent of the values from each end
+pub fn trimmed_mean(durations: &[u128], pct: f64) -> Option<u128> {
+    let mut sorted = durations.to_vec();
+    sorted.sort_unstable();
+    let cut = ((sorted.len() as f64) * pct / 100.0).floor() as usize;
+    let kept = sorted.get(cut..sorted.len().saturating_sub(cut))?;
+    if kept.is_empty() {
+        return None;
+    }
+    Some(kept.iter().sum::<u128>() / kept.len() as u128)
+}
@@ -0,0 +1,71 @@
+use super::Report;
+use anyhow::{Context, Result};
+use arrow::array::{ArrayRef, BooleanArray, StringArray, TimestampMillisecondArray, UInt32Array, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+fn timestamp_type() -> DataType {
+    DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()))
+}
+
+/// One row per iteration of every endpoint, with the run timestamp so files from many runs can be queried together
+pub fn write(reports: &[Report], path: &Path) -> Result<()> {
+    let rows: Vec<_> = reports
+        .iter()
+        .flat_map(|r| r.tests.iter().flat_map(move |t| t.samples.iter().map(move |s| (r, t, s))))
+        .collect();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("run_timestamp", timestamp_type(), false),
+        Field::new("endpoint", DataType::Utf8, false),
+        Field::new("mode", DataType::Utf8, false),
+        Field::new("test", DataType::Utf8, false),
+        Field::new("iteration", DataType::UInt32, false),
+        Field::new("success", DataType::Boolean, false),
+        Field::new("skipped", DataType::Boolean, false),
+        Field::new("duration_ms", DataType::UInt64, false),
+        Field::new("ttfb_ms", DataType::UInt64, false),
+        Field::new("download_ms", DataType::UInt64, false),
+        Field::new("bytes", DataType::UInt64, false),
+        Field::new("error", DataType::Utf8, true),
+        Field::new("timestamp", timestamp_type(), false),
+    ]));
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(
+            TimestampMillisecondArray::from_iter_values(rows.iter().map(|(r, _, _)| r.timestamp.timestamp_millis()))
+                .with_timezone("UTC"),
+        ),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|(r, _, _)| &r.endpoint.url))),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|(r, _, _)| r.config.mode))),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|(_, t, _)| &t.name))),
+        Arc::new(UInt32Array::from_iter_values(rows.iter().map(|(_, _, s)| s.iteration as u32))),
+        Arc::new(BooleanArray::from_iter(rows.iter().map(|(_, _, s)| Some(s.success)))),
+        Arc::new(BooleanArray::from_iter(rows.iter().map(|(_, _, s)| Some(s.skipped)))),
+        Arc::new(UInt64Array::from_iter_values(rows.iter().map(|(_, _, s)| s.duration_ms as u64))),
+        Arc::new(UInt64Array::from_iter_values(rows.iter().map(|(_, _, s)| s.ttfb_ms as u64))),
+        Arc::new(UInt64Array::from_iter_values(rows.iter().map(|(_, _, s)| s.download_ms as u64))),
+        Arc::new(UInt64Array::from_iter_values(rows.iter().map(|(_, _, s)| s.bytes))),
+        Arc::new(StringArray::from_iter(rows.iter().map(|(_, _, s)| s.error.as_deref()))),
+        Arc::new(
+            TimestampMillisecondArray::from_iter_values(rows.iter().map(|(_, _, s)| s.timestamp.timestamp_millis()))
+                .with_timezone("UTC"),
+        ),
+    ];
+
+    let batch = RecordBatch::try_new(schema.clone(), columns).context("Failed to build Parquet batch")?;
+
+    let file = File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let props = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+    let mut writer = ArrowWriter::try_new(file, schema, Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+
+    Ok(())
+}
@@ -0,0 +1,219 @@
+use super::Report;
+use crate::monitor::Monitor;
+
+/// Histogram bucket upper bounds in seconds, aligned with the speed ratings
+pub const BUCKETS: [f64; 8] = [0.05, 0.1, 0.3, 0.6, 1.0, 2.5, 5.0, 10.0];
+
+/// Escape a label value per the exposition format
+fn label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Each report with its endpoint label, escaped once
+fn labelled(reports: &[Report]) -> impl Iterator<Item = (&Report, String)> {
+    reports.iter().map(|report| (report, label(&report.endpoint.url)))
+}
+
+/// Prometheus text exposition of per-method latency histograms and request counters,
+/// with every endpoint of the run under a single HELP and TYPE per metric family
+pub fn render(reports: &[Report]) -> String {
+    let mut out = String::new();
+
+    out.push_str("# HELP solana_rpc_request_duration_seconds Latency of successful RPC requests.\n");
+    out.push_str("# TYPE solana_rpc_request_duration_seconds histogram\n");
+    for (report, endpoint) in labelled(reports) {
+        for test in &report.tests {
+            let method = label(&test.name);
+            let durations: Vec<f64> = test
+                .samples
+                .iter()
+                .filter(|s| s.success)
+                .map(|s| s.duration_ms as f64 / 1000.0)
+                .collect();
+
+            for bound in BUCKETS {
+                let count = durations.iter().filter(|d| **d <= bound).count();
+                out.push_str(&format!(
+                    "solana_rpc_request_duration_seconds_bucket{{endpoint=\"{}\",method=\"{}\",le=\"{}\"}} {}\n",
+                    endpoint, method, bound, count
+                ));
+            }
+            out.push_str(&format!(
+                "solana_rpc_request_duration_seconds_bucket{{endpoint=\"{}\",method=\"{}\",le=\"+Inf\"}} {}\n",
+                endpoint,
+                method,
+                durations.len()
+            ));
+            out.push_str(&format!(
+                "solana_rpc_request_duration_seconds_sum{{endpoint=\"{}\",method=\"{}\"}} {}\n",
+                endpoint,
+                method,
+                durations.iter().sum::<f64>()
+            ));
+            out.push_str(&format!(
+                "solana_rpc_request_duration_seconds_count{{endpoint=\"{}\",method=\"{}\"}} {}\n",
+                endpoint,
+                method,
+                durations.len()
+            ));
+        }
+    }
+
+    out.push_str("# HELP solana_rpc_requests_total RPC requests by outcome.\n");
+    out.push_str("# TYPE solana_rpc_requests_total counter\n");
+    for (report, endpoint) in labelled(reports) {
+        for test in &report.tests {
+            let method = label(&test.name);
+            for (status, count) in [("success", test.successful), ("failure", test.failed), ("skipped", test.skipped)] {
+                out.push_str(&format!(
+                    "solana_rpc_requests_total{{endpoint=\"{}\",method=\"{}\",status=\"{}\"}} {}\n",
+                    endpoint, method, status, count
+                ));
+            }
+        }
+    }
+
+    out.push_str("# HELP solana_rpc_success_ratio Share of completed requests that succeeded.\n");
+    out.push_str("# TYPE solana_rpc_success_ratio gauge\n");
+    for (report, endpoint) in labelled(reports) {
+        for test in &report.tests {
+            out.push_str(&format!(
+                "solana_rpc_success_ratio{{endpoint=\"{}\",method=\"{}\"}} {}\n",
+                endpoint,
+                label(&test.name),
+                test.success_rate / 100.0
+            ));
+        }
+    }
+
+    out.push_str("# HELP solana_rpc_score Composite endpoint score from 0 to 100.\n");
+    out.push_str("# TYPE solana_rpc_score gauge\n");
+    for (report, endpoint) in labelled(reports) {
+        out.push_str(&format!("solana_rpc_score{{endpoint=\"{}\"}} {:.1}\n", endpoint, report.score.score));
+    }
+
+    if reports.iter().any(|r| r.node.is_some()) {
+        out.push_str("# HELP solana_rpc_node_info Software version reported by the node.\n");
+        out.push_str("# TYPE solana_rpc_node_info gauge\n");
+    }
+    for (report, endpoint) in labelled(reports) {
+        if let Some(node) = &report.node {
+            out.push_str(&format!(
+                "solana_rpc_node_info{{endpoint=\"{}\",version=\"{}\",feature_set=\"{}\",identity=\"{}\"}} 1\n",
+                endpoint,
+                label(&node.solana_core),
+                node.feature_set.map(|f| f.to_string()).unwrap_or_default(),
+                node.identity.as_deref().unwrap_or_default()
+            ));
+        }
+    }
+
+    if reports.iter().any(|r| r.slot_lag.is_some()) {
+        out.push_str("# HELP solana_rpc_slot_lag_slots Average slots behind the reference endpoint.\n");
+        out.push_str("# TYPE solana_rpc_slot_lag_slots gauge\n");
+    }
+    for (report, endpoint) in labelled(reports) {
+        if let Some(lag) = &report.slot_lag {
+            out.push_str(&format!(
+                "solana_rpc_slot_lag_slots{{endpoint=\"{}\",reference=\"{}\"}} {:.1}\n",
+                endpoint,
+                label(&lag.reference),
+                lag.avg_slots
+            ));
+        }
+    }
+
+    out.push_str("# HELP solana_rpc_last_run_timestamp_seconds Unix time the last run finished.\n");
+    out.push_str("# TYPE solana_rpc_last_run_timestamp_seconds gauge\n");
+    for (report, endpoint) in labelled(reports) {
+        out.push_str(&format!(
+            "solana_rpc_last_run_timestamp_seconds{{endpoint=\"{}\"}} {}\n",
+            endpoint,
+            report.timestamp.timestamp()
+        ));
+    }
+
+    out
+}
+
+/// Live metrics of monitor mode: counters and the latency histogram since monitoring started,
+/// gauges over the rolling window. Families and labels match the one-shot export
+pub fn render_monitor(monitor: &Monitor) -> String {
+    let mut out = String::new();
+    let labelled: Vec<_> = monitor
+        .series
+        .iter()
+        .map(|series| (series, label(&series.endpoint), label(&series.method)))
+        .collect();
+
+    out.push_str("# HELP solana_rpc_request_duration_seconds Latency of successful RPC requests.\n");
+    out.push_str("# TYPE solana_rpc_request_duration_seconds histogram\n");
+    for (series, endpoint, method) in &labelled {
+        for (bound, count) in BUCKETS.iter().zip(series.totals.buckets) {
+            out.push_str(&format!(
+                "solana_rpc_request_duration_seconds_bucket{{endpoint=\"{}\",method=\"{}\",le=\"{}\"}} {}\n",
+                endpoint, method, bound, count
+            ));
+        }
+        out.push_str(&format!(
+            "solana_rpc_request_duration_seconds_bucket{{endpoint=\"{}\",method=\"{}\",le=\"+Inf\"}} {}\n",
+            endpoint, method, series.totals.succeeded
+        ));
+        out.push_str(&format!(
+            "solana_rpc_request_duration_seconds_sum{{endpoint=\"{}\",method=\"{}\"}} {}\n",
+            endpoint, method, series.totals.sum_seconds
+        ));
+        out.push_str(&format!(
+            "solana_rpc_request_duration_seconds_count{{endpoint=\"{}\",method=\"{}\"}} {}\n",
+            endpoint, method, series.totals.succeeded
+        ));
+    }
+
+    out.push_str("# HELP solana_rpc_requests_total RPC requests by outcome.\n");
+    out.push_str("# TYPE solana_rpc_requests_total counter\n");
+    for (series, endpoint, method) in &labelled {
+        for (status, count) in [("success", series.totals.succeeded), ("failure", series.totals.failed)] {
+            out.push_str(&format!(
+                "solana_rpc_requests_total{{endpoint=\"{}\",method=\"{}\",status=\"{}\"}} {}\n",
+                endpoint, method, status, count
+            ));
+        }
+    }
+
+    out.push_str("# HELP solana_rpc_success_ratio Share of completed requests that succeeded.\n");
+    out.push_str("# TYPE solana_rpc_success_ratio gauge\n");
+    for (series, endpoint, method) in &labelled {
+        out.push_str(&format!(
+            "solana_rpc_success_ratio{{endpoint=\"{}\",method=\"{}\"}} {}\n",
+            endpoint,
+            method,
+            series.stats().success_rate / 100.0
+        ));
+    }
+
+    out.push_str("# HELP solana_rpc_window_p95_seconds p95 latency over the monitor's rolling window.\n");
+    out.push_str("# TYPE solana_rpc_window_p95_seconds gauge\n");
+    for (series, endpoint, method) in &labelled {
+        if let Some(p95) = series.stats().p95_ms {
+            out.push_str(&format!(
+                "solana_rpc_window_p95_seconds{{endpoint=\"{}\",method=\"{}\"}} {}\n",
+                endpoint,
+                method,
+                p95 as f64 / 1000.0
+            ));
+        }
+    }
+
+    out.push_str("# HELP solana_rpc_health Monitor health of the method: 0 up, 1 degraded, 2 down.\n");
+    out.push_str("# TYPE solana_rpc_health gauge\n");
+    for (series, endpoint, method) in &labelled {
+        out.push_str(&format!(
+            "solana_rpc_health{{endpoint=\"{}\",method=\"{}\"}} {}\n",
+            endpoint,
+            method,
+            series.health.level()
+        ));
+    }
+
+    out
+}
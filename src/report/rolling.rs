@@ -0,0 +1,54 @@
+use super::LatencyHistogram;
+use crate::rpc_tests::TestResult;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+/// Windows reported for runs long enough to fill them
+pub const WINDOWS: [(&str, i64); 3] = [("1m", 60), ("5m", 300), ("15m", 900)];
+
+/// Latency and success over the most recent part of a run
+#[derive(Serialize, Clone, Debug)]
+pub struct WindowStats {
+    pub window: &'static str,
+    pub total: usize,
+    pub success_rate: f64,
+    pub avg_ms: Option<u128>,
+    pub p95_ms: Option<u128>,
+}
+
+impl WindowStats {
+    /// Stats over the iterations that finished within `seconds` before `end`
+    pub fn new(window: &'static str, seconds: i64, results: &[&TestResult], end: DateTime<Utc>) -> Self {
+        let start = end - Duration::seconds(seconds);
+        let recent: Vec<&&TestResult> = results
+            .iter()
+            .filter(|r| !r.skipped && r.timestamp > start && r.timestamp <= end)
+            .collect();
+
+        let histogram: LatencyHistogram = recent.iter().filter(|r| r.success).map(|r| r.duration_ms).collect();
+        let successful = histogram.len() as usize;
+
+        WindowStats {
+            window,
+            total: recent.len(),
+            success_rate: super::success_rate(successful, recent.len()),
+            avg_ms: histogram.mean(),
+            p95_ms: histogram.percentile(95.0),
+        }
+    }
+}
+
+/// Every window shorter than the span the results cover, ending at the last result
+pub fn windows(results: &[&TestResult]) -> Vec<WindowStats> {
+    let ran = results.iter().filter(|r| !r.skipped);
+    let (Some(first), Some(last)) = (ran.clone().map(|r| r.timestamp).min(), ran.map(|r| r.timestamp).max()) else {
+        return Vec::new();
+    };
+
+    let span = (last - first).num_seconds();
+    WINDOWS
+        .iter()
+        .filter(|(_, seconds)| *seconds < span)
+        .map(|(label, seconds)| WindowStats::new(label, *seconds, results, last))
+        .collect()
+}
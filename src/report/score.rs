@@ -0,0 +1,86 @@
+use super::{OverallStats, SpeedThresholds, TestStats};
+use serde::Serialize;
+use std::str::FromStr;
+
+/// Relative weight of each component in the composite score
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct ScoreWeights {
+    pub success: f64,
+    pub median: f64,
+    pub tail: f64,
+    pub coverage: f64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        ScoreWeights { success: 0.4, median: 0.25, tail: 0.25, coverage: 0.1 }
+    }
+}
+
+impl FromStr for ScoreWeights {
+    type Err = String;
+
+    /// `key=value` pairs, e.g. `success=0.5,tail=0.3`; missing keys keep their default
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = ScoreWeights::default();
+        for pair in s.split(',').filter(|p| !p.trim().is_empty()) {
+            let (key, value) = pair.split_once('=').ok_or_else(|| format!("expected key=value, got '{}'", pair))?;
+            let value: f64 = value.trim().parse().map_err(|e| format!("invalid weight '{}': {}", value, e))?;
+            if value < 0.0 {
+                return Err(format!("weight for '{}' must not be negative", key));
+            }
+            match key.trim() {
+                "success" => weights.success = value,
+                "median" => weights.median = value,
+                "tail" => weights.tail = value,
+                "coverage" => weights.coverage = value,
+                other => return Err(format!("unknown weight '{}' (use success, median, tail, coverage)", other)),
+            }
+        }
+        if weights.success + weights.median + weights.tail + weights.coverage <= 0.0 {
+            return Err("at least one weight must be positive".to_string());
+        }
+        Ok(weights)
+    }
+}
+
+/// One 0–100 number to rank and gate endpoints on, with its components
+#[derive(Serialize, Clone, Debug)]
+pub struct EndpointScore {
+    pub score: f64,
+    pub success: f64,
+    pub median: f64,
+    pub tail: f64,
+    pub coverage: f64,
+}
+
+/// 100 up to the Excellent bound, falling linearly to 0 at twice the Slow bound
+fn latency_score(ms: Option<u128>, thresholds: &SpeedThresholds) -> f64 {
+    let Some(ms) = ms else {
+        return 0.0;
+    };
+    let best = thresholds.excellent as f64;
+    let worst = (thresholds.slow * 2) as f64;
+    (100.0 * (1.0 - (ms as f64 - best) / (worst - best))).clamp(0.0, 100.0)
+}
+
+impl EndpointScore {
+    pub fn new(overall: &OverallStats, tests: &[TestStats], thresholds: &SpeedThresholds, weights: &ScoreWeights) -> Self {
+        let ran = tests.iter().filter(|t| t.total > 0).count();
+        let covered = tests.iter().filter(|t| t.successful > 0).count();
+        let coverage = if ran > 0 { covered as f64 / ran as f64 * 100.0 } else { 0.0 };
+
+        let success = overall.success_rate;
+        let median = latency_score(overall.p50_ms, thresholds);
+        let tail = latency_score(overall.p99_ms, thresholds);
+
+        let total_weight = weights.success + weights.median + weights.tail + weights.coverage;
+        let score = (success * weights.success
+            + median * weights.median
+            + tail * weights.tail
+            + coverage * weights.coverage)
+            / total_weight;
+
+        EndpointScore { score, success, median, tail, coverage }
+    }
+}
@@ -0,0 +1,47 @@
+use super::Report;
+
+/// Descriptions may not contain `#`, which starts a directive
+fn description(text: &str) -> String {
+    text.replace('#', "\\#")
+}
+
+/// TAP version 13, one test point per method iteration, numbered across all endpoints
+pub fn render(reports: &[Report]) -> String {
+    let count: usize = reports.iter().flat_map(|r| &r.tests).map(|t| t.samples.len()).sum();
+    let mut out = format!("TAP version 13\n1..{}\n", count);
+
+    let mut number = 0;
+    for report in reports {
+        write_points(&mut out, report, &mut number);
+    }
+
+    out
+}
+
+fn write_points(out: &mut String, report: &Report, number: &mut usize) {
+    out.push_str(&format!("# {}\n", report.endpoint.url));
+
+    for test in &report.tests {
+        for sample in &test.samples {
+            *number += 1;
+            let name = description(&format!("{} iteration {}", test.name, sample.iteration));
+
+            if sample.skipped {
+                out.push_str(&format!("ok {} - {} # SKIP max duration exceeded\n", number, name));
+            } else if sample.success {
+                out.push_str(&format!("ok {} - {} ({} ms)\n", number, name, sample.duration_ms));
+            } else {
+                out.push_str(&format!("not ok {} - {}\n", number, name));
+                out.push_str("  ---\n");
+                out.push_str(&format!("  duration_ms: {}\n", sample.duration_ms));
+                if let Some(error) = &sample.error {
+                    out.push_str("  message: |\n");
+                    for line in error.lines() {
+                        out.push_str(&format!("    {}\n", line));
+                    }
+                }
+                out.push_str("  ...\n");
+            }
+        }
+    }
+}
@@ -0,0 +1,551 @@
+use super::{host, Report, TestStats};
+use crate::style::{banner, icon, is_plain, rule};
+use colored::*;
+use std::fmt::{self, Write};
+
+fn success_color(success_rate: f64) -> &'static str {
+    if success_rate == 100.0 {
+        "bright_green"
+    } else if success_rate >= 80.0 {
+        "green"
+    } else if success_rate >= 50.0 {
+        "yellow"
+    } else {
+        "red"
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1_048_575 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// Correlation from which size is reported as explaining latency
+const SIZE_CORRELATION_CALLOUT: f64 = 0.7;
+
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Most glyphs in a sparkline, longer runs are folded into this many buckets
+const SPARK_WIDTH: usize = 40;
+
+/// Latencies in run order, `✗` for failures and `·` for skipped iterations. Up to
+/// `SPARK_WIDTH` iterations get a glyph each; beyond that each glyph covers a run of
+/// consecutive iterations and shows the slowest, or `✗` if any of them failed
+fn sparkline(test: &TestStats) -> Option<String> {
+    if is_plain() || test.samples.len() < 2 {
+        return None;
+    }
+
+    let (min, max) = (test.min_ms?, test.max_ms?);
+    let range = (max - min).max(1);
+    let (count, width) = (test.samples.len(), test.samples.len().min(SPARK_WIDTH));
+    let line = (0..width)
+        .map(|i| {
+            let bucket = &test.samples[i * count / width..(i + 1) * count / width];
+            if bucket.iter().any(|sample| !sample.success && !sample.skipped) {
+                return '✗';
+            }
+            match bucket.iter().filter(|sample| sample.success).map(|sample| sample.duration_ms).max() {
+                Some(slowest) => SPARK_LEVELS[((slowest - min) * (SPARK_LEVELS.len() as u128 - 1) / range) as usize],
+                None => '·',
+            }
+        })
+        .collect();
+    Some(line)
+}
+
+/// Below this many successful iterations a distribution says nothing
+const MIN_CHART_SAMPLES: usize = 5;
+const CHART_BUCKETS: usize = 6;
+const CHART_WIDTH: usize = 30;
+
+/// Bucketed bars showing the shape of the latency distribution
+fn write_distribution(out: &mut String, test: &TestStats) -> fmt::Result {
+    let buckets = test.histogram.buckets(CHART_BUCKETS);
+    let peak = buckets.iter().map(|(_, _, count)| *count).max().unwrap_or(0);
+    if test.successful < MIN_CHART_SAMPLES || buckets.len() < 2 || peak == 0 {
+        return Ok(());
+    }
+
+    let bar = if is_plain() { "#" } else { "█" };
+    let label_width = buckets.iter().map(|(low, high, _)| format!("{}-{}", low, high).len()).max().unwrap_or(0);
+    for (low, high, count) in buckets {
+        let length = (count as usize * CHART_WIDTH).div_ceil(peak as usize);
+        writeln!(
+            out,
+            "     {:>width$}ms {} {}",
+            format!("{}-{}", low, high),
+            bar.repeat(length).cyan(),
+            count,
+            width = label_width
+        )?;
+    }
+    Ok(())
+}
+
+pub fn render(report: &Report) -> String {
+    let mut out = String::new();
+    write_report(&mut out, report).expect("writing to a String cannot fail");
+    out
+}
+
+fn write_report(out: &mut String, report: &Report) -> fmt::Result {
+    if report.tests.is_empty() {
+        writeln!(out, "No test results to display.")?;
+        return Ok(());
+    }
+
+    let overall = &report.overall;
+    let metric = report.config.rating_metric.label();
+    let (speed_rating, rating_color) = report.config.speed_thresholds.rate(overall.rating_ms.unwrap_or(0));
+
+    // Print header with fancy box
+    writeln!(out, "\n{}", banner("RPC PERFORMANCE REPORT"))?;
+
+    writeln!(out, "{}", format!("{}Endpoint: {}", icon("🔗"), report.endpoint.url).dimmed())?;
+    writeln!(out, "{}", format!("{}Timestamp: {}", icon("📊"), report.timestamp).dimmed())?;
+    if let Some(node) = &report.node {
+        writeln!(out, "{}", format!("{}Node version: {}", icon("🧬"), node.describe()).dimmed())?;
+        if let Some(identity) = &node.identity {
+            writeln!(out, "{}", format!("{}Node identity: {}", icon("🪪"), identity).dimmed())?;
+        }
+    }
+    if let Some(network) = &report.network {
+        let non_vote = network.non_vote_tps.map(|tps| format!(" ({:.0} non-vote)", tps)).unwrap_or_default();
+        writeln!(out, "{}", format!("{}Network TPS: {:.0}{} over the last {} min",
+            icon("🌍"), network.tps, non_vote, network.window_secs / 60).dimmed())?;
+    }
+    if let Some(cluster) = &report.cluster {
+        writeln!(out, "{}", format!("{}Cluster: {}", icon("🪐"), cluster.describe()).dimmed())?;
+    }
+    if let (true, Some(expected)) = (report.cluster_mismatch(), report.expected_cluster) {
+        writeln!(out, "{}", format!("{}CLUSTER MISMATCH: expected {}, got {}", icon("🚨"), expected,
+            report.cluster.as_ref().map(|c| c.describe()).unwrap_or_else(|| "unknown".to_string())).red().bold())?;
+    }
+    if let Some(spread) = &report.backends {
+        let counts: Vec<String> = spread.backends.iter().map(|b| b.responses.to_string()).collect();
+        match spread.backends.len() {
+            0 => writeln!(out, "{}", format!("{}Backends: none of {} fingerprint requests answered",
+                icon("🎛️ "), spread.requests).yellow())?,
+            1 => writeln!(out, "{}", format!("{}Backends: a single node ({}) answered {}/{} requests",
+                icon("🎛️ "), spread.backends[0].identity, spread.answered, spread.requests).dimmed())?,
+            n => writeln!(out, "{}", format!("{}Backends: {} nodes behind the endpoint ({} of {} responses), slot skew {}, {} slot regressions",
+                icon("🎛️ "), n, counts.join("/"), spread.answered, spread.slot_skew, spread.slot_regressions).yellow())?,
+        }
+    }
+    if let Some(sticky) = &report.stickiness {
+        let line = format!("{}Sessions: {} ({} backend(s) over one connection, {} over {} fresh ones, {} slot regressions)",
+            icon("🧲"), sticky.verdict(), sticky.pooled_backends, sticky.fresh_backends, sticky.requests,
+            sticky.pooled_regressions);
+        writeln!(out, "{}", if sticky.consistent() { line.dimmed() } else { line.yellow() })?;
+    }
+    if let Some(scaling) = &report.batch_scaling {
+        let cap = match (scaling.max_accepted, scaling.rejected_at) {
+            (Some(max), Some(_)) => format!("up to {} accounts accepted", max),
+            (Some(max), None) => format!("{} or more accounts accepted", max),
+            (None, _) => "no batch accepted".to_string(),
+        };
+        let line = format!("{}getMultipleAccounts batches: {}", icon("📦"), cap);
+        match &scaling.rejection {
+            Some(reason) => writeln!(out, "{}", format!("{} ({})", line, reason).dimmed())?,
+            None => writeln!(out, "{}", line.dimmed())?,
+        }
+        let ms = |v: Option<u128>| v.map(|v| format!("{}ms", v)).unwrap_or_else(|| "–".to_string());
+        let levels: Vec<String> = scaling
+            .levels
+            .iter()
+            .map(|level| match level.per_account_ms {
+                Some(each) => format!("{} → {} ({:.2}ms each)", level.size, ms(level.p50_ms), each),
+                None => format!("{} → failed", level.size),
+            })
+            .collect();
+        writeln!(out, "{}", format!("   p50 by batch size: {}", levels.join(", ")).dimmed())?;
+    }
+    if let Some(lag) = &report.slot_lag {
+        let line = format!("{}Slot lag vs {}: {:.1} slots (~{:.0}ms) on average, {} to {} over {} samples",
+            icon("⏱️ "), lag.reference, lag.avg_slots, lag.avg_behind_ms, lag.min_slots, lag.max_slots, lag.samples);
+        if lag.avg_slots >= crate::node::LAGGING_SLOTS {
+            writeln!(out, "{}", line.yellow())?;
+        } else {
+            writeln!(out, "{}", line.dimmed())?;
+        }
+    }
+    if let Some(fees) = &report.priority_fees {
+        let scope = if fees.accounts.is_empty() { "cluster-wide".to_string() } else { fees.accounts.join(", ") };
+        writeln!(out, "{}", format!("{}Priority fees (µlamports/CU, {} slots, {}): p50 {} | p75 {} | p90 {} | max {}",
+            icon("💸"), fees.slots, scope, fees.p50, fees.p75, fees.p90, fees.max).dimmed())?;
+    }
+    if let Some(ledger) = &report.ledger {
+        let kind = if ledger.archive { "archive" } else { "pruned" };
+        writeln!(out, "{}", format!("{}Ledger: first block {} | minimum ledger slot {} | ~{:.1} days of history ({})",
+            icon("📚"), ledger.first_available_block, ledger.minimum_ledger_slot, ledger.history_days, kind).dimmed())?;
+        if let Some(full) = ledger.full_snapshot_slot {
+            let incremental = ledger
+                .incremental_snapshot_slot
+                .map(|slot| format!(" | incremental {} ({} slots behind)", slot, ledger.current_slot.saturating_sub(slot)))
+                .unwrap_or_default();
+            writeln!(out, "{}", format!("{}Snapshots: full {} ({} slots behind){}",
+                icon("📸"), full, ledger.current_slot.saturating_sub(full), incremental).dimmed())?;
+        }
+    }
+    writeln!(out, "{}", format!("{}Overall Success Rate: {:.1}%", icon("🔍"), overall.success_rate)
+        .color(success_color(overall.success_rate)))?;
+    writeln!(out, "{}", format!("{}Overall Speed Rating: {} ({} ms {}, {} ms avg)",
+        icon("⚡"), speed_rating, overall.rating_ms.unwrap_or(0), metric, overall.avg_ms).color(rating_color))?;
+    writeln!(out, "{}", format!("{}Endpoint Score: {:.0}/100 (success {:.0}, median {:.0}, tail {:.0}, coverage {:.0})",
+        icon("🏆"), report.score.score, report.score.success, report.score.median, report.score.tail,
+        report.score.coverage).bold())?;
+    if let (Some(p50), Some(p90), Some(p95), Some(p99)) = (overall.p50_ms, overall.p90_ms, overall.p95_ms, overall.p99_ms) {
+        writeln!(out, "{}Overall Percentiles: p50 {}ms | p90 {}ms | p95 {}ms | p99 {}ms", icon("📈"), p50, p90, p95, p99)?;
+    }
+    if overall.skipped > 0 {
+        writeln!(out, "{}", format!("{}Skipped iterations: {} (max duration exceeded)",
+            icon("⏭️ "), overall.skipped).yellow())?;
+    }
+    if let Some(connection) = &report.connection {
+        let tls = connection.tls_ms.map(|ms| format!(" | tls {:.1}ms", ms)).unwrap_or_default();
+        writeln!(
+            out,
+            "{}Connection to {}: dns {:.1}ms | tcp {:.1}ms{} | request {:.1}ms | total {:.1}ms",
+            icon("🌐"),
+            connection.remote_addr,
+            connection.dns_ms,
+            connection.tcp_ms,
+            tls,
+            connection.request_ms,
+            connection.total_ms
+        )?;
+    }
+    let limits = &report.rate_limits;
+    if !limits.is_empty() {
+        let mut line = format!("{}Rate limits:", icon("🚦"));
+        if limits.throttled > 0 {
+            line.push_str(&format!(" {} × HTTP 429", limits.throttled));
+            if let Some(retry_after) = &limits.retry_after {
+                line.push_str(&format!(" (retry after {})", retry_after));
+            }
+            line.push(';');
+        }
+        for (name, value) in &limits.headers {
+            line.push_str(&format!(" {}={}", name, value));
+        }
+        let line = if limits.throttled > 0 { line.red() } else { line.normal() };
+        writeln!(out, "{}", line)?;
+    }
+    for throughput in &report.throughput {
+        let mut gaps = String::new();
+        if let Some(missing) = throughput.missing_slots {
+            gaps.push_str(&format!(", {} slots without events", missing));
+        }
+        if let Some(silence) = throughput.longest_silence_ms {
+            gaps.push_str(&format!(", longest silence {}ms", silence));
+        }
+        writeln!(out, "{}{}: {} notifications in {:.1}s ({:.1}/s, {:.2} MB/s{})", icon("📡"), throughput.name,
+            throughput.notifications, throughput.window_ms as f64 / 1000.0, throughput.per_sec, throughput.mbps, gaps)?;
+    }
+    if let Some(subs) = &report.subscription_limit {
+        let line = format!("{}Subscriptions per connection: {} ok ({:.1}ms each to confirm)",
+            icon("🧵"), subs.limit, subs.overhead_ms);
+        match (subs.failed_at, &subs.reason) {
+            (Some(count), Some(reason)) => writeln!(out, "{}", format!("{}; failed at {}: {}", line, count, reason).yellow())?,
+            _ => writeln!(out, "{}", line)?,
+        }
+    }
+    if let Some(race) = &report.race {
+        let behind = race.avg_behind_ms.map(|ms| format!(", {:.1}ms behind the winner otherwise", ms)).unwrap_or_default();
+        writeln!(out, "{}Race: first in {} of {} rounds ({:.1}%){}, {} failed", icon("🏁"),
+            race.wins, race.rounds, race.win_pct, behind, race.failures)?;
+    }
+    if let Some(failover) = &report.failover {
+        let line = format!("{}Failover: {:.1}% available vs {:.1}% for the primary alone ({} injected {} in {} rounds, {}ms timeout)",
+            icon("🛟"), failover.availability, failover.primary_availability, failover.injected,
+            failover.inject.label(), failover.rounds, failover.timeout_ms);
+        writeln!(out, "{}", if failover.failed > 0 { line.yellow() } else { line.normal() })?;
+        let served: Vec<String> = failover
+            .chain
+            .iter()
+            .zip(&failover.served_by)
+            .enumerate()
+            .map(|(i, (url, count))| format!("#{} {} {}", i + 1, host(url), count))
+            .collect();
+        let ms = |v: Option<u128>| v.map(|v| format!("{}ms", v)).unwrap_or_else(|| "–".to_string());
+        writeln!(out, "   time to success p50 {} | p95 {} | max {}; served by {}, unanswered {}",
+            ms(failover.p50_ms), ms(failover.p95_ms), ms(failover.max_ms), served.join(", "), failover.failed)?;
+    }
+    if let Some(load) = &report.load {
+        let offered = load.sent as f64 / (load.duration_ms as f64 / 1000.0).max(f64::EPSILON);
+        let line = format!("{}Load: {:.1} of {:.1} rps achieved under {} ({} sent, {} ok, at most {} in flight)",
+            icon("📶"), load.achieved_rps, offered, load.profile, load.sent, load.succeeded, load.max_in_flight);
+        // Falling short of the offered rate means the endpoint is past its capacity
+        let saturated = load.achieved_rps < offered * 0.95 || load.succeeded < load.sent;
+        writeln!(out, "{}", if saturated { line.yellow() } else { line.normal() })?;
+        if !load.windows.is_empty() {
+            writeln!(out, "{}", format!("   {:>15} {:>9} {:>7} {:>7} {:>7}", "Window", "rps", "p50 ms", "p99 ms", "ok").dimmed())?;
+            let ms = |v: Option<u128>| v.map(|v| v.to_string()).unwrap_or_else(|| "–".to_string());
+            for window in &load.windows {
+                let ok = if window.sent > 0 { window.succeeded as f64 / window.sent as f64 * 100.0 } else { 0.0 };
+                let span = format!("{:.0}s–{:.0}s", window.from_ms as f64 / 1000.0, window.to_ms as f64 / 1000.0);
+                let row = format!("   {:>15} {:>9.1} {:>7} {:>7} {:>6.1}%", span, window.target_rps,
+                    ms(window.p50_ms), ms(window.p99_ms), ok);
+                writeln!(out, "{}", if window.succeeded < window.sent { row.yellow() } else { row.normal() })?;
+            }
+        }
+    }
+    if let Some(closed) = &report.closed_loop {
+        let line = format!("{}Closed loop: {} workers achieved {:.1} rps over {:.0}s ({} sent, {} ok)",
+            icon("👷"), closed.workers, closed.achieved_rps, closed.duration_ms as f64 / 1000.0, closed.sent, closed.succeeded);
+        writeln!(out, "{}", if closed.succeeded < closed.sent { line.yellow() } else { line.normal() })?;
+    }
+    for bandwidth in &report.bandwidth {
+        let limit = match bandwidth.limit {
+            super::Limit::Compute => "compute-limited",
+            super::Limit::Bandwidth => "bandwidth-limited",
+        };
+        let transfer = bandwidth.transfer_mbps.map(|mbps| format!(", {:.1} MB/s while downloading", mbps)).unwrap_or_default();
+        writeln!(out, "{}Bandwidth of {}: {:.1} MB/s sustained on {:.1} MB responses{}; {} ({:.0}% waiting for the first byte)",
+            icon("🚚"), bandwidth.test, bandwidth.sustained_mbps, bandwidth.avg_mb, transfer, limit, bandwidth.ttfb_pct)?;
+    }
+    if let Some(streaming) = &report.streaming_under_load {
+        writeln!(out, "{}Streaming under load: {} slot subscriptions on {} connection(s){}", icon("🌊"),
+            streaming.subscriptions, streaming.connections,
+            if streaming.dropped_connections > 0 { format!(", {} dropped", streaming.dropped_connections) } else { String::new() })?;
+        let ms = |v: Option<u128>| v.map(|v| format!("{}ms", v)).unwrap_or_else(|| "–".to_string());
+        for (label, stats) in [("before", &streaming.baseline), ("during", &streaming.under_load)] {
+            let line = format!("   {} load: fan-out lag p50 {} | p99 {} | max {} over {} slots, {} of {} notifications missed",
+                label, ms(stats.p50_lag_ms), ms(stats.p99_lag_ms), ms(stats.max_lag_ms), stats.slots, stats.missed,
+                stats.notifications + stats.missed);
+            writeln!(out, "{}", if stats.missed > 0 { line.yellow() } else { line.normal() })?;
+        }
+    }
+    if let Some(sweep) = &report.sweep {
+        writeln!(out, "{}Concurrency sweep of {} ({:.0}s per level):", icon("🪜"), sweep.method, sweep.step_ms as f64 / 1000.0)?;
+        writeln!(out, "{}", format!("   {:>7} {:>9} {:>7} {:>7} {:>7} {:>7}", "Workers", "rps", "p50 ms", "p95 ms", "p99 ms", "ok").dimmed())?;
+        let ms = |v: Option<u128>| v.map(|v| v.to_string()).unwrap_or_else(|| "–".to_string());
+        for level in &sweep.levels {
+            let ok = if level.requests > 0 { level.succeeded as f64 / level.requests as f64 * 100.0 } else { 0.0 };
+            let row = format!("   {:>7} {:>9.1} {:>7} {:>7} {:>7} {:>6.1}%", level.concurrency, level.rps,
+                ms(level.p50_ms), ms(level.p95_ms), ms(level.p99_ms), ok);
+            if sweep.peak_concurrency == Some(level.concurrency) {
+                writeln!(out, "{}  {}", row.green(), "peak".green())?;
+            } else {
+                writeln!(out, "{}", row)?;
+            }
+        }
+    }
+    if let Some(capacity) = &report.capacity {
+        let limits = format!("≤{}% errors, p99 ≤{}ms", capacity.max_error_pct, capacity.max_p99_ms);
+        match capacity.sustainable_rps {
+            Some(rps) if capacity.at_range_limit => writeln!(out, "{}Capacity: at least {:.0} rps ({}), the top of the searched range",
+                icon("🎯"), rps, limits)?,
+            Some(rps) => writeln!(out, "{}Capacity: {:.0} rps sustained ({})", icon("🎯"), rps, limits)?,
+            None => writeln!(out, "{}", format!("{}Capacity: not even the lowest rate stayed within {}", icon("🎯"), limits).yellow())?,
+        }
+        let ms = |v: Option<u128>| v.map(|v| format!("{}ms", v)).unwrap_or_else(|| "–".to_string());
+        for probe in &capacity.probes {
+            let row = format!("   {:>7.0} rps → {:>7.1} achieved, {:>5.1}% errors, p99 {}",
+                probe.target_rps, probe.achieved_rps, probe.error_pct, ms(probe.p99_ms));
+            writeln!(out, "{}", if probe.passed { row.green() } else { row.red() })?;
+        }
+    }
+    if let Some(probe) = &report.rate_limit_probe {
+        match (probe.throttled_at_rps, probe.last_clean_rps) {
+            (Some(throttled), Some(clean)) => writeln!(out, "{}", format!("{}Rate limit: throttled at {:.0} rps, clean at {:.0} rps",
+                icon("🚦"), throttled, clean).yellow())?,
+            (Some(throttled), None) => writeln!(out, "{}", format!("{}Rate limit: throttled already at {:.0} rps",
+                icon("🚦"), throttled).yellow())?,
+            (None, _) => {
+                let top = probe.steps.last().map(|step| step.rps).unwrap_or_default();
+                writeln!(out, "{}Rate limit: no 429s up to {:.0} rps", icon("🚦"), top)?
+            }
+        }
+        if probe.throttled_at_rps.is_some() {
+            let retry_after = if probe.retry_after.is_empty() {
+                "no Retry-After".to_string()
+            } else {
+                format!("Retry-After {}", probe.retry_after.join(", "))
+            };
+            let missing = if probe.missing_retry_after > 0 && !probe.retry_after.is_empty() {
+                format!(" ({} 429s without it)", probe.missing_retry_after)
+            } else {
+                String::new()
+            };
+            writeln!(out, "   {}{}", retry_after, missing)?;
+        }
+        if let Some(recovery) = &probe.recovery {
+            let line = match recovery.recovered_after_ms {
+                Some(_) if recovery.clean() => format!("   Recovered cleanly after the {}ms pause", recovery.waited_ms),
+                Some(ms) => format!("   Recovered {}ms after backing off, still throttled {} time(s) past the {}ms pause",
+                    ms, recovery.throttled_while_retrying, recovery.waited_ms),
+                None => format!("   Not recovered within the retry window after a {}ms pause ({} more 429s)",
+                    recovery.waited_ms, recovery.throttled_while_retrying),
+            };
+            writeln!(out, "{}", if recovery.clean() { line.green() } else { line.red() })?;
+        }
+    }
+    writeln!(out)?;
+
+    // Print divider
+    writeln!(out, "{}", rule(false).dimmed())?;
+    writeln!(out)?;
+
+    // Sort tests by average duration (fastest first)
+    let mut sorted_tests: Vec<_> = report.tests.iter().collect();
+    sorted_tests.sort_by_key(|test| test.avg_ms.unwrap_or(u128::MAX));
+
+    for test in sorted_tests {
+        if test.total == 0 {
+            writeln!(out, "{}{} {} (0/0)", icon("🔹"), test.name.bold(), "skipped".yellow())?;
+            writeln!(out, "  {}Skipped {} iterations (max duration exceeded)", icon("⏭️ "), test.skipped)?;
+            writeln!(out)?;
+            continue;
+        }
+
+        write!(
+            out,
+            "{}{} {} ({}/{})",
+            icon("🔹"),
+            test.name.bold(),
+            format!("{:.1}%", test.success_rate).color(success_color(test.success_rate)),
+            test.successful,
+            test.total
+        )?;
+        match sparkline(test) {
+            Some(line) => writeln!(out, "  {}", line.cyan())?,
+            None => writeln!(out)?,
+        }
+
+        if let (Some(avg), Some(min), Some(max)) = (test.avg_ms, test.min_ms, test.max_ms) {
+            let rating_ms = test.rating_ms.unwrap_or(avg);
+            let (speed_rating, rating_color) = report.config.speed_thresholds.rate(rating_ms);
+            writeln!(
+                out,
+                "  {}Response time: avg {}ms | min {}ms | max {}ms",
+                icon("⏱️ "),
+                avg.to_string().cyan(),
+                min.to_string().green(),
+                max.to_string().yellow()
+            )?;
+            writeln!(
+                out,
+                "  {}Speed rating: {} ({} {}ms)",
+                icon("💨"),
+                speed_rating.color(rating_color),
+                metric,
+                rating_ms
+            )?;
+        }
+
+        if let (Some(p50), Some(p90), Some(p95), Some(p99)) = (test.p50_ms, test.p90_ms, test.p95_ms, test.p99_ms) {
+            writeln!(
+                out,
+                "  {}Percentiles: p50 {}ms | p90 {}ms | p95 {}ms | p99 {}ms",
+                icon("📈"),
+                p50.to_string().cyan(),
+                p90.to_string().cyan(),
+                p95.to_string().yellow(),
+                p99.to_string().red()
+            )?;
+        }
+
+        if !test.rolling.is_empty() {
+            let windows: Vec<String> = test
+                .rolling
+                .iter()
+                .map(|w| {
+                    let avg = w.avg_ms.map(|ms| format!("{}ms", ms)).unwrap_or_else(|| "–".to_string());
+                    format!("{} {} {:.0}%", w.window, avg, w.success_rate)
+                })
+                .collect();
+            writeln!(out, "  {}Recent: {}", icon("🕒"), windows.join(" | "))?;
+        }
+
+        if let Some(trimmed) = test.trimmed_avg_ms {
+            writeln!(out, "  {}Trimmed mean: {}ms", icon("✂️ "), trimmed.to_string().cyan())?;
+        }
+
+        if !test.outliers.is_empty() {
+            let flagged: Vec<String> = test
+                .samples
+                .iter()
+                .filter(|s| s.outlier)
+                .map(|s| format!("#{} ({}ms)", s.iteration, s.duration_ms))
+                .collect();
+            writeln!(out, "  {}Outliers: {}", icon("🎯"), flagged.join(", ").yellow())?;
+        }
+
+        if let Some(stddev) = test.stddev_ms {
+            let jitter = test.jitter_ms.map(|j| format!("{}ms", j)).unwrap_or_else(|| "–".to_string());
+            let cv = test.cv.map(|cv| format!(" | cv {:.2}", cv)).unwrap_or_default();
+            writeln!(out, "  {}Spread: stddev {:.1}ms | max jump {}{}", icon("📉"), stddev, jitter, cv)?;
+        }
+
+        if test.inconsistent {
+            writeln!(
+                out,
+                "  {}{}",
+                icon("⚠️ "),
+                "Fast on average but wildly inconsistent between iterations".yellow()
+            )?;
+        }
+
+        if let (Some(ttfb), Some(download)) = (test.avg_ttfb_ms, test.avg_download_ms) {
+            writeln!(out, "  {}Breakdown: ttfb {}ms | download {}ms", icon("🔬"), ttfb, download)?;
+        }
+
+        if let Some(confirm) = test.avg_confirm_ms {
+            writeln!(out, "  {}Confirmation: {}ms avg after send", icon("⏱️ "), confirm)?;
+        }
+
+        if let Some(units) = test.avg_compute_units {
+            writeln!(out, "  {}Compute units: {} avg", icon("🧮"), units)?;
+        }
+
+        if let (Some(bytes), Some(mbps)) = (test.avg_bytes, test.throughput_mbps) {
+            writeln!(out, "  {}Payload: {} avg | {:.2} MB/s", icon("📦"), format_bytes(bytes), mbps)?;
+        }
+
+        // Only worth calling out when latency clearly follows the payload
+        if let (Some(r), Some(slope)) = (test.size_correlation, test.ms_per_kb) {
+            if r >= SIZE_CORRELATION_CALLOUT && slope > 0.0 {
+                writeln!(
+                    out,
+                    "  {}Latency tracks payload size (r={:.2}, ~{:.2}ms per KB): slowness is mostly transfer, not server time",
+                    icon("🔗"),
+                    r,
+                    slope
+                )?;
+            }
+        }
+
+        write_distribution(out, test)?;
+
+        if test.skipped > 0 {
+            writeln!(out, "  {}Skipped {} iterations (max duration exceeded)", icon("⏭️ "), test.skipped)?;
+        }
+
+        // Print errors if any
+        if !test.error_categories.is_empty() {
+            let categories: Vec<String> = test
+                .error_categories
+                .iter()
+                .map(|(category, count)| format!("{} ×{}", category, count))
+                .collect();
+            writeln!(out, "  {}{}: {}", icon("🧩"), "Error categories".red(), categories.join(", "))?;
+        }
+        for error in &test.errors {
+            writeln!(out, "  {}{}: {}", icon("❌"), "Error".red(), error)?;
+        }
+
+        writeln!(out)?;
+    }
+
+    // Print footer
+    writeln!(out, "{}", rule(true).bright_blue())?;
+    writeln!(out, "{}", "Thank you for using Solana RPC Performance Checker!".bright_green())?;
+    writeln!(out, "{}", "Created by Nitriot (@nitriotsol) | Twitter | Telegram: vitualsolana | Discord: nitriot".dimmed())?;
+
+    Ok(())
+}
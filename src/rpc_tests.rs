@@ -0,0 +1,853 @@
+use crate::endpoint::Endpoint;
+use crate::har::HarRecorder;
+use crate::rate_limit::RateLimitTracker;
+use crate::transport::{Transport, TransportCounters, TransportSender};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde_json::{json, Value};
+use solana_client::client_error::Result as ClientResult;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::RpcClientConfig;
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::rpc_config::{
+    RpcAccountInfoConfig, RpcBlockConfig, RpcProgramAccountsConfig, RpcSimulateTransactionConfig, RpcSupplyConfig,
+};
+use solana_client::rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType};
+use solana_client::rpc_request::{RpcRequest, TokenAccountsFilter};
+use solana_client::rpc_response::{Response, RpcKeyedAccount, RpcSupply};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status::UiTransactionEncoding;
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+
+/// Wallet the balance and token account tests look up
+pub const SAMPLE_WALLET: &str = "SoLANAGZJPWXuWQiACz5JJzx1jZKp55FpbjLPwmxA";
+
+pub struct TestResult {
+    pub name: String,
+    pub success: bool,
+    pub skipped: bool,
+    pub duration_ms: u128,
+    pub error: Option<String>,
+    /// When the iteration finished (or was skipped)
+    pub timestamp: DateTime<Utc>,
+    /// Response body bytes of the timed request
+    pub bytes: u64,
+    /// Time until the timed request's response headers arrived
+    pub ttfb_ms: u128,
+    /// Time spent reading the timed request's response body
+    pub download_ms: u128,
+    /// Compute units a simulated transaction consumed
+    pub compute_units: Option<u64>,
+    /// Time from a successful send until the transaction was confirmed
+    pub confirm_ms: Option<u128>,
+}
+
+impl TestResult {
+    pub fn success(name: &str, duration: Duration) -> Self {
+        TestResult {
+            name: name.to_string(),
+            success: true,
+            skipped: false,
+            duration_ms: duration.as_millis(),
+            error: None,
+            timestamp: Utc::now(),
+            bytes: 0,
+            ttfb_ms: 0,
+            download_ms: 0,
+            compute_units: None,
+            confirm_ms: None,
+        }
+    }
+
+    pub fn failure(name: &str, duration: Duration, error: impl Display) -> Self {
+        TestResult {
+            name: name.to_string(),
+            success: false,
+            skipped: false,
+            duration_ms: duration.as_millis(),
+            error: Some(error.to_string()),
+            timestamp: Utc::now(),
+            bytes: 0,
+            ttfb_ms: 0,
+            download_ms: 0,
+            compute_units: None,
+            confirm_ms: None,
+        }
+    }
+
+    /// An iteration that never ran because the run deadline was reached
+    pub fn skipped(name: &str) -> Self {
+        TestResult {
+            name: name.to_string(),
+            success: false,
+            skipped: true,
+            duration_ms: 0,
+            error: None,
+            timestamp: Utc::now(),
+            bytes: 0,
+            ttfb_ms: 0,
+            download_ms: 0,
+            compute_units: None,
+            confirm_ms: None,
+        }
+    }
+
+    pub fn with_compute_units(mut self, units: Option<u64>) -> Self {
+        self.compute_units = units;
+        self
+    }
+
+    pub fn with_confirmation(mut self, confirm: Option<Duration>) -> Self {
+        self.confirm_ms = confirm.map(|d| d.as_millis());
+        self
+    }
+
+    pub fn from_result<T, E: Display>(name: &str, duration: Duration, result: Result<T, E>) -> Self {
+        match result {
+            Ok(_) => TestResult::success(name, duration),
+            Err(e) => TestResult::failure(name, duration, e),
+        }
+    }
+}
+
+/// A `memcmp` filter given as `OFFSET:BASE58_BYTES`
+#[derive(Clone, Debug)]
+pub struct MemcmpFilter {
+    pub offset: usize,
+    pub bytes: String,
+}
+
+impl FromStr for MemcmpFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (offset, bytes) = s.split_once(':').ok_or("expected OFFSET:BASE58_BYTES")?;
+        let offset = offset.trim().parse().map_err(|_| format!("invalid offset '{}'", offset))?;
+        Ok(MemcmpFilter { offset, bytes: bytes.trim().to_string() })
+    }
+}
+
+/// A `dataSlice` given as `OFFSET:LENGTH`
+#[derive(Clone, Copy, Debug)]
+pub struct DataSlice {
+    pub offset: usize,
+    pub length: usize,
+}
+
+impl FromStr for DataSlice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (offset, length) = s.split_once(':').ok_or("expected OFFSET:LENGTH")?;
+        let offset = offset.trim().parse().map_err(|_| format!("invalid offset '{}'", offset))?;
+        let length = length.trim().parse().map_err(|_| format!("invalid length '{}'", length))?;
+        Ok(DataSlice { offset, length })
+    }
+}
+
+/// The getProgramAccounts query to benchmark
+#[derive(Clone, Debug)]
+pub struct ProgramAccountsQuery {
+    pub program: Pubkey,
+    pub data_size: Option<u64>,
+    pub memcmp: Vec<MemcmpFilter>,
+    pub data_slice: Option<DataSlice>,
+}
+
+impl ProgramAccountsQuery {
+    /// SPL Token accounts (165 bytes) owned by the sample wallet (offset 32), reading only
+    /// the mint and owner of each
+    pub fn sample_token_accounts(program: Pubkey) -> Self {
+        ProgramAccountsQuery {
+            program,
+            data_size: Some(165),
+            memcmp: vec![MemcmpFilter { offset: 32, bytes: SAMPLE_WALLET.to_string() }],
+            data_slice: Some(DataSlice { offset: 0, length: 64 }),
+        }
+    }
+}
+
+/// User-supplied inputs of the tests
+#[derive(Clone, Debug)]
+pub struct TestSettings {
+    pub program_accounts: ProgramAccountsQuery,
+    /// Vote account for the reward lookups, the highest-staked one if unset
+    pub vote_account: Option<Pubkey>,
+    /// Stake account for getStakeActivation, the test is left out without one
+    pub stake_account: Option<Pubkey>,
+    /// Writable accounts to scope getRecentPrioritizationFees to
+    pub fee_accounts: Vec<Pubkey>,
+    /// Funded keypair paying for simulated and (with `send`) submitted transactions
+    pub keypair: Option<Arc<Keypair>>,
+    /// Actually submit transactions in the sendTransaction test
+    pub send: bool,
+    /// Run the Digital Asset Standard tests of enhanced endpoints
+    pub das: bool,
+    /// Run the large-response tests that measure sustained transfer rates
+    pub bandwidth: bool,
+    /// Ad-hoc JSON-RPC call from --method/--params, benchmarked alongside the suite
+    pub custom: Option<CustomMethod>,
+}
+
+/// Any JSON-RPC method with raw params
+#[derive(Clone, Debug)]
+pub struct CustomMethod {
+    pub method: String,
+    pub params: Option<Value>,
+}
+
+/// Everything a test needs to talk to the endpoint under test
+pub struct TestContext {
+    pub client: RpcClient,
+    /// Raw HTTP path shared with `client`, for requests the client can't make
+    pub transport: Arc<Transport>,
+    pub settings: Arc<TestSettings>,
+    /// Resolved once per context so iterations only time the call under test
+    vote_account: OnceCell<Pubkey>,
+    /// Filled in by getTokenAccountsByOwner, or looked up on first use
+    token_account: OnceCell<Pubkey>,
+    /// Transport counters of the latest timed call, for the runner to pick up
+    measured: Mutex<Option<TransportCounters>>,
+}
+
+impl TestContext {
+    pub fn new(
+        endpoint: &Endpoint,
+        har: Option<HarRecorder>,
+        rate_limits: RateLimitTracker,
+        settings: Arc<TestSettings>,
+    ) -> Self {
+        let transport = Arc::new(endpoint.transport(har, rate_limits));
+        let client = RpcClient::new_sender(
+            TransportSender(transport.clone()),
+            RpcClientConfig::with_commitment(endpoint.commitment()),
+        );
+
+        TestContext {
+            client,
+            transport,
+            settings,
+            vote_account: OnceCell::new(),
+            token_account: OnceCell::new(),
+            measured: Mutex::new(None),
+        }
+    }
+
+    /// Time the call under test, keeping the bytes and transfer times of just that call so
+    /// setup requests before or after it don't count towards the iteration
+    pub async fn timed<T>(&self, call: impl Future<Output = T>) -> (Duration, T) {
+        let before = self.transport.counters();
+        let start = Instant::now();
+        let output = call.await;
+        let duration = start.elapsed();
+        *self.measured.lock().unwrap() = Some(self.transport.counters() - before);
+        (duration, output)
+    }
+
+    /// Counters of the latest timed call, zero if none was made since the last take
+    pub fn take_measured(&self) -> TransportCounters {
+        self.measured.lock().unwrap().take().unwrap_or_default()
+    }
+
+    /// A token account of the sample wallet, as found by getTokenAccountsByOwner
+    pub async fn token_account(&self) -> Result<Pubkey> {
+        let pubkey = self
+            .token_account
+            .get_or_try_init(|| async {
+                let accounts = get_token_accounts(self).await?;
+                let first = accounts.first().ok_or_else(|| anyhow!("sample wallet has no token accounts"))?;
+                Ok::<_, anyhow::Error>(Pubkey::from_str(&first.pubkey)?)
+            })
+            .await?;
+        Ok(*pubkey)
+    }
+
+    /// The configured vote account, or the highest-staked current one
+    pub async fn vote_account(&self) -> Result<Pubkey> {
+        let pubkey = self
+            .vote_account
+            .get_or_try_init(|| async {
+                if let Some(pubkey) = self.settings.vote_account {
+                    return Ok(pubkey);
+                }
+                let accounts = self.client.get_vote_accounts().await?;
+                let top = accounts
+                    .current
+                    .iter()
+                    .max_by_key(|account| account.activated_stake)
+                    .ok_or_else(|| anyhow!("no current vote accounts"))?;
+                Ok::<_, anyhow::Error>(Pubkey::from_str(&top.vote_pubkey)?)
+            })
+            .await?;
+        Ok(*pubkey)
+    }
+}
+
+pub type TestFn = fn(&TestContext) -> BoxFuture<'_, Result<TestResult>>;
+
+async fn test_get_latest_blockhash(ctx: &TestContext) -> Result<TestResult> {
+    let (duration, result) = ctx.timed(ctx.client.get_latest_blockhash()).await;
+    Ok(TestResult::from_result("getLatestBlockhash", duration, result))
+}
+
+async fn test_get_slot(ctx: &TestContext) -> Result<TestResult> {
+    let (duration, result) = ctx.timed(ctx.client.get_slot()).await;
+    Ok(TestResult::from_result("getSlot", duration, result))
+}
+
+async fn test_get_balance(ctx: &TestContext) -> Result<TestResult> {
+    // Using a known Solana address for testing
+    let address = Pubkey::from_str(SAMPLE_WALLET).unwrap_or_default();
+
+    let (duration, result) = ctx.timed(ctx.client.get_balance(&address)).await;
+    Ok(TestResult::from_result("getBalance", duration, result))
+}
+
+/// Calls packed into the batched request
+const BATCH_SIZE: usize = 10;
+
+async fn test_batch_get_balance(ctx: &TestContext) -> Result<TestResult> {
+    const NAME: &str = "getBalance (batch of 10)";
+    let batch: Vec<Value> = (0..BATCH_SIZE)
+        .map(|id| json!({ "jsonrpc": "2.0", "id": id, "method": "getBalance", "params": [SAMPLE_WALLET] }))
+        .collect();
+
+    let (duration, response) = ctx.timed(ctx.transport.post_json(&Value::Array(batch))).await;
+
+    let response = match response {
+        Ok(resp) if !resp.status.is_success() => {
+            return Ok(TestResult::failure(NAME, duration, format!("HTTP {}: {}", resp.status, resp.body)));
+        }
+        Ok(resp) => resp,
+        Err(e) => return Ok(TestResult::failure(NAME, duration, e)),
+    };
+
+    // Load balancers that don't understand batches tend to answer with a single
+    // object, drop entries or mix up ids, so check every part of the answer
+    let json: Value = serde_json::from_str(&response.body)?;
+    let Some(items) = json.as_array() else {
+        return Ok(TestResult::failure(NAME, duration, format!("Batch answered with a non-array: {}", json)));
+    };
+    if items.len() != BATCH_SIZE {
+        return Ok(TestResult::failure(
+            NAME,
+            duration,
+            format!("Batch of {} answered with {} responses", BATCH_SIZE, items.len()),
+        ));
+    }
+    for id in 0..BATCH_SIZE {
+        match items.iter().find(|item| item["id"] == id) {
+            Some(item) if item["result"].is_object() => {}
+            Some(item) => {
+                return Ok(TestResult::failure(NAME, duration, format!("Batch entry {} failed: {}", id, item["error"])));
+            }
+            None => return Ok(TestResult::failure(NAME, duration, format!("Batch response is missing id {}", id))),
+        }
+    }
+
+    Ok(TestResult::success(NAME, duration))
+}
+
+async fn test_get_account_info(ctx: &TestContext) -> Result<TestResult> {
+    // Using a known Solana address for testing
+    let address = Pubkey::from_str(SAMPLE_WALLET).unwrap_or_default();
+
+    let call = ctx.client.get_account_with_commitment(&address, CommitmentConfig::confirmed());
+    let (duration, result) = ctx.timed(call).await;
+    Ok(TestResult::from_result("getAccountInfo", duration, result))
+}
+
+async fn test_get_recent_performance_samples(ctx: &TestContext) -> Result<TestResult> {
+    let (duration, result) = ctx.timed(ctx.client.get_recent_performance_samples(Some(10))).await;
+    Ok(TestResult::from_result("getRecentPerformanceSamples", duration, result))
+}
+
+async fn get_supply(ctx: &TestContext, exclude_accounts: bool) -> (Duration, ClientResult<Response<RpcSupply>>) {
+    let config = RpcSupplyConfig {
+        commitment: None,
+        exclude_non_circulating_accounts_list: exclude_accounts,
+    };
+
+    ctx.timed(ctx.client.send(RpcRequest::GetSupply, json!([config]))).await
+}
+
+async fn test_get_supply(ctx: &TestContext) -> Result<TestResult> {
+    // The full variant returns every non-circulating account, which is
+    // expensive enough that some providers turn it off
+    let (duration, result) = get_supply(ctx, false).await;
+    Ok(match result {
+        Ok(_) => TestResult::success("getSupply", duration),
+        Err(e) => TestResult::failure(
+            "getSupply",
+            duration,
+            format!("{} (the non-circulating accounts list may be disabled by the provider)", e),
+        ),
+    })
+}
+
+async fn test_get_supply_excluding_accounts(ctx: &TestContext) -> Result<TestResult> {
+    let (duration, result) = get_supply(ctx, true).await;
+    Ok(TestResult::from_result("getSupply (excl. accounts)", duration, result))
+}
+
+async fn test_get_inflation_rate(ctx: &TestContext) -> Result<TestResult> {
+    let (duration, result) = ctx.timed(ctx.client.get_inflation_rate()).await;
+    Ok(TestResult::from_result("getInflationRate", duration, result))
+}
+
+async fn test_get_inflation_reward(ctx: &TestContext) -> Result<TestResult> {
+    let vote_account = match ctx.vote_account().await {
+        Ok(pubkey) => pubkey,
+        Err(e) => {
+            return Ok(TestResult::failure(
+                "getInflationReward",
+                Duration::ZERO,
+                format!("Failed to pick a vote account: {}", e),
+            ));
+        }
+    };
+
+    // Without an epoch the node looks up the most recently completed one,
+    // which is served from long-term storage on many setups
+    let (duration, result) = ctx.timed(ctx.client.get_inflation_reward(&[vote_account], None)).await;
+    Ok(TestResult::from_result("getInflationReward", duration, result))
+}
+
+async fn test_get_stake_minimum_delegation(ctx: &TestContext) -> Result<TestResult> {
+    let (duration, result) = ctx.timed(ctx.client.get_stake_minimum_delegation()).await;
+    Ok(TestResult::from_result("getStakeMinimumDelegation", duration, result))
+}
+
+async fn test_get_stake_activation(ctx: &TestContext) -> Result<TestResult> {
+    let Some(stake_account) = ctx.settings.stake_account else {
+        return Ok(TestResult::failure("getStakeActivation", Duration::ZERO, "No stake account configured"));
+    };
+
+    // The point is timing the RPC method itself while nodes still serve it; it is being
+    // removed from the JSON-RPC API, which is what the deprecation is about
+    #[allow(deprecated)]
+    let (duration, result) = ctx.timed(ctx.client.get_stake_activation(stake_account, None)).await;
+    Ok(TestResult::from_result("getStakeActivation", duration, result))
+}
+
+async fn test_get_first_available_block(ctx: &TestContext) -> Result<TestResult> {
+    let (duration, result) = ctx.timed(ctx.client.get_first_available_block()).await;
+    Ok(TestResult::from_result("getFirstAvailableBlock", duration, result))
+}
+
+async fn test_minimum_ledger_slot(ctx: &TestContext) -> Result<TestResult> {
+    let (duration, result) = ctx.timed(ctx.client.minimum_ledger_slot()).await;
+    Ok(TestResult::from_result("minimumLedgerSlot", duration, result))
+}
+
+async fn test_get_highest_snapshot_slot(ctx: &TestContext) -> Result<TestResult> {
+    let (duration, result) = ctx.timed(ctx.client.get_highest_snapshot_slot()).await;
+    Ok(TestResult::from_result("getHighestSnapshotSlot", duration, result))
+}
+
+async fn test_get_recent_prioritization_fees(ctx: &TestContext) -> Result<TestResult> {
+    let (duration, result) = ctx.timed(ctx.client.get_recent_prioritization_fees(&ctx.settings.fee_accounts)).await;
+    Ok(TestResult::from_result("getRecentPrioritizationFees", duration, result))
+}
+
+async fn test_get_epoch_schedule(ctx: &TestContext) -> Result<TestResult> {
+    let (duration, result) = ctx.timed(ctx.client.get_epoch_schedule()).await;
+    Ok(TestResult::from_result("getEpochSchedule", duration, result))
+}
+
+async fn test_get_identity(ctx: &TestContext) -> Result<TestResult> {
+    let (duration, result) = ctx.timed(ctx.client.get_identity()).await;
+    Ok(TestResult::from_result("getIdentity", duration, result))
+}
+
+async fn test_get_genesis_hash(ctx: &TestContext) -> Result<TestResult> {
+    let (duration, result) = ctx.timed(ctx.client.get_genesis_hash()).await;
+    Ok(TestResult::from_result("getGenesisHash", duration, result))
+}
+
+async fn test_get_block(ctx: &TestContext) -> Result<TestResult> {
+    // First get the current slot
+    let slot = match ctx.client.get_slot().await {
+        Ok(slot) => slot,
+        Err(e) => {
+            return Ok(TestResult::failure("getBlock", Duration::ZERO, format!("Failed to get slot: {}", e)));
+        }
+    };
+
+    // Use a slot that's a bit older to ensure it's available
+    let slot = slot.saturating_sub(10);
+
+    // Use a custom config to handle transaction version
+    let config = RpcBlockConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        transaction_details: None,
+        rewards: None,
+        commitment: None,
+        max_supported_transaction_version: Some(0),
+    };
+
+    let (duration, result) = ctx.timed(ctx.client.get_block_with_config(slot, config)).await;
+    Ok(TestResult::from_result("getBlock", duration, result))
+}
+
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+async fn get_token_accounts(ctx: &TestContext) -> ClientResult<Vec<RpcKeyedAccount>> {
+    let address = Pubkey::from_str(SAMPLE_WALLET).unwrap_or_default();
+    let token_program_id = Pubkey::from_str(TOKEN_PROGRAM_ID).unwrap();
+
+    ctx.client.get_token_accounts_by_owner(&address, TokenAccountsFilter::ProgramId(token_program_id)).await
+}
+
+async fn test_get_token_accounts_by_owner(ctx: &TestContext) -> Result<TestResult> {
+    let (duration, result) = ctx.timed(get_token_accounts(ctx)).await;
+
+    // Remember an account for getTokenAccountBalance, so it tests real data
+    if let Some(pubkey) = result.as_ref().ok().and_then(|accounts| accounts.first()) {
+        if let Ok(pubkey) = Pubkey::from_str(&pubkey.pubkey) {
+            let _ = ctx.token_account.set(pubkey);
+        }
+    }
+
+    Ok(TestResult::from_result("getTokenAccountsByOwner", duration, result))
+}
+
+async fn test_get_token_account_balance(ctx: &TestContext) -> Result<TestResult> {
+    let token_account = match ctx.token_account().await {
+        Ok(pubkey) => pubkey,
+        Err(e) => {
+            return Ok(TestResult::failure(
+                "getTokenAccountBalance",
+                Duration::ZERO,
+                format!("Failed to find a token account: {}", e),
+            ));
+        }
+    };
+
+    let (duration, result) = ctx.timed(ctx.client.get_token_account_balance(&token_account)).await;
+    Ok(TestResult::from_result("getTokenAccountBalance", duration, result))
+}
+
+async fn test_get_program_accounts(ctx: &TestContext) -> Result<TestResult> {
+    let query = &ctx.settings.program_accounts;
+
+    let mut filters: Vec<RpcFilterType> = query.data_size.map(RpcFilterType::DataSize).into_iter().collect();
+    filters.extend(
+        query
+            .memcmp
+            .iter()
+            .map(|m| RpcFilterType::Memcmp(Memcmp::new(m.offset, MemcmpEncodedBytes::Base58(m.bytes.clone())))),
+    );
+
+    let config = RpcProgramAccountsConfig {
+        filters: (!filters.is_empty()).then_some(filters),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: query.data_slice.map(|s| UiDataSliceConfig { offset: s.offset, length: s.length }),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let (duration, result) = ctx.timed(ctx.client.get_program_accounts_with_config(&query.program, config)).await;
+
+    let accounts = match result {
+        Ok(accounts) => accounts,
+        Err(e) => return Ok(TestResult::failure("getProgramAccounts", duration, e)),
+    };
+
+    // With a fixed account size every slice must come back complete, anything
+    // shorter means the endpoint cut the data down
+    if let (Some(slice), Some(size)) = (query.data_slice, query.data_size) {
+        let expected = slice.length.min((size as usize).saturating_sub(slice.offset));
+        if let Some((pubkey, account)) = accounts.iter().find(|(_, a)| a.data.len() < expected) {
+            return Ok(TestResult::failure(
+                "getProgramAccounts",
+                duration,
+                format!("Truncated dataSlice for {}: expected {} bytes, got {}", pubkey, expected, account.data.len()),
+            ));
+        }
+    }
+
+    Ok(TestResult::success("getProgramAccounts", duration))
+}
+
+/// A transfer from `payer` to itself, the cheapest transaction that executes
+fn self_transfer(payer: &Keypair, lamports: u64, blockhash: Hash) -> Transaction {
+    let instruction = system_instruction::transfer(&payer.pubkey(), &payer.pubkey(), lamports);
+    Transaction::new_signed_with_payer(&[instruction], Some(&payer.pubkey()), &[payer], blockhash)
+}
+
+async fn test_simulate_transaction(ctx: &TestContext) -> Result<TestResult> {
+    // The node swaps in a fresh blockhash and skips signature checks, so the
+    // transaction can be built offline, from a throwaway keypair if none is given
+    let transaction = match &ctx.settings.keypair {
+        Some(keypair) => self_transfer(keypair, 1, Hash::default()),
+        None => self_transfer(&Keypair::new(), 1, Hash::default()),
+    };
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        ..RpcSimulateTransactionConfig::default()
+    };
+
+    let (duration, result) = ctx.timed(ctx.client.simulate_transaction_with_config(&transaction, config)).await;
+
+    // A simulation that fails on-chain (e.g. the unfunded payer) was still served
+    Ok(match result {
+        Ok(response) => TestResult::success("simulateTransaction", duration)
+            .with_compute_units(response.value.units_consumed.filter(|units| *units > 0)),
+        Err(e) => TestResult::failure("simulateTransaction", duration, e),
+    })
+}
+
+/// How long to wait for a sent transaction to be confirmed
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+const CONFIRM_POLL: Duration = Duration::from_millis(200);
+
+/// Poll the signature status until it reaches `confirmed`, None on timeout
+async fn wait_for_confirmation(ctx: &TestContext, signature: &Signature) -> Option<Duration> {
+    let start = Instant::now();
+    while start.elapsed() < CONFIRM_TIMEOUT {
+        let confirmed = ctx.client.confirm_transaction_with_commitment(signature, CommitmentConfig::confirmed()).await;
+        if confirmed.is_ok_and(|response| response.value) {
+            return Some(start.elapsed());
+        }
+        tokio::time::sleep(CONFIRM_POLL).await;
+    }
+    None
+}
+
+async fn test_send_transaction(ctx: &TestContext) -> Result<TestResult> {
+    let Some(keypair) = &ctx.settings.keypair else {
+        return Ok(TestResult::failure("sendTransaction", Duration::ZERO, "No keypair configured"));
+    };
+
+    let blockhash = match ctx.client.get_latest_blockhash().await {
+        Ok(blockhash) => blockhash,
+        Err(e) => {
+            return Ok(TestResult::failure("sendTransaction", Duration::ZERO, format!("Failed to get blockhash: {}", e)));
+        }
+    };
+    // A random amount keeps iterations that share a blockhash from being duplicates
+    let transaction = self_transfer(keypair, rand::random::<u16>() as u64 + 1, blockhash);
+
+    // Only the submission is timed, confirmation depends on the cluster rather than the endpoint
+    let (duration, result) = ctx.timed(ctx.client.send_transaction(&transaction)).await;
+
+    Ok(match result {
+        Ok(signature) => match wait_for_confirmation(ctx, &signature).await {
+            Some(confirm) => TestResult::success("sendTransaction", duration).with_confirmation(Some(confirm)),
+            None => TestResult::failure(
+                "sendTransaction",
+                duration,
+                format!("Sent {} but not confirmed within {}s", signature, CONFIRM_TIMEOUT.as_secs()),
+            ),
+        },
+        Err(e) => TestResult::failure("sendTransaction", duration, e),
+    })
+}
+
+async fn test_get_health(ctx: &TestContext) -> Result<TestResult> {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "getHealth",
+    });
+    let (duration, response) = ctx.timed(ctx.transport.post_json(&request)).await;
+
+    match response {
+        Ok(resp) => {
+            let json: Value = serde_json::from_str(&resp.body)?;
+            if json["result"] == "ok" {
+                Ok(TestResult::success("getHealth", duration))
+            } else {
+                Ok(TestResult::failure("getHealth", duration, format!("Unexpected response: {:?}", json)))
+            }
+        },
+        Err(e) => Ok(TestResult::failure("getHealth", duration, e)),
+    }
+}
+
+/// Asset the DAS tests look up, the USDC mint (DAS indexes fungible tokens too)
+const SAMPLE_ASSET: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+/// JSON-RPC code for a method the endpoint doesn't implement
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Call a method the stock client doesn't know, e.g. DAS with its named params
+async fn raw_call(ctx: &TestContext, method: &str, params: Option<&Value>) -> (Duration, Result<Value, String>) {
+    let mut request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+    });
+    if let Some(params) = params {
+        request["params"] = params.clone();
+    }
+
+    let (duration, response) = ctx.timed(ctx.transport.post_json(&request)).await;
+
+    let result = match response {
+        Ok(resp) if !resp.status.is_success() => Err(format!("HTTP {}: {}", resp.status, resp.body)),
+        Ok(resp) => match serde_json::from_str::<Value>(&resp.body) {
+            Ok(json) if json["error"].is_object() => Err(format!(
+                "RPC response error {}: {}",
+                json["error"]["code"],
+                json["error"]["message"].as_str().unwrap_or_default()
+            )),
+            Ok(mut json) => Ok(json["result"].take()),
+            Err(e) => Err(e.to_string()),
+        },
+        Err(e) => Err(e.to_string()),
+    };
+    (duration, result)
+}
+
+/// Whether the endpoint implements the DAS API at all, checked once before the run
+pub async fn supports_das(ctx: &TestContext) -> bool {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "getAsset",
+        "params": { "id": SAMPLE_ASSET },
+    });
+
+    // Only a JSON-RPC answer says anything about DAS, a failed request or HTTP error doesn't
+    match ctx.transport.post_json(&request).await {
+        Ok(resp) if resp.status.is_success() => match serde_json::from_str::<Value>(&resp.body) {
+            Ok(json) if json["error"].is_object() => json["error"]["code"].as_i64() != Some(METHOD_NOT_FOUND),
+            Ok(json) => !json["result"].is_null(),
+            Err(_) => false,
+        },
+        _ => false,
+    }
+}
+
+async fn test_get_asset(ctx: &TestContext) -> Result<TestResult> {
+    let (duration, result) = raw_call(ctx, "getAsset", Some(&json!({ "id": SAMPLE_ASSET }))).await;
+    Ok(TestResult::from_result("getAsset", duration, result))
+}
+
+async fn test_get_assets_by_owner(ctx: &TestContext) -> Result<TestResult> {
+    let params = json!({ "ownerAddress": SAMPLE_WALLET, "page": 1, "limit": 10 });
+    let (duration, result) = raw_call(ctx, "getAssetsByOwner", Some(&params)).await;
+    Ok(TestResult::from_result("getAssetsByOwner", duration, result))
+}
+
+async fn test_search_assets(ctx: &TestContext) -> Result<TestResult> {
+    let params = json!({ "ownerAddress": SAMPLE_WALLET, "tokenType": "fungible", "page": 1, "limit": 10 });
+    let (duration, result) = raw_call(ctx, "searchAssets", Some(&params)).await;
+    Ok(TestResult::from_result("searchAssets", duration, result))
+}
+
+/// Tests that deliberately fetch responses of several MB, to measure transfer rates
+pub const BANDWIDTH_TESTS: [&str; 2] = ["getBlock (full, jsonParsed)", "getProgramAccounts (vote accounts)"];
+
+const VOTE_PROGRAM_ID: &str = "Vote111111111111111111111111111111111111111";
+
+async fn test_get_block_full(ctx: &TestContext) -> Result<TestResult> {
+    let name = BANDWIDTH_TESTS[0];
+    let slot = match ctx.client.get_slot().await {
+        Ok(slot) => slot.saturating_sub(10),
+        Err(e) => return Ok(TestResult::failure(name, Duration::ZERO, format!("Failed to get slot: {}", e))),
+    };
+
+    // Every transaction parsed, with rewards: about the largest response a single block makes
+    let params = json!([slot, {
+        "encoding": "jsonParsed",
+        "transactionDetails": "full",
+        "rewards": true,
+        "maxSupportedTransactionVersion": 0,
+    }]);
+    let (duration, result) = raw_call(ctx, "getBlock", Some(&params)).await;
+    Ok(TestResult::from_result(name, duration, result))
+}
+
+async fn test_get_program_accounts_large(ctx: &TestContext) -> Result<TestResult> {
+    // Every vote account with its full data, thousands of accounts of a few KB each on mainnet
+    let params = json!([VOTE_PROGRAM_ID, { "encoding": "base64" }]);
+    let (duration, result) = raw_call(ctx, "getProgramAccounts", Some(&params)).await;
+    Ok(TestResult::from_result(BANDWIDTH_TESTS[1], duration, result))
+}
+
+async fn test_custom_method(ctx: &TestContext) -> Result<TestResult> {
+    let Some(custom) = &ctx.settings.custom else {
+        return Ok(TestResult::failure("custom", Duration::ZERO, "No custom method configured"));
+    };
+
+    let (duration, result) = raw_call(ctx, &custom.method, custom.params.as_ref()).await;
+    Ok(TestResult::from_result(&custom.method, duration, result))
+}
+
+/// All tests in the order they are run, leaving out those `settings` lack inputs for
+pub fn all_tests(settings: &TestSettings) -> Vec<(Cow<'static, str>, TestFn)> {
+    let mut tests: Vec<(&'static str, TestFn)> = vec![
+        ("getLatestBlockhash", |ctx| Box::pin(test_get_latest_blockhash(ctx))),
+        ("getSlot", |ctx| Box::pin(test_get_slot(ctx))),
+        ("getBalance", |ctx| Box::pin(test_get_balance(ctx))),
+        ("getBalance (batch of 10)", |ctx| Box::pin(test_batch_get_balance(ctx))),
+        ("getAccountInfo", |ctx| Box::pin(test_get_account_info(ctx))),
+        ("getBlock", |ctx| Box::pin(test_get_block(ctx))),
+        ("getTokenAccountsByOwner", |ctx| Box::pin(test_get_token_accounts_by_owner(ctx))),
+        ("getTokenAccountBalance", |ctx| Box::pin(test_get_token_account_balance(ctx))),
+        ("getProgramAccounts", |ctx| Box::pin(test_get_program_accounts(ctx))),
+        ("getRecentPerformanceSamples", |ctx| Box::pin(test_get_recent_performance_samples(ctx))),
+        ("getSupply", |ctx| Box::pin(test_get_supply(ctx))),
+        ("getSupply (excl. accounts)", |ctx| Box::pin(test_get_supply_excluding_accounts(ctx))),
+        ("getInflationRate", |ctx| Box::pin(test_get_inflation_rate(ctx))),
+        ("getInflationReward", |ctx| Box::pin(test_get_inflation_reward(ctx))),
+        ("getStakeMinimumDelegation", |ctx| Box::pin(test_get_stake_minimum_delegation(ctx))),
+        ("getFirstAvailableBlock", |ctx| Box::pin(test_get_first_available_block(ctx))),
+        ("minimumLedgerSlot", |ctx| Box::pin(test_minimum_ledger_slot(ctx))),
+        ("getHighestSnapshotSlot", |ctx| Box::pin(test_get_highest_snapshot_slot(ctx))),
+        ("getRecentPrioritizationFees", |ctx| Box::pin(test_get_recent_prioritization_fees(ctx))),
+        ("getGenesisHash", |ctx| Box::pin(test_get_genesis_hash(ctx))),
+        ("getEpochSchedule", |ctx| Box::pin(test_get_epoch_schedule(ctx))),
+        ("getIdentity", |ctx| Box::pin(test_get_identity(ctx))),
+        ("simulateTransaction", |ctx| Box::pin(test_simulate_transaction(ctx))),
+        ("getHealth", |ctx| Box::pin(test_get_health(ctx))),
+    ];
+
+    if settings.stake_account.is_some() {
+        tests.insert(tests.len() - 1, ("getStakeActivation", |ctx| Box::pin(test_get_stake_activation(ctx))));
+    }
+    if settings.send && settings.keypair.is_some() {
+        tests.insert(tests.len() - 1, ("sendTransaction", |ctx| Box::pin(test_send_transaction(ctx))));
+    }
+    if settings.das {
+        let das: [(&'static str, TestFn); 3] = [
+            ("getAsset", |ctx| Box::pin(test_get_asset(ctx))),
+            ("getAssetsByOwner", |ctx| Box::pin(test_get_assets_by_owner(ctx))),
+            ("searchAssets", |ctx| Box::pin(test_search_assets(ctx))),
+        ];
+        tests.splice(tests.len() - 1..tests.len() - 1, das);
+    }
+    if settings.bandwidth {
+        let bandwidth: [(&'static str, TestFn); 2] = [
+            (BANDWIDTH_TESTS[0], |ctx| Box::pin(test_get_block_full(ctx))),
+            (BANDWIDTH_TESTS[1], |ctx| Box::pin(test_get_program_accounts_large(ctx))),
+        ];
+        tests.splice(tests.len() - 1..tests.len() - 1, bandwidth);
+    }
+
+    // Only the custom method's name isn't known at compile time
+    let mut tests: Vec<_> = tests.into_iter().map(|(name, test_fn)| (Cow::Borrowed(name), test_fn)).collect();
+    if let Some(custom) = &settings.custom {
+        tests.push((Cow::Owned(custom.method.clone()), |ctx| Box::pin(test_custom_method(ctx))));
+    }
+
+    tests
+}
@@ -0,0 +1,206 @@
+use crate::endpoint::Endpoint;
+use crate::har::HarRecorder;
+use crate::rate_limit::RateLimitTracker;
+use crate::rpc_tests::{TestContext, TestFn, TestResult, TestSettings};
+use clap::ValueEnum;
+use futures::future::join_all;
+use indicatif::ProgressBar;
+use serde_json::json;
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// How completed iterations are streamed while the run is in progress
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamFormat {
+    /// One JSON object per line
+    Jsonl,
+}
+
+/// Settings shared by every test in a run
+#[derive(Clone)]
+pub struct RunOptions {
+    pub iterations: usize,
+    /// Run each test repeatedly for this long instead of a fixed number of iterations
+    pub duration: Option<Duration>,
+    /// Unrecorded iterations run first, to establish connections and warm caches
+    pub warmup: usize,
+    /// Print per-iteration status lines when the progress bar is disabled
+    pub echo: bool,
+    /// Point in time after which no further iterations are started
+    pub deadline: Option<tokio::time::Instant>,
+    /// Print every iteration to stdout as soon as it completes
+    pub stream: Option<StreamFormat>,
+    pub endpoint: Endpoint,
+    pub har: Option<HarRecorder>,
+    pub rate_limits: RateLimitTracker,
+    pub settings: Arc<TestSettings>,
+}
+
+/// Keep a finished iteration and stream it out if requested
+pub fn record(results: &mut Vec<TestResult>, result: TestResult, options: &RunOptions) {
+    if let Some(StreamFormat::Jsonl) = options.stream {
+        let line = json!({
+            "endpoint": options.endpoint.url,
+            "test": result.name,
+            "iteration": results.len() + 1,
+            "success": result.success,
+            "skipped": result.skipped,
+            "duration_ms": result.duration_ms,
+            "bytes": result.bytes,
+            "ttfb_ms": result.ttfb_ms,
+            "download_ms": result.download_ms,
+            "error": result.error,
+            "timestamp": result.timestamp,
+        });
+        println!("{}", line);
+    }
+    results.push(result);
+}
+
+async fn run_test(
+    test_fn: TestFn,
+    ctx: &TestContext,
+    test_name: &str,
+    options: &RunOptions,
+    progress_bar: &ProgressBar,
+) -> Vec<TestResult> {
+    let mut results = Vec::new();
+    let iterations = options.iterations;
+
+    for i in 0..options.warmup {
+        if options.deadline.is_some_and(|deadline| tokio::time::Instant::now() >= deadline) {
+            break;
+        }
+
+        progress_bar.set_message(format!("Warming up {} {}/{}", test_name, i + 1, options.warmup));
+        if options.echo {
+            print!("Warming up {} {}/{}...\r", test_name, i + 1, options.warmup);
+        }
+
+        // Outcomes are discarded, only the side effects on connections and caches matter
+        match options.deadline {
+            Some(deadline) => {
+                let _ = tokio::time::timeout_at(deadline, test_fn(ctx)).await;
+            }
+            None => {
+                let _ = test_fn(ctx).await;
+            }
+        }
+    }
+
+    // The time budget starts after warm-up, so every test gets the same measured window
+    let budget_end = options.duration.map(|duration| tokio::time::Instant::now() + duration);
+    let mut i = 0;
+    while match budget_end {
+        Some(end) => tokio::time::Instant::now() < end,
+        None => i < iterations,
+    } {
+        if options.deadline.is_some_and(|deadline| tokio::time::Instant::now() >= deadline) {
+            // Out of time: record what is left as skipped so the report shows it. A
+            // duration-based run has no fixed count, so nothing is left over
+            if budget_end.is_none() {
+                for _ in i..iterations {
+                    record(&mut results, TestResult::skipped(test_name), options);
+                }
+                progress_bar.inc((iterations - i) as u64);
+            }
+            break;
+        }
+
+        let status = match budget_end {
+            Some(end) => format!(
+                "Running {} test {} ({}s left)",
+                test_name,
+                i + 1,
+                end.saturating_duration_since(tokio::time::Instant::now()).as_secs()
+            ),
+            None => format!("Running {} test {}/{}", test_name, i + 1, iterations),
+        };
+        progress_bar.set_message(status.clone());
+        if options.echo {
+            print!("{}...\r", status);
+        }
+
+        // Drop counters a previous test may have left behind
+        ctx.take_measured();
+        let outcome = match options.deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline, test_fn(ctx)).await.ok(),
+            None => Some(test_fn(ctx).await),
+        };
+
+        let mut result = match outcome {
+            Some(Ok(result)) => result,
+            Some(Err(e)) => TestResult::failure(test_name, Duration::ZERO, e),
+            // The deadline hit while the request was still in flight
+            None => TestResult::skipped(test_name),
+        };
+        let used = ctx.take_measured();
+        result.bytes = used.bytes;
+        result.ttfb_ms = (used.wait_micros / 1000) as u128;
+        result.download_ms = (used.download_micros / 1000) as u128;
+        record(&mut results, result, options);
+
+        // Add a small delay between tests
+        match options.deadline {
+            Some(deadline) => {
+                let _ = tokio::time::timeout_at(deadline, sleep(Duration::from_millis(100))).await;
+            }
+            None => sleep(Duration::from_millis(100)).await,
+        }
+        progress_bar.inc(1);
+        i += 1;
+    }
+
+    results
+}
+
+pub async fn run_suite(
+    tests: Vec<(Cow<'static, str>, TestFn)>,
+    parallel: bool,
+    options: &RunOptions,
+    progress_bar: &ProgressBar,
+) -> Vec<TestResult> {
+    let mut all_results = Vec::new();
+
+    if parallel {
+        // Run tests in parallel, each with its own client
+        let mut futures = Vec::new();
+
+        for (test_name, test_fn) in tests {
+            let ctx = Arc::new(TestContext::new(
+                &options.endpoint,
+                options.har.clone(),
+                options.rate_limits.clone(),
+                options.settings.clone(),
+            ));
+            let options = options.clone();
+            let pb = progress_bar.clone();
+
+            futures.push(tokio::spawn(async move {
+                run_test(test_fn, &ctx, &test_name, &options, &pb).await
+            }));
+        }
+
+        // Wait for all tests to complete
+        let results = join_all(futures).await;
+
+        // Collect results
+        for result in results {
+            match result {
+                Ok(test_results) => all_results.extend(test_results),
+                Err(e) => eprintln!("Error running test: {}", e),
+            }
+        }
+    } else {
+        // Run tests sequentially
+        let ctx = TestContext::new(&options.endpoint, options.har.clone(), options.rate_limits.clone(), options.settings.clone());
+        for (test_name, test_fn) in tests {
+            let results = run_test(test_fn, &ctx, &test_name, options, progress_bar).await;
+            all_results.extend(results);
+        }
+    }
+
+    all_results
+}
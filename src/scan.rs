@@ -0,0 +1,164 @@
+use crate::style::{banner, icon, rule};
+use anyhow::{Context, Result};
+use colored::*;
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+/// What a single RPC node advertised in gossip and how it answered
+#[derive(Serialize, Clone, Debug)]
+pub struct ScannedNode {
+    pub pubkey: String,
+    pub rpc: String,
+    /// Version from gossip, which unreachable nodes still have
+    pub gossip_version: Option<String>,
+    pub reachable: bool,
+    /// Round trip of getSlot
+    pub latency_ms: Option<u128>,
+    pub slot: Option<u64>,
+    /// Behind the highest slot any scanned node reported
+    pub slots_behind: Option<u64>,
+    pub healthy: Option<bool>,
+    pub error: Option<String>,
+}
+
+/// Publicly reachable RPC nodes of the cluster the seed endpoint belongs to
+#[derive(Serialize, Debug)]
+pub struct Survey {
+    pub seed: String,
+    /// Every node in the gossip table
+    pub gossip_nodes: usize,
+    /// Nodes advertising an RPC address
+    pub advertised: usize,
+    pub reachable: usize,
+    pub healthy: usize,
+    pub nodes: Vec<ScannedNode>,
+}
+
+/// Limits of a scan
+pub struct ScanConfig {
+    pub concurrency: usize,
+    pub timeout: Duration,
+    /// Probe at most this many of the advertised nodes
+    pub limit: Option<usize>,
+}
+
+/// getSlot, getHealth and a round-trip time from one node, all under the timeout
+async fn probe(pubkey: String, rpc: String, gossip_version: Option<String>, timeout: Duration) -> ScannedNode {
+    let client = RpcClient::new_with_timeout_and_commitment(format!("http://{}", rpc), timeout, CommitmentConfig::processed());
+
+    let start = Instant::now();
+    let slot = tokio::time::timeout(timeout, client.get_slot()).await;
+    let latency = start.elapsed();
+
+    let (slot, error) = match slot {
+        Ok(Ok(slot)) => (Some(slot), None),
+        Ok(Err(e)) => (None, Some(e.to_string())),
+        Err(_) => (None, Some(format!("no answer within {}ms", timeout.as_millis()))),
+    };
+    let healthy = match slot {
+        Some(_) => tokio::time::timeout(timeout, client.get_health()).await.ok().map(|health| health.is_ok()),
+        None => None,
+    };
+
+    ScannedNode {
+        pubkey,
+        rpc,
+        gossip_version,
+        reachable: slot.is_some(),
+        latency_ms: slot.map(|_| latency.as_millis()),
+        slot,
+        slots_behind: None,
+        healthy,
+        error,
+    }
+}
+
+/// Pull the gossip table from the seed and probe every node that advertises RPC
+pub async fn scan(seed: &str, config: &ScanConfig) -> Result<Survey> {
+    let nodes = RpcClient::new(seed.to_string())
+        .get_cluster_nodes()
+        .await
+        .with_context(|| format!("getClusterNodes failed on {}", seed))?;
+    let gossip_nodes = nodes.len();
+
+    let mut advertised: Vec<_> = nodes.into_iter().filter_map(|n| n.rpc.map(|rpc| (n.pubkey, rpc, n.version))).collect();
+    let total = advertised.len();
+    if let Some(limit) = config.limit {
+        advertised.truncate(limit);
+    }
+
+    let mut scanned: Vec<ScannedNode> = stream::iter(advertised)
+        .map(|(pubkey, rpc, version)| probe(pubkey, rpc.to_string(), version, config.timeout))
+        .buffer_unordered(config.concurrency.max(1))
+        .collect()
+        .await;
+
+    // Lag is relative to the tip the scan saw, not to the seed, which may itself be behind
+    if let Some(tip) = scanned.iter().filter_map(|n| n.slot).max() {
+        for node in &mut scanned {
+            node.slots_behind = node.slot.map(|slot| tip.saturating_sub(slot));
+        }
+    }
+    scanned.sort_by_key(|n| (!n.reachable, n.latency_ms.unwrap_or(u128::MAX)));
+
+    Ok(Survey {
+        seed: seed.to_string(),
+        gossip_nodes,
+        advertised: total,
+        reachable: scanned.iter().filter(|n| n.reachable).count(),
+        healthy: scanned.iter().filter(|n| n.healthy == Some(true)).count(),
+        nodes: scanned,
+    })
+}
+
+impl Survey {
+    /// Summary, version spread and a table of reachable nodes, fastest first
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out).expect("writing to a String cannot fail");
+        out
+    }
+
+    fn write(&self, out: &mut String) -> std::fmt::Result {
+        writeln!(out, "\n{}", banner("RPC NODE SCAN"))?;
+        writeln!(out, "{}", format!("{}Seed: {}", icon("🌱"), self.seed).dimmed())?;
+        writeln!(out, "{}Gossip nodes: {} | advertising RPC: {} | probed: {}",
+            icon("🛰️ "), self.gossip_nodes, self.advertised, self.nodes.len())?;
+        writeln!(out, "{}", format!("{}Reachable: {} | healthy: {}", icon("✅"), self.reachable, self.healthy).green())?;
+
+        let mut versions: BTreeMap<&str, usize> = BTreeMap::new();
+        for node in self.nodes.iter().filter(|n| n.reachable) {
+            *versions.entry(node.gossip_version.as_deref().unwrap_or("unknown")).or_default() += 1;
+        }
+        if !versions.is_empty() {
+            let spread: Vec<String> = versions.iter().rev().map(|(version, count)| format!("{} × {}", version, count)).collect();
+            writeln!(out, "{}Versions: {}", icon("🧬"), spread.join(", "))?;
+        }
+        writeln!(out)?;
+
+        writeln!(out, "{}", format!("{:<44} {:<22} {:<12} {:>8} {:>7} {:>8}",
+            "Identity", "RPC", "Version", "Latency", "Behind", "Health").bold())?;
+        writeln!(out, "{}", rule(false).dimmed())?;
+        for node in self.nodes.iter().filter(|n| n.reachable) {
+            // Padded before coloring, escape codes would count towards the width
+            let health = match node.healthy {
+                Some(true) => format!("{:>8}", "ok").green(),
+                Some(false) => format!("{:>8}", "behind").yellow(),
+                None => format!("{:>8}", "?").dimmed(),
+            };
+            writeln!(out, "{:<44} {:<22} {:<12} {:>6}ms {:>7} {}",
+                node.pubkey,
+                node.rpc,
+                node.gossip_version.as_deref().unwrap_or("?"),
+                node.latency_ms.unwrap_or_default(),
+                node.slots_behind.map(|s| s.to_string()).unwrap_or_default(),
+                health)?;
+        }
+        Ok(())
+    }
+}
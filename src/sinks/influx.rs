@@ -0,0 +1,76 @@
+use crate::report::Report;
+use anyhow::{bail, Context, Result};
+
+/// Where to push measurements on an InfluxDB 2.x server
+pub struct InfluxConfig {
+    pub url: String,
+    pub bucket: String,
+    pub org: Option<String>,
+    pub token: Option<String>,
+}
+
+/// Escape commas, equals signs and spaces in tag keys and values
+fn tag(value: &str) -> String {
+    value.replace(',', "\\,").replace('=', "\\=").replace(' ', "\\ ")
+}
+
+/// Escape double quotes and backslashes in string field values
+fn string_field(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// One `rpc_latency` point per iteration, timestamped in milliseconds
+pub fn render(report: &Report) -> String {
+    let endpoint = tag(&report.endpoint.url);
+    let mut out = String::new();
+
+    for test in &report.tests {
+        for sample in &test.samples {
+            let mut fields = format!(
+                "iteration={}i,success={},skipped={},duration_ms={}i",
+                sample.iteration, sample.success, sample.skipped, sample.duration_ms
+            );
+            if let Some(error) = &sample.error {
+                fields.push_str(&format!(",error={}", string_field(error)));
+            }
+
+            out.push_str(&format!(
+                "rpc_latency,endpoint={},method={},mode={} {} {}\n",
+                endpoint,
+                tag(&test.name),
+                report.config.mode,
+                fields,
+                sample.timestamp.timestamp_millis()
+            ));
+        }
+    }
+
+    out
+}
+
+/// Write every sample of the report through the v2 write API
+pub async fn push(config: &InfluxConfig, report: &Report) -> Result<()> {
+    let url = format!("{}/api/v2/write", config.url.trim_end_matches('/'));
+    let mut query = vec![("bucket", config.bucket.as_str()), ("precision", "ms")];
+    if let Some(org) = &config.org {
+        query.push(("org", org));
+    }
+
+    let mut request = reqwest::Client::new().post(&url).query(&query).body(render(report));
+    if let Some(token) = &config.token {
+        request = request.header("Authorization", format!("Token {}", token));
+    }
+
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach InfluxDB at {}", config.url))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        bail!("InfluxDB write failed with {}: {}", status, body.trim());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,10 @@
+//! Destinations that finished reports are pushed to, next to the rendered output
+
+pub mod influx;
+pub mod otlp;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod statsd;
+pub mod zabbix;
@@ -0,0 +1,115 @@
+use crate::report::Report;
+use anyhow::{bail, Context, Result};
+use chrono::Duration;
+use serde_json::{json, Value};
+
+/// OTLP span kinds and status codes, as numbers per the JSON encoding
+const SPAN_KIND_INTERNAL: u8 = 1;
+const SPAN_KIND_CLIENT: u8 = 3;
+const STATUS_OK: u8 = 1;
+const STATUS_ERROR: u8 = 2;
+
+fn random_id(bytes: usize) -> String {
+    (0..bytes).map(|_| format!("{:02x}", rand::random::<u8>())).collect()
+}
+
+fn string_attr(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+fn int_attr(key: &str, value: i64) -> Value {
+    // 64-bit integers are strings in the OTLP JSON encoding
+    json!({ "key": key, "value": { "intValue": value.to_string() } })
+}
+
+fn nanos(time: chrono::DateTime<chrono::Utc>) -> String {
+    time.timestamp_nanos_opt().unwrap_or_default().to_string()
+}
+
+/// A single trace for the run, with one client span per executed iteration
+pub fn render(report: &Report) -> Value {
+    let trace_id = random_id(16);
+    let root_id = random_id(8);
+    let mut spans = Vec::new();
+
+    let samples = report.tests.iter().flat_map(|t| t.samples.iter().map(move |s| (t, s)));
+    let mut run_start = report.timestamp;
+
+    for (test, sample) in samples.filter(|(_, s)| !s.skipped) {
+        let end = sample.timestamp;
+        let start = end - Duration::milliseconds(sample.duration_ms as i64);
+        run_start = run_start.min(start);
+
+        let status = match &sample.error {
+            Some(error) => json!({ "code": STATUS_ERROR, "message": error }),
+            None => json!({ "code": STATUS_OK }),
+        };
+
+        spans.push(json!({
+            "traceId": trace_id,
+            "spanId": random_id(8),
+            "parentSpanId": root_id,
+            "name": test.name,
+            "kind": SPAN_KIND_CLIENT,
+            "startTimeUnixNano": nanos(start),
+            "endTimeUnixNano": nanos(end),
+            "attributes": [
+                string_attr("rpc.system", "jsonrpc"),
+                string_attr("rpc.method", &test.name),
+                string_attr("url.full", &report.endpoint.url),
+                int_attr("rpc.checker.iteration", sample.iteration as i64),
+            ],
+            "status": status,
+        }));
+    }
+
+    spans.push(json!({
+        "traceId": trace_id,
+        "spanId": root_id,
+        "name": "rpc-checker run",
+        "kind": SPAN_KIND_INTERNAL,
+        "startTimeUnixNano": nanos(run_start),
+        "endTimeUnixNano": nanos(report.timestamp),
+        "attributes": [
+            string_attr("url.full", &report.endpoint.url),
+            string_attr("rpc.checker.mode", report.config.mode),
+            int_attr("rpc.checker.iterations", report.config.iterations as i64),
+        ],
+        "status": { "code": if report.overall.failed > 0 { STATUS_ERROR } else { STATUS_OK } },
+    }));
+
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    string_attr("service.name", "solana-rpc-checker"),
+                    string_attr("service.version", report.tool_version),
+                ],
+            },
+            "scopeSpans": [{
+                "scope": { "name": "solana-rpc-checker", "version": report.tool_version },
+                "spans": spans,
+            }],
+        }],
+    })
+}
+
+/// Send the run's spans to an OTLP/HTTP collector (e.g. http://localhost:4318)
+pub async fn export(endpoint: &str, report: &Report) -> Result<()> {
+    let url = format!("{}/v1/traces", endpoint.trim_end_matches('/'));
+
+    let response = reqwest::Client::new()
+        .post(&url)
+        .json(&render(report))
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach OTLP collector at {}", endpoint))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        bail!("OTLP trace export failed with {}: {}", status, body.trim());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use crate::report::Report;
+use anyhow::{Context, Result};
+use tokio_postgres::NoTls;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS rpc_checker_runs (
+    id            BIGSERIAL PRIMARY KEY,
+    timestamp     TIMESTAMPTZ NOT NULL,
+    host          TEXT NOT NULL,
+    endpoint      TEXT NOT NULL,
+    mode          TEXT NOT NULL,
+    iterations    INTEGER NOT NULL,
+    tool_version  TEXT NOT NULL,
+    git_commit    TEXT,
+    success_rate  DOUBLE PRECISION NOT NULL,
+    avg_ms        BIGINT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS rpc_checker_samples (
+    run_id        BIGINT NOT NULL REFERENCES rpc_checker_runs(id) ON DELETE CASCADE,
+    test          TEXT NOT NULL,
+    iteration     INTEGER NOT NULL,
+    success       BOOLEAN NOT NULL,
+    skipped       BOOLEAN NOT NULL,
+    duration_ms   BIGINT NOT NULL,
+    error         TEXT,
+    timestamp     TIMESTAMPTZ NOT NULL
+);
+CREATE INDEX IF NOT EXISTS rpc_checker_samples_run_id ON rpc_checker_samples(run_id);
+CREATE INDEX IF NOT EXISTS rpc_checker_runs_endpoint_timestamp ON rpc_checker_runs(endpoint, timestamp);
+";
+
+/// Insert the run and all of its samples, returning the new run id
+pub async fn append(dsn: &str, report: &Report) -> Result<i64> {
+    let (mut client, connection) = tokio_postgres::connect(dsn, NoTls)
+        .await
+        .context("Failed to connect to PostgreSQL")?;
+
+    // The connection object drives the socket and has to be polled on its own task
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("PostgreSQL connection error: {}", e);
+        }
+    });
+
+    client.batch_execute(SCHEMA).await.context("Failed to create result tables")?;
+
+    let host = gethostname::gethostname().to_string_lossy().into_owned();
+    let tx = client.transaction().await?;
+    let row = tx
+        .query_one(
+            "INSERT INTO rpc_checker_runs
+                 (timestamp, host, endpoint, mode, iterations, tool_version, git_commit, success_rate, avg_ms)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             RETURNING id",
+            &[
+                &report.timestamp,
+                &host,
+                &report.endpoint.url,
+                &report.config.mode,
+                &(report.config.iterations as i32),
+                &report.tool_version,
+                &report.git_commit,
+                &report.overall.success_rate,
+                &(report.overall.avg_ms as i64),
+            ],
+        )
+        .await?;
+    let run_id: i64 = row.get(0);
+
+    let insert = tx
+        .prepare(
+            "INSERT INTO rpc_checker_samples
+                 (run_id, test, iteration, success, skipped, duration_ms, error, timestamp)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .await?;
+    for test in &report.tests {
+        for sample in &test.samples {
+            tx.execute(
+                &insert,
+                &[
+                    &run_id,
+                    &test.name,
+                    &(sample.iteration as i32),
+                    &sample.success,
+                    &sample.skipped,
+                    &(sample.duration_ms as i64),
+                    &sample.error,
+                    &sample.timestamp,
+                ],
+            )
+            .await?;
+        }
+    }
+
+    tx.commit().await?;
+    Ok(run_id)
+}
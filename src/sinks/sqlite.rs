@@ -0,0 +1,159 @@
+use crate::report::histogram::LatencyHistogram;
+use crate::report::Report;
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OpenFlags};
+use std::path::Path;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp     TEXT NOT NULL,
+    endpoint      TEXT NOT NULL,
+    mode          TEXT NOT NULL,
+    iterations    INTEGER NOT NULL,
+    tool_version  TEXT NOT NULL,
+    git_commit    TEXT,
+    success_rate  REAL NOT NULL,
+    avg_ms        INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS samples (
+    run_id        INTEGER NOT NULL REFERENCES runs(id),
+    test          TEXT NOT NULL,
+    iteration     INTEGER NOT NULL,
+    success       INTEGER NOT NULL,
+    skipped       INTEGER NOT NULL,
+    duration_ms   INTEGER NOT NULL,
+    error         TEXT,
+    timestamp     TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS samples_run_id ON samples(run_id);
+CREATE INDEX IF NOT EXISTS runs_endpoint_timestamp ON runs(endpoint, timestamp);
+";
+
+/// Append the run and all of its samples, returning the new run id
+pub fn append(path: &Path, report: &Report) -> Result<i64> {
+    let mut conn = Connection::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    conn.execute_batch(SCHEMA).context("Failed to create history tables")?;
+
+    // One transaction per run, so a crash never leaves a run without its samples
+    let tx = conn.transaction()?;
+    tx.execute(
+        "INSERT INTO runs (timestamp, endpoint, mode, iterations, tool_version, git_commit, success_rate, avg_ms)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            report.timestamp.to_rfc3339(),
+            report.endpoint.url,
+            report.config.mode,
+            report.config.iterations as i64,
+            report.tool_version,
+            report.git_commit,
+            report.overall.success_rate,
+            report.overall.avg_ms as i64,
+        ],
+    )?;
+    let run_id = tx.last_insert_rowid();
+
+    {
+        let mut insert = tx.prepare(
+            "INSERT INTO samples (run_id, test, iteration, success, skipped, duration_ms, error, timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+        for test in &report.tests {
+            for sample in &test.samples {
+                insert.execute(params![
+                    run_id,
+                    test.name,
+                    sample.iteration as i64,
+                    sample.success,
+                    sample.skipped,
+                    sample.duration_ms as i64,
+                    sample.error,
+                    sample.timestamp.to_rfc3339(),
+                ])?;
+            }
+        }
+    }
+
+    tx.commit()?;
+    Ok(run_id)
+}
+
+/// One method of a stored run
+pub struct HistoryTest {
+    pub name: String,
+    pub p50_ms: Option<u128>,
+    pub success_rate: f64,
+}
+
+/// A stored run with per-method stats recomputed from its samples
+pub struct HistoryRun {
+    pub timestamp: String,
+    pub endpoint: String,
+    pub tests: Vec<HistoryTest>,
+}
+
+/// The last `runs` runs of every endpoint (or only of `endpoints`), oldest first per endpoint
+pub fn history(path: &Path, endpoints: &[String], runs: usize) -> Result<Vec<HistoryRun>> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+
+    let endpoints = if endpoints.is_empty() {
+        let mut query = conn.prepare("SELECT DISTINCT endpoint FROM runs ORDER BY endpoint")?;
+        let rows = query.query_map([], |row| row.get(0))?;
+        rows.collect::<rusqlite::Result<Vec<String>>>()?
+    } else {
+        endpoints.to_vec()
+    };
+
+    let mut latest = conn.prepare(
+        "SELECT id, timestamp FROM runs WHERE endpoint = ?1 ORDER BY timestamp DESC, id DESC LIMIT ?2",
+    )?;
+    let mut samples = conn.prepare(
+        "SELECT test, success, duration_ms FROM samples WHERE run_id = ?1 AND skipped = 0 ORDER BY rowid",
+    )?;
+
+    let mut history = Vec::new();
+    for endpoint in endpoints {
+        let mut found = latest
+            .query_map(params![endpoint, runs as i64], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        found.reverse();
+
+        for (id, timestamp) in found {
+            // Per test: successful latencies and how many iterations ran
+            let mut tests: Vec<(String, LatencyHistogram, usize)> = Vec::new();
+            let rows = samples.query_map(params![id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?, row.get::<_, i64>(2)?))
+            })?;
+            for row in rows {
+                let (test, success, duration_ms) = row?;
+                let index = match tests.iter().position(|(name, _, _)| *name == test) {
+                    Some(index) => index,
+                    None => {
+                        tests.push((test, LatencyHistogram::new(), 0));
+                        tests.len() - 1
+                    }
+                };
+                let (_, histogram, total) = &mut tests[index];
+                *total += 1;
+                if success {
+                    histogram.record(duration_ms.max(0) as u128);
+                }
+            }
+
+            history.push(HistoryRun {
+                timestamp,
+                endpoint: endpoint.clone(),
+                tests: tests
+                    .into_iter()
+                    .map(|(name, histogram, total)| HistoryTest {
+                        name,
+                        p50_ms: histogram.percentile(50.0),
+                        success_rate: histogram.len() as f64 / total.max(1) as f64 * 100.0,
+                    })
+                    .collect(),
+            });
+        }
+    }
+    Ok(history)
+}
@@ -0,0 +1,69 @@
+use crate::report::Report;
+use anyhow::{Context, Result};
+use tokio::net::UdpSocket;
+
+/// Keep datagrams below a typical MTU so they are not fragmented
+const MAX_PACKET: usize = 1432;
+
+/// Where to send metrics and how to name them
+pub struct StatsdConfig {
+    pub addr: String,
+    pub prefix: String,
+}
+
+/// Tags may not contain the separators used by the DogStatsD format
+fn tag(value: &str) -> String {
+    value.replace(['|', ',', '#', '\n'], "_")
+}
+
+/// DogStatsD lines: a timing per successful iteration and a counter per outcome
+pub fn render(config: &StatsdConfig, report: &Report) -> Vec<String> {
+    let endpoint = tag(&report.endpoint.url);
+    let mut lines = Vec::new();
+
+    for test in &report.tests {
+        let tags = format!("endpoint:{},method:{}", endpoint, tag(&test.name));
+        for sample in &test.samples {
+            let status = if sample.skipped {
+                "skipped"
+            } else if sample.success {
+                "success"
+            } else {
+                "failure"
+            };
+
+            if sample.success {
+                lines.push(format!("{}.latency:{}|ms|#{}", config.prefix, sample.duration_ms, tags));
+            }
+            lines.push(format!("{}.requests:1|c|#{},status:{}", config.prefix, tags, status));
+        }
+    }
+
+    lines
+}
+
+/// Fire all metrics over UDP, packing as many lines per datagram as fit
+pub async fn send(config: &StatsdConfig, report: &Report) -> Result<()> {
+    let socket = UdpSocket::bind("0.0.0.0:0").await.context("Failed to open UDP socket")?;
+    socket
+        .connect(&config.addr)
+        .await
+        .with_context(|| format!("Failed to resolve StatsD address {}", config.addr))?;
+
+    let mut packet = String::new();
+    for line in render(config, report) {
+        if !packet.is_empty() && packet.len() + line.len() + 1 > MAX_PACKET {
+            socket.send(packet.as_bytes()).await.context("Failed to send StatsD metrics")?;
+            packet.clear();
+        }
+        if !packet.is_empty() {
+            packet.push('\n');
+        }
+        packet.push_str(&line);
+    }
+    if !packet.is_empty() {
+        socket.send(packet.as_bytes()).await.context("Failed to send StatsD metrics")?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use crate::report::Report;
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Discovery rule key the method prototypes hang off
+const DISCOVERY_KEY: &str = "solana.rpc.discovery";
+
+/// Trapper server to send to and the host the items belong to
+pub struct ZabbixConfig {
+    pub server: String,
+    pub host: String,
+}
+
+/// Low-level discovery payload listing every tested method as `{#METHOD}`
+pub fn discovery(report: &Report) -> Value {
+    Value::Array(report.tests.iter().map(|t| json!({ "{#METHOD}": t.name })).collect())
+}
+
+/// Per-method latency and availability items, plus run-wide values
+pub fn items(config: &ZabbixConfig, report: &Report) -> Vec<Value> {
+    let clock = report.timestamp.timestamp();
+    let item = |key: String, value: String| json!({ "host": config.host, "key": key, "value": value, "clock": clock });
+
+    let mut items = Vec::new();
+    for test in &report.tests {
+        if let Some(avg) = test.avg_ms {
+            items.push(item(format!("solana.rpc.latency[{}]", test.name), avg.to_string()));
+        }
+        items.push(item(format!("solana.rpc.success[{}]", test.name), format!("{:.1}", test.success_rate)));
+    }
+    items.push(item("solana.rpc.latency.avg".to_string(), report.overall.avg_ms.to_string()));
+    items.push(item("solana.rpc.success.overall".to_string(), format!("{:.1}", report.overall.success_rate)));
+    items
+}
+
+/// One request/response exchange using the Zabbix sender protocol
+async fn send_request(server: &str, request: &Value) -> Result<Value> {
+    let payload = serde_json::to_vec(request)?;
+    let mut packet = Vec::with_capacity(13 + payload.len());
+    packet.extend_from_slice(b"ZBXD\x01");
+    packet.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    packet.extend_from_slice(&payload);
+
+    let mut stream = TcpStream::connect(server)
+        .await
+        .with_context(|| format!("Failed to connect to Zabbix server at {}", server))?;
+    stream.write_all(&packet).await?;
+
+    let mut header = [0u8; 13];
+    stream.read_exact(&mut header).await.context("Zabbix server closed the connection")?;
+    if &header[..4] != b"ZBXD" {
+        bail!("Unexpected response header from Zabbix server");
+    }
+    let len = u64::from_le_bytes(header[5..13].try_into()?) as usize;
+    let mut body = vec![0u8; len];
+    stream.read_exact(&mut body).await?;
+
+    Ok(serde_json::from_slice(&body)?)
+}
+
+async fn send_data(server: &str, data: Vec<Value>) -> Result<()> {
+    let response = send_request(server, &json!({ "request": "sender data", "data": data })).await?;
+    if response["response"] != "success" {
+        bail!("Zabbix server rejected the data: {}", response);
+    }
+
+    // Unknown keys are silently dropped by the server; surface them via the info line
+    let info = response["info"].as_str().unwrap_or_default();
+    if info.contains("failed: 0") {
+        Ok(())
+    } else {
+        bail!("Zabbix server did not accept all items ({})", info)
+    }
+}
+
+/// Send discovery first so new methods get their items created, then the values
+pub async fn send(config: &ZabbixConfig, report: &Report) -> Result<()> {
+    let discovery = json!({
+        "host": config.host,
+        "key": DISCOVERY_KEY,
+        "value": discovery(report).to_string(),
+    });
+    send_data(&config.server, vec![discovery]).await?;
+    send_data(&config.server, items(config, report)).await
+}
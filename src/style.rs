@@ -0,0 +1,58 @@
+use colored::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Banner and rule width, matching the original box drawing
+const WIDTH: usize = 65;
+
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+/// Switch to plain output: no colors, emoji or box drawing
+pub fn set_plain(plain: bool) {
+    PLAIN.store(plain, Ordering::Relaxed);
+    if plain {
+        colored::control::set_override(false);
+    }
+}
+
+pub fn is_plain() -> bool {
+    PLAIN.load(Ordering::Relaxed)
+}
+
+/// Undo a temporary color override without re-enabling colors in plain mode
+pub fn restore_colors() {
+    if !is_plain() {
+        colored::control::unset_override();
+    }
+}
+
+/// The emoji followed by a space, or nothing in plain mode
+pub fn icon(emoji: &str) -> String {
+    if is_plain() {
+        String::new()
+    } else {
+        format!("{} ", emoji)
+    }
+}
+
+/// A boxed title, or an underlined one in plain mode
+pub fn banner(title: &str) -> String {
+    if is_plain() {
+        return format!("{}\n{}", title, "=".repeat(title.len()));
+    }
+
+    let inner = WIDTH - 2;
+    let top = format!("╔{}╗", "═".repeat(inner));
+    let middle = format!("║{:^width$}║", title, width = inner);
+    let bottom = format!("╚{}╝", "═".repeat(inner));
+    format!("{}\n{}\n{}", top.bright_blue(), middle.bright_blue(), bottom.bright_blue())
+}
+
+/// A full-width divider line
+pub fn rule(heavy: bool) -> String {
+    match (is_plain(), heavy) {
+        (true, true) => "=".repeat(WIDTH),
+        (true, false) => "-".repeat(WIDTH),
+        (false, true) => "═".repeat(WIDTH),
+        (false, false) => "─".repeat(WIDTH),
+    }
+}
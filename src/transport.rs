@@ -0,0 +1,166 @@
+use crate::har::{Exchange, HarRecorder};
+use crate::rate_limit::RateLimitTracker;
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::header::HeaderMap;
+use reqwest::{Client, StatusCode};
+use serde_json::Value;
+use solana_client::client_error::Result as ClientResult;
+use solana_client::rpc_request::{RpcError, RpcRequest, RpcResponseErrorData};
+use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Same request timeout the stock solana-client HTTP sender uses
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Status, headers and body of a finished response
+pub struct RawResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+/// The HTTP path every request to the endpoint goes through, so bytes can be
+/// counted and exchanges recorded regardless of which RPC method is called
+pub struct Transport {
+    client: Client,
+    url: String,
+    har: Option<HarRecorder>,
+    rate_limits: RateLimitTracker,
+    bytes_received: AtomicU64,
+    /// Cumulative time until response headers arrived, in microseconds
+    wait_micros: AtomicU64,
+    /// Cumulative time spent reading response bodies, in microseconds
+    download_micros: AtomicU64,
+    request_id: AtomicU64,
+}
+
+/// Running totals of a transport, subtract two snapshots to get one call's share
+#[derive(Clone, Copy, Default)]
+pub struct TransportCounters {
+    pub bytes: u64,
+    pub wait_micros: u64,
+    pub download_micros: u64,
+}
+
+impl std::ops::Sub for TransportCounters {
+    type Output = TransportCounters;
+
+    fn sub(self, earlier: TransportCounters) -> TransportCounters {
+        TransportCounters {
+            bytes: self.bytes - earlier.bytes,
+            wait_micros: self.wait_micros - earlier.wait_micros,
+            download_micros: self.download_micros - earlier.download_micros,
+        }
+    }
+}
+
+impl Transport {
+    pub fn new(url: &str, har: Option<HarRecorder>, rate_limits: RateLimitTracker) -> Self {
+        Transport {
+            client: Client::builder().timeout(REQUEST_TIMEOUT).build().unwrap_or_default(),
+            url: url.to_string(),
+            har,
+            rate_limits,
+            bytes_received: AtomicU64::new(0),
+            wait_micros: AtomicU64::new(0),
+            download_micros: AtomicU64::new(0),
+            request_id: AtomicU64::new(0),
+        }
+    }
+
+    /// Send these headers with every request, e.g. a provider's API key
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        if !headers.is_empty() {
+            self.client =
+                Client::builder().timeout(REQUEST_TIMEOUT).default_headers(headers).build().unwrap_or_default();
+        }
+        self
+    }
+
+    /// Bytes and time received so far over this transport
+    pub fn counters(&self) -> TransportCounters {
+        TransportCounters {
+            bytes: self.bytes_received.load(Ordering::Relaxed),
+            wait_micros: self.wait_micros.load(Ordering::Relaxed),
+            download_micros: self.download_micros.load(Ordering::Relaxed),
+        }
+    }
+
+    /// POST a JSON body to the endpoint
+    pub async fn post_json(&self, body: &Value) -> reqwest::Result<RawResponse> {
+        let request = self.client.post(&self.url).json(body).build()?;
+        let request_headers = request.headers().clone();
+
+        let started = Utc::now();
+        let start = Instant::now();
+        let response = self.client.execute(request).await?;
+        let wait = start.elapsed();
+
+        let status = response.status();
+        let version = format!("{:?}", response.version());
+        let response_headers = response.headers().clone();
+        self.rate_limits.observe(status, &response_headers);
+        let text = response.text().await?;
+        let total = start.elapsed();
+
+        self.bytes_received.fetch_add(text.len() as u64, Ordering::Relaxed);
+        self.wait_micros.fetch_add(wait.as_micros() as u64, Ordering::Relaxed);
+        self.download_micros.fetch_add((total - wait).as_micros() as u64, Ordering::Relaxed);
+
+        if let Some(har) = &self.har {
+            har.record(&Exchange {
+                started,
+                url: &self.url,
+                version,
+                request_headers: &request_headers,
+                request_body: &body.to_string(),
+                status,
+                response_headers: &response_headers,
+                response_body: &text,
+                wait,
+                total,
+            });
+        }
+
+        Ok(RawResponse { status, headers: response_headers, body: text })
+    }
+}
+
+/// JSON-RPC sender for `RpcClient` on top of a shared [`Transport`]
+pub struct TransportSender(pub Arc<Transport>);
+
+#[async_trait]
+impl RpcSender for TransportSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        let id = self.0.request_id.fetch_add(1, Ordering::Relaxed);
+        let body = request.build_request_json(id, params);
+        let response = self.0.post_json(&body).await?;
+
+        if !response.status.is_success() {
+            return Err(RpcError::RpcRequestError(format!("HTTP {}: {}", response.status, response.body)).into());
+        }
+
+        let mut json: Value = serde_json::from_str(&response.body)?;
+        if let Some(error) = json.get("error").filter(|e| e.is_object()) {
+            return Err(RpcError::RpcResponseError {
+                code: error["code"].as_i64().unwrap_or_default(),
+                message: error["message"].as_str().unwrap_or_default().to_string(),
+                data: RpcResponseErrorData::Empty,
+            }
+            .into());
+        }
+
+        Ok(json["result"].take())
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        self.0.url.clone()
+    }
+}
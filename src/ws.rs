@@ -0,0 +1,720 @@
+use crate::endpoint::Endpoint;
+use crate::rpc_tests::TestResult;
+use anyhow::{bail, Context, Result};
+use futures::{SinkExt, StreamExt};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use solana_client::rpc_config::RpcBlockConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_transaction_status::{TransactionDetails, UiTransactionEncoding};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// How long to wait for any single notification before giving up
+const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The PubSub URL of an HTTP endpoint, following the solana CLI convention
+/// that a local validator listens one port above its RPC port
+pub fn derive_url(http_url: &str) -> String {
+    let ws = if let Some(rest) = http_url.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = http_url.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        http_url.to_string()
+    };
+
+    match reqwest::Url::parse(&ws) {
+        Ok(mut url) if url.port().is_some() => {
+            let port = url.port().unwrap_or_default();
+            let _ = url.set_port(Some(port + 1));
+            url.to_string()
+        }
+        _ => ws,
+    }
+}
+
+/// How often a ping is sent while waiting for notifications
+const PING_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long the server gets to answer a close frame
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Name under which control-frame round trips are reported, apart from notification latency
+const PING_TEST: &str = "WebSocket ping/pong";
+
+/// One PubSub subscription on its own connection
+pub struct Subscription {
+    socket: Socket,
+    /// Notification payload bytes received so far
+    pub bytes: u64,
+    /// Payload and send time of the ping awaiting its pong
+    pending_ping: Option<(Vec<u8>, Instant)>,
+    last_ping: Option<Instant>,
+    ping_rtts: Vec<Duration>,
+}
+
+impl Subscription {
+    /// Connect, subscribe and wait until the node confirms the subscription
+    pub async fn open(url: &str, method: &str, params: Value) -> Result<Subscription> {
+        let (socket, _) = connect_async(url).await.with_context(|| format!("Failed to connect to {}", url))?;
+        let mut subscription =
+            Subscription { socket, bytes: 0, pending_ping: None, last_ping: None, ping_rtts: Vec::new() };
+
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        subscription.socket.send(Message::Text(request.to_string())).await?;
+
+        loop {
+            let (message, _) = timeout(NOTIFICATION_TIMEOUT, subscription.next_json())
+                .await
+                .context("No subscription confirmation")??;
+            if message["id"] != 1 {
+                continue;
+            }
+            if message["error"].is_object() {
+                bail!(
+                    "RPC response error {}: {}",
+                    message["error"]["code"],
+                    message["error"]["message"].as_str().unwrap_or_default()
+                );
+            }
+            return Ok(subscription);
+        }
+    }
+
+    /// Next JSON message and its size, timing pongs and skipping other control frames
+    async fn next_json(&mut self) -> Result<(Value, usize)> {
+        while let Some(message) = self.socket.next().await {
+            match message? {
+                Message::Text(text) => return Ok((serde_json::from_str(&text)?, text.len())),
+                Message::Binary(bytes) => return Ok((serde_json::from_slice(&bytes)?, bytes.len())),
+                Message::Close(frame) => bail!("Connection closed by server: {:?}", frame),
+                Message::Pong(payload) => {
+                    if let Some((expected, sent)) = self.pending_ping.take() {
+                        if payload == expected {
+                            self.ping_rtts.push(sent.elapsed());
+                        } else {
+                            self.pending_ping = Some((expected, sent));
+                        }
+                    }
+                }
+                _ => continue,
+            }
+        }
+        bail!("Connection closed")
+    }
+
+    /// Send a ping if one is due, so transport health is sampled throughout the test
+    async fn maybe_ping(&mut self) -> Result<()> {
+        if self.pending_ping.is_some() || self.last_ping.is_some_and(|at| at.elapsed() < PING_INTERVAL) {
+            return Ok(());
+        }
+
+        let payload = rand::random::<u64>().to_be_bytes().to_vec();
+        self.socket.send(Message::Ping(payload.clone())).await?;
+        let now = Instant::now();
+        self.pending_ping = Some((payload, now));
+        self.last_ping = Some(now);
+        Ok(())
+    }
+
+    /// The `result` of the next notification, or an error after `NOTIFICATION_TIMEOUT`
+    pub async fn next(&mut self) -> Result<Value> {
+        timeout(NOTIFICATION_TIMEOUT, async {
+            self.maybe_ping().await?;
+            loop {
+                let (mut message, size) = self.next_json().await?;
+                if message["method"].as_str().is_some_and(|m| m.ends_with("Notification")) {
+                    self.bytes += size as u64;
+                    return Ok(message["params"]["result"].take());
+                }
+            }
+        })
+        .await
+        .with_context(|| format!("No notification within {}s", NOTIFICATION_TIMEOUT.as_secs()))?
+    }
+
+    /// Start a close handshake and wait for the server's close frame, as RFC 6455
+    /// requires; a server that just drops the connection fails this
+    pub async fn close(mut self) -> Result<Duration> {
+        let start = Instant::now();
+        self.socket.close(None).await?;
+
+        timeout(CLOSE_TIMEOUT, async {
+            while let Some(message) = self.socket.next().await {
+                match message {
+                    Ok(Message::Close(_)) => return Ok(start.elapsed()),
+                    Ok(_) => continue,
+                    Err(e) => bail!("Connection dropped without a close frame: {}", e),
+                }
+            }
+            bail!("Connection dropped without a close frame")
+        })
+        .await
+        .with_context(|| format!("No close frame within {}s", CLOSE_TIMEOUT.as_secs()))?
+    }
+
+    /// Ping round trips measured on this connection so far
+    pub fn ping_results(&self) -> impl Iterator<Item = TestResult> + '_ {
+        self.ping_rtts.iter().map(|rtt| TestResult::success(PING_TEST, *rtt))
+    }
+}
+
+/// Time to the first slot notification, then the gap before each of the next `slots`
+pub async fn slot_subscribe(url: &str, slots: usize) -> Vec<TestResult> {
+    const FIRST: &str = "slotSubscribe (first notification)";
+    const INTERVAL: &str = "slotSubscribe (interval)";
+
+    let start = Instant::now();
+    let mut subscription = match Subscription::open(url, "slotSubscribe", json!([])).await {
+        Ok(subscription) => subscription,
+        Err(e) => return vec![TestResult::failure(FIRST, start.elapsed(), format!("{:#}", e))],
+    };
+
+    let mut results = vec![TestResult::from_result(FIRST, start.elapsed(), subscription.next().await)];
+    if !results[0].success {
+        return results;
+    }
+
+    // The spread of these gaps is the arrival jitter
+    let mut last = Instant::now();
+    for _ in 0..slots {
+        let notification = subscription.next().await;
+        results.push(TestResult::from_result(INTERVAL, last.elapsed(), notification.map_err(|e| format!("{:#}", e))));
+        last = Instant::now();
+        if !results.last().is_some_and(|r| r.success) {
+            break;
+        }
+    }
+
+    results.extend(subscription.ping_results());
+    results
+}
+
+/// Slots kept around to match account notifications against
+const SLOT_HISTORY: u64 = 150;
+
+/// Notifications of `method` and the slot each one belongs to, timed against a
+/// parallel slot subscription: `{label} (first notification)`, `{label} (interval)`
+/// and `{label} (delay after slot)`, the time since that slot was announced
+async fn watch(
+    url: &str,
+    method: &str,
+    params: Value,
+    notifications: usize,
+    label: &str,
+    slot_of: fn(&Value) -> Option<u64>,
+) -> (Vec<TestResult>, Vec<u64>) {
+    let first = format!("{} (first notification)", label);
+    let interval = format!("{} (interval)", label);
+    let delay = format!("{} (delay after slot)", label);
+
+    let start = Instant::now();
+    let (mut slots, mut updates) = match tokio::try_join!(
+        Subscription::open(url, "slotSubscribe", json!([])),
+        Subscription::open(url, method, params),
+    ) {
+        Ok(pair) => pair,
+        Err(e) => return (vec![TestResult::failure(&first, start.elapsed(), format!("{:#}", e))], Vec::new()),
+    };
+
+    let mut results = Vec::new();
+    let mut seen_slots = Vec::new();
+    let mut announced: BTreeMap<u64, Instant> = BTreeMap::new();
+    let mut last: Option<Instant> = None;
+
+    // The first notification plus `notifications` intervals
+    while seen_slots.len() <= notifications {
+        tokio::select! {
+            slot = slots.next() => match slot {
+                Ok(slot) => {
+                    if let Some(slot) = slot["slot"].as_u64() {
+                        announced.entry(slot).or_insert_with(Instant::now);
+                        announced.retain(|s, _| s + SLOT_HISTORY > slot);
+                    }
+                }
+                Err(e) => {
+                    results.push(TestResult::failure(&delay, Duration::ZERO, format!("{:#}", e)));
+                    break;
+                }
+            },
+            update = updates.next() => {
+                let now = Instant::now();
+                let name = if last.is_some() { &interval } else { &first };
+                let since = last.unwrap_or(start);
+                match update {
+                    Ok(update) => {
+                        results.push(TestResult::success(name, now - since));
+                        let slot = slot_of(&update);
+                        if let Some(announced_at) = slot.and_then(|slot| announced.get(&slot)) {
+                            results.push(TestResult::success(&delay, now - *announced_at));
+                        }
+                        seen_slots.push(slot.unwrap_or_default());
+                        last = Some(now);
+                    }
+                    Err(e) => {
+                        results.push(TestResult::failure(name, now - since, format!("{:#}", e)));
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    results.extend(slots.ping_results().chain(updates.ping_results()));
+    (results, seen_slots)
+}
+
+/// How often an account's notifications arrive and how long after their slot
+pub async fn account_subscribe(url: &str, account: &str, notifications: usize) -> Vec<TestResult> {
+    let params = json!([account, { "encoding": "base64", "commitment": "processed" }]);
+    let (results, _) = watch(url, "accountSubscribe", params, notifications, "accountSubscribe", |update| {
+        update["context"]["slot"].as_u64()
+    })
+    .await;
+    results
+}
+
+/// Full-block notifications, followed by fetching the same blocks over HTTP for comparison.
+/// Many nodes only offer blockSubscribe behind a flag, in which case the first result
+/// carries the node's refusal
+pub async fn block_subscribe(url: &str, endpoint: &Endpoint, notifications: usize) -> Vec<TestResult> {
+    let config = json!({
+        "commitment": "confirmed",
+        "encoding": "base64",
+        "transactionDetails": "full",
+        "maxSupportedTransactionVersion": 0,
+        "showRewards": false,
+    });
+    let (mut results, slots) = watch(url, "blockSubscribe", json!(["all", config]), notifications, "blockSubscribe", |update| {
+        update["value"]["slot"].as_u64()
+    })
+    .await;
+
+    let client = endpoint.rpc_client();
+    let block_config = RpcBlockConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        transaction_details: Some(TransactionDetails::Full),
+        rewards: Some(false),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    for slot in slots.into_iter().filter(|slot| *slot > 0) {
+        let start = Instant::now();
+        let result = client.get_block_with_config(slot, block_config).await;
+        results.push(TestResult::from_result("getBlock (same blocks over HTTP)", start.elapsed(), result));
+    }
+
+    results
+}
+
+/// Notification rate of a subscription over a fixed window
+#[derive(Serialize, Clone, Debug)]
+pub struct Throughput {
+    pub name: String,
+    pub notifications: u64,
+    pub window_ms: u128,
+    pub per_sec: f64,
+    pub mbps: f64,
+    /// Slots inside the window that delivered no event, for sources that cover every slot
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub missing_slots: Option<u64>,
+    /// Longest stretch without any event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longest_silence_ms: Option<u128>,
+}
+
+impl Throughput {
+    pub fn new(name: &str, notifications: u64, bytes: u64, window: Duration) -> Self {
+        let secs = window.as_secs_f64().max(f64::EPSILON);
+        Throughput {
+            name: name.to_string(),
+            notifications,
+            window_ms: window.as_millis(),
+            per_sec: notifications as f64 / secs,
+            mbps: bytes as f64 / 1_000_000.0 / secs,
+            missing_slots: None,
+            longest_silence_ms: None,
+        }
+    }
+}
+
+/// Count a program's account notifications for `window`, after timing the first one
+pub async fn program_subscribe(url: &str, program: &str, window: Duration) -> (Vec<TestResult>, Option<Throughput>) {
+    const FIRST: &str = "programSubscribe (first notification)";
+
+    let start = Instant::now();
+    let params = json!([program, { "encoding": "base64", "commitment": "processed" }]);
+    let mut subscription = match Subscription::open(url, "programSubscribe", params).await {
+        Ok(subscription) => subscription,
+        Err(e) => return (vec![TestResult::failure(FIRST, start.elapsed(), format!("{:#}", e))], None),
+    };
+
+    let first = subscription.next().await;
+    let mut results = vec![TestResult::from_result(FIRST, start.elapsed(), first.as_ref().map_err(|e| format!("{:#}", e)))];
+    if first.is_err() {
+        return (results, None);
+    }
+
+    // Intervals are too short to time one by one on a busy program, so count instead
+    let counting = Instant::now();
+    let bytes_before = subscription.bytes;
+    let mut notifications = 0;
+    while counting.elapsed() < window {
+        match timeout(window.saturating_sub(counting.elapsed()), subscription.next()).await {
+            Ok(Ok(_)) => notifications += 1,
+            Ok(Err(_)) | Err(_) => break,
+        }
+    }
+
+    let throughput = Throughput::new("programSubscribe", notifications, subscription.bytes - bytes_before, counting.elapsed());
+    results.extend(subscription.ping_results());
+    (results, Some(throughput))
+}
+
+/// Times a connection is dropped and re-established in the reconnection test
+const RECONNECT_ROUNDS: usize = 3;
+
+/// Close a live slot subscription and time how long a client needs to get back to
+/// receiving notifications, checking on the way that the server completes the close handshake
+pub async fn reconnect(url: &str) -> Vec<TestResult> {
+    const CLOSE: &str = "WebSocket close handshake";
+    const RECONNECT: &str = "WebSocket reconnect + resubscribe";
+
+    let mut results = Vec::new();
+    let start = Instant::now();
+    let mut subscription = match Subscription::open(url, "slotSubscribe", json!([])).await {
+        Ok(subscription) => subscription,
+        Err(e) => return vec![TestResult::failure(RECONNECT, start.elapsed(), format!("{:#}", e))],
+    };
+
+    for _ in 0..RECONNECT_ROUNDS {
+        // Only drop a connection that is known to be delivering
+        if let Err(e) = subscription.next().await {
+            results.push(TestResult::failure(RECONNECT, Duration::ZERO, format!("{:#}", e)));
+            break;
+        }
+
+        let closing = Instant::now();
+        let closed = subscription.close().await;
+        results.push(TestResult::from_result(CLOSE, closing.elapsed(), closed.map_err(|e| format!("{:#}", e))));
+
+        let start = Instant::now();
+        let reopened = match Subscription::open(url, "slotSubscribe", json!([])).await {
+            Ok(mut reopened) => reopened.next().await.map(|_| reopened),
+            Err(e) => Err(e),
+        };
+        match reopened {
+            Ok(reopened) => {
+                results.push(TestResult::success(RECONNECT, start.elapsed()));
+                subscription = reopened;
+            }
+            Err(e) => {
+                results.push(TestResult::failure(RECONNECT, start.elapsed(), format!("{:#}", e)));
+                return results;
+            }
+        }
+    }
+
+    results
+}
+
+/// Outcome of opening ever more subscriptions on one connection
+#[derive(Serialize, Clone, Debug)]
+pub struct SubscriptionLimit {
+    /// Largest count that was fully accepted and still delivered in time
+    pub limit: usize,
+    /// Count at which the endpoint refused or degraded, None if `max` was reached
+    pub failed_at: Option<usize>,
+    pub reason: Option<String>,
+    /// Average time to confirm one subscription at `limit`
+    pub overhead_ms: f64,
+}
+
+/// A slot fanned out to every subscription later than this counts as degraded
+const MAX_FANOUT_SPREAD: Duration = Duration::from_secs(1);
+
+/// Subscribe `count` times on one connection, then wait until one slot reaches all
+/// of them; returns the time per confirmation
+async fn open_many(url: &str, count: usize) -> Result<Duration> {
+    let (mut socket, _) = connect_async(url).await.with_context(|| format!("Failed to connect to {}", url))?;
+
+    let start = Instant::now();
+    for id in 0..count {
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": "slotSubscribe", "params": [] });
+        socket.send(Message::Text(request.to_string())).await?;
+    }
+
+    let mut confirmed = 0;
+    let mut per_slot: BTreeMap<u64, (usize, Instant)> = BTreeMap::new();
+    let mut overhead = None;
+    timeout(NOTIFICATION_TIMEOUT, async {
+        while let Some(message) = socket.next().await {
+            let text = match message? {
+                Message::Text(text) => text,
+                Message::Close(frame) => bail!("Connection closed by server: {:?}", frame),
+                _ => continue,
+            };
+            let message: Value = serde_json::from_str(&text)?;
+
+            if message["error"].is_object() {
+                bail!("Subscription refused: {}", message["error"]["message"].as_str().unwrap_or_default());
+            }
+            if message["id"].is_u64() {
+                confirmed += 1;
+                if confirmed == count {
+                    overhead = Some(start.elapsed() / count as u32);
+                }
+                continue;
+            }
+
+            if let Some(slot) = message["params"]["result"]["slot"].as_u64() {
+                let (seen, first) = per_slot.entry(slot).or_insert((0, Instant::now()));
+                *seen += 1;
+                // Only slots announced after every subscription was in place see all of them
+                if *seen == count && overhead.is_some() {
+                    if first.elapsed() > MAX_FANOUT_SPREAD {
+                        bail!("Slot {} took {:?} to reach all {} subscriptions", slot, first.elapsed(), count);
+                    }
+                    return Ok(());
+                }
+            }
+        }
+        bail!("Connection closed")
+    })
+    .await
+    .with_context(|| format!("{} subscriptions not served within {}s", count, NOTIFICATION_TIMEOUT.as_secs()))??;
+
+    Ok(overhead.unwrap_or_default())
+}
+
+/// Double the number of subscriptions on a connection until the endpoint refuses
+/// or falls behind, or `max` is reached
+pub async fn subscription_limit(url: &str, max: usize) -> SubscriptionLimit {
+    let mut limit = SubscriptionLimit { limit: 0, failed_at: None, reason: None, overhead_ms: 0.0 };
+
+    let mut count = 1;
+    while count <= max {
+        match open_many(url, count).await {
+            Ok(overhead) => {
+                limit.limit = count;
+                limit.overhead_ms = overhead.as_secs_f64() * 1000.0;
+            }
+            Err(e) => {
+                limit.failed_at = Some(count);
+                limit.reason = Some(format!("{:#}", e));
+                break;
+            }
+        }
+        if count == max {
+            break;
+        }
+        count = (count * 2).min(max);
+    }
+
+    limit
+}
+
+/// Take every transaction log on the cluster for `duration` and report how many
+/// events arrived and where the stream had holes
+pub async fn logs_stress(url: &str, duration: Duration) -> (Vec<TestResult>, Option<Throughput>) {
+    const NAME: &str = "logsSubscribe (stress)";
+
+    let start = Instant::now();
+    let params = json!(["all", { "commitment": "processed" }]);
+    let mut subscription = match Subscription::open(url, "logsSubscribe", params).await {
+        Ok(subscription) => subscription,
+        Err(e) => return (vec![TestResult::failure(NAME, start.elapsed(), format!("{:#}", e))], None),
+    };
+
+    let counting = Instant::now();
+    let mut results = Vec::new();
+    let mut events = 0;
+    let mut slots = std::collections::BTreeSet::new();
+    let mut last_event = counting;
+    let mut longest_silence = Duration::ZERO;
+    while counting.elapsed() < duration {
+        match timeout(duration.saturating_sub(counting.elapsed()), subscription.next()).await {
+            Ok(Ok(event)) => {
+                events += 1;
+                longest_silence = longest_silence.max(last_event.elapsed());
+                last_event = Instant::now();
+                if let Some(slot) = event["context"]["slot"].as_u64() {
+                    slots.insert(slot);
+                }
+            }
+            Ok(Err(e)) => {
+                results.push(TestResult::failure(NAME, counting.elapsed(), format!("{:#}", e)));
+                break;
+            }
+            Err(_) => break,
+        }
+    }
+    let window = counting.elapsed();
+    longest_silence = longest_silence.max(last_event.elapsed());
+    if results.is_empty() {
+        results.push(TestResult::success(NAME, window));
+    }
+
+    // Practically every mainnet block carries non-vote transactions, so a slot
+    // without logs was either skipped by its leader or lost on the way
+    let missing_slots = match (slots.first(), slots.last()) {
+        (Some(first), Some(last)) => Some(last - first + 1 - slots.len() as u64),
+        _ => None,
+    };
+
+    let mut throughput = Throughput::new("logsSubscribe (all)", events, subscription.bytes, window);
+    throughput.missing_slots = missing_slots;
+    throughput.longest_silence_ms = Some(longest_silence.as_millis());
+    results.extend(subscription.ping_results());
+    (results, Some(throughput))
+}
+
+/// Subscriptions a stream monitor puts on one connection, below common per-connection caps
+const SUBSCRIPTIONS_PER_CONNECTION: usize = 50;
+
+/// Delivery of slot notifications to many subscriptions over one phase of a run
+#[derive(Serialize, Clone, Debug)]
+pub struct StreamStats {
+    pub window_ms: u128,
+    pub slots: usize,
+    pub notifications: usize,
+    /// Notifications that never arrived, counting every subscription for every slot
+    pub missed: usize,
+    /// How much later than the first copy the other subscriptions got each slot
+    pub p50_lag_ms: Option<u128>,
+    pub p99_lag_ms: Option<u128>,
+    pub max_lag_ms: Option<u128>,
+}
+
+/// Streaming latency on its own and while the endpoint serves HTTP load
+#[derive(Serialize, Clone, Debug)]
+pub struct StreamingUnderLoad {
+    pub subscriptions: usize,
+    pub connections: usize,
+    pub baseline: StreamStats,
+    pub under_load: StreamStats,
+    /// Connections that closed or failed before the end of the run
+    pub dropped_connections: usize,
+}
+
+/// Slot and arrival time of every notification a connection received, and whether it
+/// closed or failed before it was stopped
+type Arrivals = (Vec<(u64, Instant)>, bool);
+
+/// Hundreds of slot subscriptions recording when every notification arrived, so their
+/// delivery can be compared before and during a load run
+pub struct StreamMonitor {
+    subscriptions: usize,
+    connections: usize,
+    stop: tokio::sync::watch::Sender<bool>,
+    readers: Vec<tokio::task::JoinHandle<Arrivals>>,
+}
+
+impl StreamMonitor {
+    /// Open `subscriptions` slot subscriptions spread over as many connections as needed
+    pub async fn start(url: &str, subscriptions: usize) -> Result<StreamMonitor> {
+        let (stop, stopped) = tokio::sync::watch::channel(false);
+        let mut readers = Vec::new();
+        let mut remaining = subscriptions;
+        while remaining > 0 {
+            let count = remaining.min(SUBSCRIPTIONS_PER_CONNECTION);
+            remaining -= count;
+
+            let (mut socket, _) = connect_async(url).await.with_context(|| format!("Failed to connect to {}", url))?;
+            for id in 0..count {
+                let request = json!({ "jsonrpc": "2.0", "id": id, "method": "slotSubscribe", "params": [] });
+                socket.send(Message::Text(request.to_string())).await?;
+            }
+
+            let mut stopped = stopped.clone();
+            readers.push(tokio::spawn(async move {
+                let mut arrivals = Vec::new();
+                loop {
+                    let message = tokio::select! {
+                        _ = stopped.changed() => return (arrivals, false),
+                        message = socket.next() => message,
+                    };
+                    let text = match message {
+                        Some(Ok(Message::Text(text))) => text,
+                        Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return (arrivals, true),
+                        Some(Ok(_)) => continue,
+                    };
+                    let received = Instant::now();
+                    if let Ok(message) = serde_json::from_str::<Value>(&text) {
+                        if let Some(slot) = message["params"]["result"]["slot"].as_u64() {
+                            arrivals.push((slot, received));
+                        }
+                    }
+                }
+            }));
+        }
+
+        Ok(StreamMonitor { subscriptions, connections: readers.len(), stop, readers })
+    }
+
+    /// Close the subscriptions and compare delivery before `load_start` with delivery between
+    /// it and `load_end`
+    pub async fn finish(self, started: Instant, load_start: Instant, load_end: Instant) -> StreamingUnderLoad {
+        // Let the last slots of the run reach every subscription before counting what was missed
+        tokio::time::sleep(MAX_FANOUT_SPREAD).await;
+        let _ = self.stop.send(true);
+        let mut arrivals = Vec::new();
+        let mut dropped_connections = 0;
+        for reader in futures::future::join_all(self.readers).await.into_iter().flatten() {
+            let (received, dropped) = reader;
+            arrivals.extend(received);
+            if dropped {
+                dropped_connections += 1;
+            }
+        }
+
+        StreamingUnderLoad {
+            subscriptions: self.subscriptions,
+            connections: self.connections,
+            baseline: stream_stats(&arrivals, self.subscriptions, started, load_start),
+            under_load: stream_stats(&arrivals, self.subscriptions, load_start, load_end),
+            dropped_connections,
+        }
+    }
+}
+
+/// Delivery of the slots first announced within `from..to`
+fn stream_stats(arrivals: &[(u64, Instant)], subscriptions: usize, from: Instant, to: Instant) -> StreamStats {
+    let mut per_slot: BTreeMap<u64, Vec<Instant>> = BTreeMap::new();
+    for (slot, received) in arrivals {
+        per_slot.entry(*slot).or_default().push(*received);
+    }
+
+    let mut lags = Vec::new();
+    let mut slots = 0;
+    let mut notifications = 0;
+    let mut missed = 0;
+    for received in per_slot.values() {
+        let first = *received.iter().min().expect("every slot has an arrival");
+        if first < from || first >= to {
+            continue;
+        }
+        slots += 1;
+        notifications += received.len();
+        missed += subscriptions.saturating_sub(received.len());
+        lags.extend(received.iter().map(|at| at.duration_since(first).as_millis()));
+    }
+
+    let histogram: crate::report::histogram::LatencyHistogram = lags.into_iter().collect();
+    StreamStats {
+        window_ms: to.duration_since(from).as_millis(),
+        slots,
+        notifications,
+        missed,
+        p50_lag_ms: histogram.percentile(50.0),
+        p99_lag_ms: histogram.percentile(99.0),
+        max_lag_ms: histogram.max(),
+    }
+}